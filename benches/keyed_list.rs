@@ -0,0 +1,78 @@
+//! Compares the cost of re-rendering a large keyed list after it has been shuffled, using
+//! `For::keyed()` (longest common subsequence) and `For::hashed()` (hash map).
+
+use bevy::{a11y::Focus, input::mouse::MouseWheel, prelude::*};
+use bevy_mod_picking::focus::{HoverMap, PreviousHoverMap};
+use bevy_quill::prelude::*;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+#[derive(Resource, Default)]
+struct Items(Vec<u32>);
+
+#[derive(Resource, Default, Clone, Copy, PartialEq)]
+enum Diff {
+    #[default]
+    Keyed,
+    Hashed,
+}
+
+fn list(cx: Cx) -> impl View {
+    let items = cx.use_resource::<Items>().0.clone();
+    let diff = *cx.use_resource::<Diff>();
+    Element::new().children(If::new(
+        diff == Diff::Keyed,
+        For::keyed(&items, |item| *item, |item| format!("{}", item)),
+        For::hashed(&items, |item| *item, |item| format!("{}", item)),
+    ))
+}
+
+/// Deterministic Fisher-Yates shuffle, so that both diff strategies see the same input.
+fn shuffle(items: &mut [u32]) {
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    for i in (1..items.len()).rev() {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        items.swap(i, (seed % (i as u64 + 1)) as usize);
+    }
+}
+
+fn setup(diff: Diff, len: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default(), QuillPlugin))
+        .init_resource::<HoverMap>()
+        .init_resource::<PreviousHoverMap>()
+        .init_resource::<Focus>()
+        .add_event::<MouseWheel>()
+        .insert_resource(diff)
+        .insert_resource(Items((0..len).collect()));
+    app.world.spawn(ViewHandle::new(list, ()));
+    app.update();
+    app
+}
+
+fn bench_shuffle(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shuffle");
+    group.sample_size(20);
+    for len in [100, 1000] {
+        let mut shuffled: Vec<u32> = (0..len).collect();
+        shuffle(&mut shuffled);
+        for (name, diff) in [("keyed", Diff::Keyed), ("hashed", Diff::Hashed)] {
+            group.bench_with_input(BenchmarkId::new(name, len), &shuffled, |b, shuffled| {
+                b.iter_batched(
+                    || setup(diff, len),
+                    |mut app| {
+                        app.world.resource_mut::<Items>().0 = shuffled.clone();
+                        app.update();
+                        app
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_shuffle);
+criterion_main!(benches);
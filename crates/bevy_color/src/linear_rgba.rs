@@ -1,5 +1,5 @@
 use crate::{oklaba::Oklaba, to_css_string::ToCssString, Hsla, Mix, SRgba};
-use bevy::render::color::SrgbColorSpace;
+use bevy::render::color::{Color, SrgbColorSpace};
 use bevy_reflect::{Reflect, ReflectDeserialize, ReflectSerialize};
 use serde::{Deserialize, Serialize};
 
@@ -77,6 +77,19 @@ impl Mix for LinearRgba {
     }
 }
 
+impl From<Color> for LinearRgba {
+    fn from(value: Color) -> Self {
+        let [red, green, blue, alpha] = value.as_linear_rgba_f32();
+        Self::new(red, green, blue, alpha)
+    }
+}
+
+impl From<LinearRgba> for Color {
+    fn from(value: LinearRgba) -> Self {
+        Color::rgba_linear(value.red, value.green, value.blue, value.alpha)
+    }
+}
+
 impl From<SRgba> for LinearRgba {
     #[inline]
     fn from(value: SRgba) -> Self {
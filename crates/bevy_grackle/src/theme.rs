@@ -176,7 +176,6 @@ static STYLE_LT_SLIDER_TRACK_ACTIVE: StyleHandle =
 #[dynamic]
 static STYLE_LT_SLIDER_THUMB: StyleHandle = StyleHandle::build(|ss| {
     ss.background_color(COLOR_GRAY_200)
-        .background_image(Some(AssetPath::from("grackle://icons/disc.png")))
         .selector(":hover > &,.drag > &", |ss| {
             ss.background_color(COLOR_GRAY_50)
         })
@@ -193,7 +192,6 @@ static STYLE_DK_SLIDER_TRACK_ACTIVE: StyleHandle =
 #[dynamic]
 static STYLE_DK_SLIDER_THUMB: StyleHandle = StyleHandle::build(|ss| {
     ss.background_color("#777")
-        .background_image(Some(AssetPath::from("grackle://icons/disc.png")))
         .selector(":hover > &,.drag > &", |ss| ss.background_color("#aaa"))
 });
 
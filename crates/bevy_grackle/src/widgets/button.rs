@@ -19,6 +19,7 @@ static STYLE_BUTTON: StyleHandle = StyleHandle::build(|ss| {
         .selector(".size-md", |ss| ss.min_height(Size::Md.height()))
         .selector(".size-lg", |ss| ss.min_height(Size::Lg.height()))
         .selector(".size-xl", |ss| ss.min_height(Size::Xl.height()))
+        .selector(".disabled", |ss| ss.cursor(Cursor::NotAllowed))
 });
 
 /// The variant determines the button's color scheme
@@ -35,8 +36,9 @@ pub enum ButtonVariant {
     Danger,
 }
 
-#[derive(PartialEq, Clone, Default)]
-pub struct ButtonProps<V: View + Clone, S: StyleTuple = ()> {
+/// Properties for the [`button`] widget.
+#[derive(Props)]
+pub struct ButtonProps<V: View + Clone = (), S: StyleTuple = ()> {
     pub id: &'static str,
     pub children: V,
     pub variant: ButtonVariant,
@@ -45,56 +47,8 @@ pub struct ButtonProps<V: View + Clone, S: StyleTuple = ()> {
     pub disabled: bool,
 }
 
-impl ButtonProps<(), ()> {
-    pub fn new(id: &'static str) -> Self {
-        Self {
-            id,
-            children: (),
-            style: (),
-            ..Default::default()
-        }
-    }
-}
-
-impl<V: View + Clone, S: StyleTuple> ButtonProps<V, S> {
-    pub fn children<V2: View + Clone>(self, children: V2) -> ButtonProps<V2, S> {
-        ButtonProps {
-            children,
-            id: self.id,
-            variant: self.variant,
-            size: self.size,
-            style: self.style,
-            disabled: self.disabled,
-        }
-    }
-
-    pub fn style<S2: StyleTuple>(self, style: S2) -> ButtonProps<V, S2> {
-        ButtonProps {
-            children: self.children,
-            id: self.id,
-            variant: self.variant,
-            size: self.size,
-            style,
-            disabled: self.disabled,
-        }
-    }
-
-    pub fn variant(mut self, variant: ButtonVariant) -> Self {
-        self.variant = variant;
-        self
-    }
-
-    pub fn size(mut self, size: Size) -> Self {
-        self.size = size;
-        self
-    }
-
-    pub fn disabled(mut self, disabled: bool) -> Self {
-        self.disabled = disabled;
-        self
-    }
-}
-
+/// A push button, invoked as `button().id("ok").children("OK")`.
+#[presenter]
 pub fn button<V: View + Clone + PartialEq + 'static, ST: StyleTuple + PartialEq + 'static>(
     cx: Cx<ButtonProps<V, ST>>,
 ) -> impl View {
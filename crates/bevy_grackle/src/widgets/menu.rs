@@ -65,8 +65,9 @@ static STYLE_MENU_ITEM: StyleHandle = StyleHandle::build(|ss| {
 static STYLE_MENU_DIVIDER: StyleHandle =
     StyleHandle::build(|ss| ss.background_color("#000").height(1).margin((0, 2)));
 
-#[derive(Clone, PartialEq, Default)]
-pub struct MenuButtonProps<V: View + Clone, VI: View + Clone, S: StyleTuple = ()> {
+/// Properties for the [`menu_button`] widget.
+#[derive(Props)]
+pub struct MenuButtonProps<V: View + Clone = (), VI: View + Clone = (), S: StyleTuple = ()> {
     pub children: V,
     pub items: VI,
     pub size: Size,
@@ -75,19 +76,9 @@ pub struct MenuButtonProps<V: View + Clone, VI: View + Clone, S: StyleTuple = ()
     pub indent: bool,
 }
 
-impl MenuButtonProps<(), (), ()> {
-    pub fn new() -> Self {
-        Self {
-            children: (),
-            items: (),
-            style: (),
-            ..Default::default()
-        }
-    }
-}
-
-#[derive(PartialEq, Clone, Default)]
-pub struct MenuItemProps<V: View + Clone> {
+/// Properties for the [`menu_item`] widget.
+#[derive(Props)]
+pub struct MenuItemProps<V: View + Clone = ()> {
     pub id: &'static str,
     pub label: V,
     pub checked: bool,
@@ -95,56 +86,8 @@ pub struct MenuItemProps<V: View + Clone> {
     // icon
 }
 
-impl<V: View + Clone, VI: View + Clone, S: StyleTuple> MenuButtonProps<V, VI, S> {
-    pub fn children<V2: View + Clone>(self, children: V2) -> MenuButtonProps<V2, VI, S> {
-        MenuButtonProps {
-            children,
-            items: self.items,
-            size: self.size,
-            style: self.style,
-            disabled: self.disabled,
-            indent: self.indent,
-        }
-    }
-
-    pub fn items<V2: View + Clone>(self, items: V2) -> MenuButtonProps<V, V2, S> {
-        MenuButtonProps {
-            children: self.children,
-            items,
-            size: self.size,
-            style: self.style,
-            disabled: self.disabled,
-            indent: self.indent,
-        }
-    }
-
-    pub fn style<S2: StyleTuple>(self, style: S2) -> MenuButtonProps<V, VI, S2> {
-        MenuButtonProps {
-            children: self.children,
-            items: self.items,
-            size: self.size,
-            style,
-            disabled: self.disabled,
-            indent: self.indent,
-        }
-    }
-
-    pub fn size(mut self, size: Size) -> Self {
-        self.size = size;
-        self
-    }
-
-    pub fn disabled(mut self, disabled: bool) -> Self {
-        self.disabled = disabled;
-        self
-    }
-
-    pub fn indent(mut self, indent: bool) -> Self {
-        self.indent = indent;
-        self
-    }
-}
-
+/// A button which opens a popup menu, invoked as `menu_button().children("Menu").items(..)`.
+#[presenter]
 pub fn menu_button<
     V: View + Clone + PartialEq + 'static,
     VI: View + Clone + PartialEq + 'static,
@@ -203,6 +146,8 @@ pub fn menu_button<
     })
 }
 
+/// An item within a menu, invoked as `menu_item().id("open").label("Open")`.
+#[presenter]
 pub fn menu_item<V: View + Clone + PartialEq + 'static>(cx: Cx<MenuItemProps<V>>) -> impl View {
     bevy_egret::widgets::menu_item.bind(bevy_egret::widgets::MenuItemProps {
         label: cx.props.label.clone(),
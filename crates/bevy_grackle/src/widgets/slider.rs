@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use bevy::{asset::AssetPath, ui};
+use bevy::{prelude::*, ui};
 use bevy_egret::widgets::SliderChildProps;
 use bevy_quill::prelude::*;
 use static_init::dynamic;
@@ -60,25 +60,19 @@ static STYLE_THUMB_FG: StyleHandle = StyleHandle::build(|ss| {
         .left(0.)
         .width(THUMB_SIZE)
         .height(THUMB_SIZE)
+        .border_radius(THUMB_SIZE * 0.5)
+        .box_shadow(BoxShadow {
+            offset: Vec2::new(2., 2.),
+            blur: 2.,
+            spread: 0.,
+            color: Color::rgba(0., 0., 0., 0.5),
+        })
         .z_index(1)
         .pointer_events(PointerEvents::None)
 });
 
-// Slider thumb shadow
-#[dynamic]
-static STYLE_THUMB_SHADOW: StyleHandle = StyleHandle::build(|ss| {
-    ss.background_color("#0008")
-        .background_image(Some(AssetPath::from("grackle://icons/disc.png")))
-        .position(ui::PositionType::Absolute)
-        .top(3.)
-        .left(3.)
-        .width(THUMB_SIZE)
-        .height(THUMB_SIZE)
-        .z_index(-1)
-        .pointer_events(PointerEvents::None)
-});
-
-#[derive(PartialEq, Clone, Default)]
+/// Properties for the [`h_slider`] widget.
+#[derive(Props)]
 pub struct SliderProps<S: StyleTuple = ()> {
     pub id: &'static str,
     pub min: f32,
@@ -87,7 +81,8 @@ pub struct SliderProps<S: StyleTuple = ()> {
     pub style: S,
 }
 
-// Horizontal slider widget
+/// Horizontal slider widget, invoked as `h_slider().id("volume").max(100.)`.
+#[presenter]
 pub fn h_slider<S: StyleTuple + PartialEq + 'static>(cx: Cx<SliderProps<S>>) -> impl View {
     // Get styles from theme. These will be combined with built-in styles.
     let track_style = cx.get_scoped_value(H_SLIDER_TRACK);
@@ -119,11 +114,10 @@ pub fn h_slider<S: StyleTuple + PartialEq + 'static>(cx: Cx<SliderProps<S>>) ->
                                 STYLE_THUMB.clone(),
                                 StyleHandle::build(|s| s.left(ui::Val::Percent(spc.percent))),
                             ))
-                            .children((
+                            .children(
                                 Element::new()
                                     .styled((STYLE_THUMB_FG.clone(), thumb_style.clone())),
-                                Element::new().styled(STYLE_THUMB_SHADOW.clone()),
-                            )),
+                            ),
                     ),
             ))
         }),
@@ -15,6 +15,7 @@ static STYLE_VSPLITTER: StyleHandle = StyleHandle::build(|ss| {
         .flex_direction(ui::FlexDirection::Column)
         .gap(8)
         .width(9)
+        .cursor(Cursor::ColResize)
 });
 
 // The decorative handle inside the splitter.
@@ -26,13 +27,15 @@ static STYLE_VSPLITTER_INNER: StyleHandle = StyleHandle::build(|ss| {
         .height(ui::Val::Percent(5.))
 });
 
-#[derive(Clone, PartialEq)]
+/// Properties for the [`v_splitter`] widget.
+#[derive(Props)]
 pub struct SplitterProps {
     pub value: f32,
     pub id: &'static str,
 }
 
-// Vertical splitter bar which can be dragged
+/// Vertical splitter bar which can be dragged, invoked as `v_splitter().id("split")`.
+#[presenter]
 pub fn v_splitter(cx: Cx<SplitterProps>) -> impl View {
     bevy_egret::widgets::v_splitter.bind(bevy_egret::widgets::SplitterProps {
         id: cx.props.id,
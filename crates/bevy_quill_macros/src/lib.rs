@@ -0,0 +1,51 @@
+//! Procedural macros for the Quill UI framework. These are re-exported by `bevy_quill`, and
+//! should be used from there.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemFn};
+
+mod presenter;
+mod props;
+
+/// Derives a builder, `Default`, `Clone` and `PartialEq` for a struct of presenter properties.
+///
+/// For each field, a setter method of the same name is generated. If the type of a field is a
+/// bare type parameter, the setter accepts any type satisfying the parameter's bounds, and
+/// returns the props with that parameter replaced. This means that fields like `children` can
+/// start out as `()` and be given a view later. If all type parameters have defaults, a
+/// `new()` constructor and an implementation of `bevy_quill::Props` are also generated.
+///
+/// Field attributes:
+/// * `#[props(default = expr)]` sets the default value of the field. Otherwise the field's
+///   `Default` implementation is used.
+/// * `#[props(ptr_eq)]` compares the field by address rather than by value. This is intended
+///   for fields holding shared closures, such as `Arc<dyn Fn(...)>`, which can't be compared.
+///   This works for `Arc`, `Box`, references, and `Option`s of these.
+#[proc_macro_derive(Props, attributes(props))]
+pub fn derive_props(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    props::derive_props(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Turns a presenter function into a builder function, so that the presenter can be invoked
+/// as `button().id("ok").children("OK")` rather than `button.bind(ButtonProps { ... })`.
+///
+/// The presenter's props type must derive `Props`. The original function is renamed with a
+/// `_presenter` suffix, and can still be bound directly.
+#[proc_macro_attribute]
+pub fn presenter(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "#[presenter] does not take any arguments",
+        )
+        .into_compile_error()
+        .into();
+    }
+    let func = parse_macro_input!(item as ItemFn);
+    presenter::presenter(func)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,120 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{FnArg, GenericArgument, GenericParam, ItemFn, PathArguments, Type};
+
+pub(crate) fn presenter(func: ItemFn) -> syn::Result<TokenStream> {
+    let name = &func.sig.ident;
+    let vis = &func.vis;
+
+    // The props type is the type argument of the `Cx` parameter.
+    let props_ty = match func.sig.inputs.first() {
+        Some(FnArg::Typed(arg)) => cx_props(&arg.ty),
+        _ => None,
+    }
+    .ok_or_else(|| {
+        syn::Error::new_spanned(
+            &func.sig,
+            "a presenter must take a single `Cx<Props>` parameter",
+        )
+    })?;
+
+    // The props type with the generic arguments removed, so that defaults are used.
+    let mut props_name = props_ty.clone();
+    if let Type::Path(ref mut path) = props_name {
+        if let Some(last) = path.path.segments.last_mut() {
+            last.arguments = PathArguments::None;
+        }
+    }
+
+    let presenter_fn = format_ident!("{}_presenter", name);
+    let marker = format_ident!("{}Presenter", to_camel_case(&name.to_string()));
+    let (impl_generics, _, where_clause) = func.sig.generics.split_for_impl();
+    let type_args: Vec<TokenStream> = func
+        .sig
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(tp) => {
+                let ident = &tp.ident;
+                Some(quote!(#ident))
+            }
+            GenericParam::Const(cp) => {
+                let ident = &cp.ident;
+                Some(quote!(#ident))
+            }
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+    let turbofish = if type_args.is_empty() {
+        quote!()
+    } else {
+        quote!(::<#(#type_args),*>)
+    };
+
+    let docs: Vec<_> = func
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .collect();
+    let marker_doc = format!("Marker type for the [`{}`] presenter.", name);
+    let presenter_doc = format!(
+        "The presenter function for [`{}`], which can be bound to props directly.",
+        name
+    );
+
+    let mut inner = func.clone();
+    inner.sig.ident = presenter_fn.clone();
+    inner.attrs.retain(|attr| !attr.path().is_ident("doc"));
+
+    Ok(quote! {
+        #(#docs)*
+        #vis fn #name() -> <#props_name as ::bevy_quill::Props>::Builder<#marker> {
+            <#props_name as ::bevy_quill::Props>::builder(#marker)
+        }
+
+        #[doc = #marker_doc]
+        #[doc(hidden)]
+        #[derive(Clone, Copy, PartialEq)]
+        #vis struct #marker;
+
+        impl #impl_generics ::bevy_quill::PresenterFor<#props_ty> for #marker #where_clause {
+            fn bind(self, props: #props_ty) -> ::bevy_quill::Bind {
+                ::bevy_quill::Bind::new(#presenter_fn #turbofish, props)
+            }
+        }
+
+        #[doc = #presenter_doc]
+        #inner
+    })
+}
+
+/// If the type is `Cx<Props>`, returns the props type.
+fn cx_props(ty: &Type) -> Option<Type> {
+    let Type::Path(ref path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    if last.ident != "Cx" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(ref args) = last.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty.clone()),
+        _ => None,
+    })
+}
+
+fn to_camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
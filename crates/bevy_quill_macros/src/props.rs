@@ -0,0 +1,411 @@
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Data, DeriveInput, Expr, Fields, GenericArgument, GenericParam, Generics, Type,
+    TypeParam, WherePredicate,
+};
+
+/// A field of the props struct, and its `#[props(...)]` options.
+struct PropsField {
+    ident: Ident,
+    ty: Type,
+    default: Option<Expr>,
+    ptr_eq: bool,
+}
+
+pub(crate) fn derive_props(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let Data::Struct(ref data) = input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Props can only be derived for structs",
+        ));
+    };
+    let Fields::Named(ref named) = data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Props can only be derived for structs with named fields",
+        ));
+    };
+    let fields = named
+        .named
+        .iter()
+        .map(|field| {
+            let mut props_field = PropsField {
+                ident: field.ident.clone().unwrap(),
+                ty: field.ty.clone(),
+                default: None,
+                ptr_eq: false,
+            };
+            for attr in field.attrs.iter().filter(|a| a.path().is_ident("props")) {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("ptr_eq") {
+                        props_field.ptr_eq = true;
+                        Ok(())
+                    } else if meta.path.is_ident("default") {
+                        if meta.input.peek(syn::Token![=]) {
+                            props_field.default = Some(meta.value()?.parse()?);
+                        }
+                        Ok(())
+                    } else {
+                        Err(meta.error("unknown props attribute"))
+                    }
+                })?;
+            }
+            Ok(props_field)
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let type_params: Vec<&TypeParam> = generics.type_params().collect();
+    let builder = format_ident!("{}Builder", name);
+
+    // Generics for the builder: the props generics, plus the presenter marker type.
+    let mut builder_generics = generics.clone();
+    let lifetime_count = generics.lifetimes().count();
+    builder_generics
+        .params
+        .insert(lifetime_count, parse_quote!(__M));
+    let (b_impl_generics, b_ty_generics, _) = builder_generics.split_for_impl();
+
+    // Setters, for both the props and the builder.
+    let mut setters: Vec<TokenStream> = Vec::new();
+    let mut builder_setters: Vec<TokenStream> = Vec::new();
+    for field in fields.iter() {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let doc = format!("Set the value of `{}`.", ident);
+        match retypable_param(field, &fields, &type_params) {
+            Some(param) => {
+                let new_param = format_ident!("__{}", param.ident);
+                let bounds = param_bounds(generics, param);
+                let props_args = replace_arg(generics, &param.ident, &new_param, None);
+                let builder_args = replace_arg(generics, &param.ident, &new_param, Some("__M"));
+                let others = fields
+                    .iter()
+                    .filter(|f| f.ident != *ident)
+                    .map(|f| &f.ident);
+                setters.push(quote! {
+                    #[doc = #doc]
+                    #[allow(non_camel_case_types)]
+                    pub fn #ident<#new_param: #bounds>(self, #ident: #new_param)
+                        -> #name<#props_args>
+                    {
+                        #name {
+                            #ident,
+                            #(#others: self.#others,)*
+                        }
+                    }
+                });
+                builder_setters.push(quote! {
+                    #[doc = #doc]
+                    #[allow(non_camel_case_types)]
+                    pub fn #ident<#new_param: #bounds>(self, #ident: #new_param)
+                        -> #builder<#builder_args>
+                    {
+                        #builder {
+                            inner: ::bevy_quill::Presented {
+                                presenter: self.inner.presenter,
+                                props: self.inner.props.#ident(#ident),
+                            },
+                        }
+                    }
+                });
+            }
+            None => {
+                setters.push(quote! {
+                    #[doc = #doc]
+                    pub fn #ident(mut self, #ident: #ty) -> Self {
+                        self.#ident = #ident;
+                        self
+                    }
+                });
+                builder_setters.push(quote! {
+                    #[doc = #doc]
+                    pub fn #ident(mut self, #ident: #ty) -> Self {
+                        self.inner.props.#ident = #ident;
+                        self
+                    }
+                });
+            }
+        }
+    }
+
+    // Bounds on generic field types, for the derived trait implementations.
+    let bounded = |trait_path: TokenStream, filter: &dyn Fn(&PropsField) -> bool| {
+        let mut predicates: Vec<WherePredicate> = where_clause
+            .map(|wc| wc.predicates.iter().cloned().collect())
+            .unwrap_or_default();
+        for field in fields.iter().filter(|f| filter(f)) {
+            if mentions_any(&field.ty, &type_params) {
+                let ty = &field.ty;
+                predicates.push(parse_quote!(#ty: #trait_path));
+            }
+        }
+        predicates
+    };
+
+    let default_preds = bounded(quote!(::std::default::Default), &|f| f.default.is_none());
+    let default_values = fields.iter().map(|f| {
+        let ident = &f.ident;
+        match f.default {
+            Some(ref expr) => quote!(#ident: #expr),
+            None => quote!(#ident: ::std::default::Default::default()),
+        }
+    });
+
+    let clone_preds = bounded(quote!(::std::clone::Clone), &|_| true);
+    let clone_values = fields.iter().map(|f| {
+        let ident = &f.ident;
+        quote!(#ident: ::std::clone::Clone::clone(&self.#ident))
+    });
+
+    let eq_preds = bounded(quote!(::std::cmp::PartialEq), &|f| !f.ptr_eq);
+    let eq_terms = fields.iter().map(|f| {
+        let ident = &f.ident;
+        if f.ptr_eq {
+            quote!(::bevy_quill::__private::PtrEq::ptr_eq(&self.#ident, &other.#ident))
+        } else {
+            quote!(self.#ident == other.#ident)
+        }
+    });
+
+    let builder_preds: Vec<WherePredicate> = where_clause
+        .map(|wc| wc.predicates.iter().cloned().collect())
+        .unwrap_or_default();
+    let builder_doc = format!(
+        "Builder which holds [`{}`] and the presenter they will be passed to. This is a View \
+         which invokes the presenter.",
+        name
+    );
+
+    // `new()` and the `Props` trait need default types for all generic parameters.
+    let has_defaults = generics.params.iter().all(|param| match param {
+        GenericParam::Type(tp) => tp.default.is_some(),
+        GenericParam::Const(cp) => cp.default.is_some(),
+        GenericParam::Lifetime(_) => false,
+    });
+    let defaults = if has_defaults {
+        quote! {
+            impl #name {
+                /// Construct a new set of props with default values.
+                pub fn new() -> Self {
+                    ::std::default::Default::default()
+                }
+            }
+
+            impl ::bevy_quill::Props for #name {
+                type Builder<__M> = #builder<__M>;
+
+                fn builder<__M>(presenter: __M) -> Self::Builder<__M> {
+                    #builder {
+                        inner: ::bevy_quill::Presented {
+                            presenter,
+                            props: ::std::default::Default::default(),
+                        },
+                    }
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#setters)*
+        }
+
+        impl #impl_generics ::std::default::Default for #name #ty_generics
+        where #(#default_preds,)*
+        {
+            fn default() -> Self {
+                Self {
+                    #(#default_values,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::std::clone::Clone for #name #ty_generics
+        where #(#clone_preds,)*
+        {
+            fn clone(&self) -> Self {
+                Self {
+                    #(#clone_values,)*
+                }
+            }
+        }
+
+        impl #impl_generics ::std::cmp::PartialEq for #name #ty_generics
+        where #(#eq_preds,)*
+        {
+            fn eq(&self, other: &Self) -> bool {
+                true #(&& #eq_terms)*
+            }
+        }
+
+        #defaults
+
+        #[doc = #builder_doc]
+        #vis struct #builder #builder_generics #where_clause {
+            inner: ::bevy_quill::Presented<__M, #name #ty_generics>,
+        }
+
+        impl #b_impl_generics #builder #b_ty_generics #where_clause {
+            #(#builder_setters)*
+        }
+
+        impl #b_impl_generics ::std::clone::Clone for #builder #b_ty_generics
+        where
+            ::bevy_quill::Presented<__M, #name #ty_generics>: ::std::clone::Clone,
+            #(#builder_preds,)*
+        {
+            fn clone(&self) -> Self {
+                Self {
+                    inner: ::std::clone::Clone::clone(&self.inner),
+                }
+            }
+        }
+
+        impl #b_impl_generics ::std::cmp::PartialEq for #builder #b_ty_generics
+        where
+            ::bevy_quill::Presented<__M, #name #ty_generics>: ::std::cmp::PartialEq,
+            #(#builder_preds,)*
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.inner == other.inner
+            }
+        }
+
+        impl #b_impl_generics ::bevy_quill::View for #builder #b_ty_generics
+        where
+            ::bevy_quill::Presented<__M, #name #ty_generics>: ::bevy_quill::View,
+            #(#builder_preds,)*
+        {
+            type State =
+                <::bevy_quill::Presented<__M, #name #ty_generics> as ::bevy_quill::View>::State;
+
+            fn nodes(
+                &self,
+                bc: &::bevy_quill::BuildContext,
+                state: &Self::State,
+            ) -> ::bevy_quill::NodeSpan {
+                self.inner.nodes(bc, state)
+            }
+
+            fn build(&self, bc: &mut ::bevy_quill::BuildContext) -> Self::State {
+                self.inner.build(bc)
+            }
+
+            fn update(&self, bc: &mut ::bevy_quill::BuildContext, state: &mut Self::State) {
+                self.inner.update(bc, state)
+            }
+
+            fn assemble(
+                &self,
+                bc: &mut ::bevy_quill::BuildContext,
+                state: &mut Self::State,
+            ) -> ::bevy_quill::NodeSpan {
+                self.inner.assemble(bc, state)
+            }
+
+            fn raze(&self, world: &mut ::bevy_quill::__private::World, state: &mut Self::State) {
+                self.inner.raze(world, state)
+            }
+        }
+    })
+}
+
+/// If the field's type is a bare type parameter which isn't used by any other field, returns
+/// that parameter. Setting such a field can change the parameter's type.
+fn retypable_param<'a>(
+    field: &PropsField,
+    fields: &[PropsField],
+    type_params: &[&'a TypeParam],
+) -> Option<&'a TypeParam> {
+    let Type::Path(ref path) = field.ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+    let ident = path.path.get_ident()?;
+    let param = type_params.iter().find(|tp| tp.ident == *ident)?;
+    let shared = fields.iter().filter(|f| f.ident != field.ident).any(|f| {
+        let ty = &f.ty;
+        mentions(quote!(#ty), ident)
+    });
+    if shared {
+        None
+    } else {
+        Some(param)
+    }
+}
+
+/// Returns the bounds of a type parameter, including those in the where clause.
+fn param_bounds(generics: &Generics, param: &TypeParam) -> TokenStream {
+    let mut bounds: Vec<TokenStream> = param.bounds.iter().map(|b| quote!(#b)).collect();
+    if let Some(ref wc) = generics.where_clause {
+        for predicate in wc.predicates.iter() {
+            if let WherePredicate::Type(ref pt) = predicate {
+                if let Type::Path(ref path) = pt.bounded_ty {
+                    if path.path.is_ident(&param.ident) {
+                        bounds.extend(pt.bounds.iter().map(|b| quote!(#b)));
+                    }
+                }
+            }
+        }
+    }
+    if bounds.is_empty() {
+        quote!(::std::marker::Sized)
+    } else {
+        quote!(#(#bounds)+*)
+    }
+}
+
+/// Returns the generic arguments of the struct, with one type parameter replaced.
+fn replace_arg(generics: &Generics, from: &Ident, to: &Ident, marker: Option<&str>) -> TokenStream {
+    let mut args: Vec<GenericArgument> = Vec::new();
+    for param in generics.params.iter() {
+        match param {
+            GenericParam::Lifetime(lp) => {
+                let lifetime = &lp.lifetime;
+                args.push(parse_quote!(#lifetime));
+            }
+            GenericParam::Type(tp) if tp.ident == *from => args.push(parse_quote!(#to)),
+            GenericParam::Type(tp) => {
+                let ident = &tp.ident;
+                args.push(parse_quote!(#ident));
+            }
+            GenericParam::Const(cp) => {
+                let ident = &cp.ident;
+                args.push(parse_quote!(#ident));
+            }
+        }
+    }
+    if let Some(marker) = marker {
+        let marker = format_ident!("{}", marker);
+        let lifetime_count = generics.lifetimes().count();
+        args.insert(lifetime_count, parse_quote!(#marker));
+    }
+    quote!(#(#args),*)
+}
+
+/// True if the type refers to any of the type parameters.
+fn mentions_any(ty: &Type, type_params: &[&TypeParam]) -> bool {
+    type_params
+        .iter()
+        .any(|tp| mentions(quote!(#ty), &tp.ident))
+}
+
+/// True if the token stream contains the identifier.
+fn mentions(tokens: TokenStream, ident: &Ident) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ref i) => i == ident,
+        TokenTree::Group(group) => mentions(group.stream(), ident),
+        _ => false,
+    })
+}
@@ -2,7 +2,7 @@ use bevy::{prelude::*, ui};
 use bevy_grackle::{
     events::Clicked,
     hooks::{EnterExitApi, EnterExitState},
-    widgets::{button, ButtonProps},
+    widgets::button,
 };
 use bevy_mod_picking::prelude::{EntityEvent, Listener, On};
 use bevy_quill::prelude::*;
@@ -137,8 +137,8 @@ pub fn dialog(mut cx: Cx<DemoDialogProps>) -> impl View {
                                     });
                             }))
                             .children((
-                                button.bind(ButtonProps::new("cancel").children("Cancel")),
-                                button.bind(ButtonProps::new("ok").children("Ok")),
+                                button().id("cancel").children("Cancel"),
+                                button().id("ok").children("Ok"),
                             )),
                     )),
                 ),
@@ -8,8 +8,6 @@ mod swatch;
 mod test_scene;
 mod viewport;
 
-use std::sync::Arc;
-
 use bevy::{
     a11y::Focus,
     asset::io::{file::FileAssetReader, AssetSource},
@@ -310,69 +308,33 @@ fn ui_main(mut cx: Cx) -> impl View {
                         .named("button-row")
                         .styled(STYLE_BUTTON_ROW.clone())
                         .children((
-                            button.bind(ButtonProps {
-                                id: "save",
-                                children: Arc::new("Save"),
-                                style: STYLE_BUTTON_FLEX.clone(),
-                                ..default()
-                            }),
-                            menu_button.bind(
-                                MenuButtonProps::new()
-                                    .children("File…")
-                                    .indent(true)
-                                    .items(Fragment::new((
-                                        menu_item.bind(MenuItemProps {
-                                            label: "Light Theme",
-                                            id: "light-theme",
-                                            ..default()
-                                        }),
-                                        menu_item.bind(MenuItemProps {
-                                            label: "Dark Theme",
-                                            id: "dark-theme",
-                                            ..default()
-                                        }),
-                                        menu_divider.bind(()),
-                                        menu_item.bind(MenuItemProps {
-                                            label: "Save",
-                                            id: "save",
-                                            ..default()
-                                        }),
-                                        menu_item.bind(MenuItemProps {
-                                            label: "Save As…",
-                                            id: "save-as",
-                                            ..default()
-                                        }),
-                                        menu_item.bind(MenuItemProps {
-                                            label: "Export…",
-                                            id: "export",
-                                            ..default()
-                                        }),
-                                        menu_item.bind(MenuItemProps {
-                                            label: "Import…",
-                                            id: "import",
-                                            ..default()
-                                        }),
-                                    )))
-                                    .style(STYLE_BUTTON_FLEX.clone()),
-                            ),
+                            button()
+                                .id("save")
+                                .children("Save")
+                                .style(STYLE_BUTTON_FLEX.clone()),
+                            menu_button()
+                                .children("File…")
+                                .indent(true)
+                                .items(Fragment::new((
+                                    menu_item().id("light-theme").label("Light Theme"),
+                                    menu_item().id("dark-theme").label("Dark Theme"),
+                                    menu_divider.bind(()),
+                                    menu_item().id("save").label("Save"),
+                                    menu_item().id("save-as").label("Save As…"),
+                                    menu_item().id("export").label("Export…"),
+                                    menu_item().id("import").label("Import…"),
+                                )))
+                                .style(STYLE_BUTTON_FLEX.clone()),
                         )),
-                    button.bind(ButtonProps::new("load").children(Fragment::new((
+                    button().id("load").children(Fragment::new((
                         "Load",
                         swatch.bind(SwatchProps { color: Color::RED }),
-                    )))),
-                    button.bind(ButtonProps {
-                        id: "quit",
-                        children: "Quit",
-                        style: (),
-                        ..default()
-                    }),
+                    ))),
+                    button().id("quit").children("Quit"),
                     color_edit,
                     node_tree,
                 )),
-            v_splitter.bind(SplitterProps {
-                id: "",
-                value: width.value,
-            }),
+            v_splitter().value(width.value),
             Element::new()
                 .styled(STYLE_VIEWPORT.clone())
                 .insert(ViewportInsetElement {})
@@ -410,27 +372,21 @@ fn color_edit(cx: Cx) -> impl View {
                 colors: &COLORS,
                 row_span: 4,
             }),
-            h_slider.bind(SliderProps {
-                id: "r",
-                min: 0.,
-                max: 255.,
-                value: edit_color.color.r() * 255.0,
-                style: STYLE_SLIDER.clone(),
-            }),
-            h_slider.bind(SliderProps {
-                id: "g",
-                min: 0.,
-                max: 255.,
-                value: edit_color.color.g() * 255.0,
-                style: STYLE_SLIDER.clone(),
-            }),
-            h_slider.bind(SliderProps {
-                id: "b",
-                min: 0.,
-                max: 255.,
-                value: edit_color.color.b() * 255.0,
-                style: STYLE_SLIDER.clone(),
-            }),
+            h_slider()
+                .id("r")
+                .max(255.)
+                .value(edit_color.color.r() * 255.0)
+                .style(STYLE_SLIDER.clone()),
+            h_slider()
+                .id("g")
+                .max(255.)
+                .value(edit_color.color.g() * 255.0)
+                .style(STYLE_SLIDER.clone()),
+            h_slider()
+                .id("b")
+                .max(255.)
+                .value(edit_color.color.b() * 255.0)
+                .style(STYLE_SLIDER.clone()),
         ))
 }
 
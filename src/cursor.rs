@@ -1,4 +1,13 @@
-//! Cursor definitions (not done yet)
+//! Cursor definitions, and the system which sets the window cursor from the style of the
+//! hovered element.
+
+use bevy::{
+    asset::AssetPath, prelude::*, render::camera::NormalizedRenderTarget, window::CursorIcon,
+};
+use bevy_mod_picking::{
+    focus::HoverMap,
+    pointer::{PointerId, PointerLocation},
+};
 
 /// 2D Cursor type - subset of standard CSS cursor types
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -48,3 +57,101 @@ pub enum Cursor {
     /// Magnifying Glass with Minus
     ZoomOut,
 }
+
+impl Cursor {
+    /// Returns the window cursor icon for this cursor type. Bevy does not yet support custom
+    /// cursor images, so [`Cursor::CustomImage`] displays the default cursor.
+    pub(crate) fn icon(self) -> CursorIcon {
+        match self {
+            Cursor::None | Cursor::CustomImage | Cursor::Default => CursorIcon::Default,
+            Cursor::Pointer => CursorIcon::Pointer,
+            Cursor::Wait => CursorIcon::Wait,
+            Cursor::Crosshair => CursorIcon::Crosshair,
+            Cursor::Text => CursorIcon::Text,
+            Cursor::VerticalText => CursorIcon::VerticalText,
+            Cursor::Move => CursorIcon::Move,
+            Cursor::NotAllowed => CursorIcon::NotAllowed,
+            Cursor::Grab => CursorIcon::Grab,
+            Cursor::ColResize => CursorIcon::ColResize,
+            Cursor::RowResize => CursorIcon::RowResize,
+            Cursor::ZoomIn => CursorIcon::ZoomIn,
+            Cursor::ZoomOut => CursorIcon::ZoomOut,
+        }
+    }
+}
+
+/// The computed cursor style of an element. This is only present on elements whose styles set
+/// the `cursor` property; other elements use the cursor of their nearest ancestor.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct ElementCursor {
+    /// The cursor type.
+    pub cursor: Cursor,
+    /// Image used when the cursor type is [`Cursor::CustomImage`].
+    pub image: Option<AssetPath<'static>>,
+    /// Position of the hot spot within the custom image.
+    pub offset: IVec2,
+}
+
+/// Sets the cursor of the window under the mouse to the cursor style of the topmost hovered
+/// element, or of its nearest ancestor which has one. If no element sets a cursor, the
+/// window's cursor is left alone, except to restore the default cursor after an element's
+/// cursor was shown.
+pub(crate) fn update_cursor(
+    hover_map: Res<HoverMap>,
+    query_pointers: Query<(&PointerId, &PointerLocation)>,
+    query_cursors: Query<&ElementCursor>,
+    query_parents: Query<&Parent>,
+    mut query_windows: Query<&mut Window>,
+    mut hidden: Local<bool>,
+    mut icon_set: Local<bool>,
+) {
+    let Some(window) = query_pointers
+        .iter()
+        .find(|(id, _)| **id == PointerId::Mouse)
+        .and_then(|(_, pointer)| match pointer.location()?.target {
+            NormalizedRenderTarget::Window(window) => Some(window.entity()),
+            _ => None,
+        })
+    else {
+        return;
+    };
+    let Ok(mut window) = query_windows.get_mut(window) else {
+        return;
+    };
+
+    // The topmost element is the one nearest to the camera.
+    let topmost = hover_map.get(&PointerId::Mouse).and_then(|hovered| {
+        hovered
+            .iter()
+            .min_by(|(_, a), (_, b)| a.depth.total_cmp(&b.depth))
+            .map(|(entity, _)| *entity)
+    });
+
+    let mut cursor = None;
+    let mut e = topmost;
+    while let Some(entity) = e {
+        if let Ok(element_cursor) = query_cursors.get(entity) {
+            cursor = Some(element_cursor.cursor);
+            break;
+        }
+        e = query_parents.get(entity).ok().map(Parent::get);
+    }
+
+    // Only restore the default icon if we set it, so that applications can set the cursor icon
+    // themselves while it isn't over an element with a cursor style.
+    if cursor.is_some() || *icon_set {
+        let icon = cursor.map_or(CursorIcon::Default, Cursor::icon);
+        if window.cursor.icon != icon {
+            window.cursor.icon = icon;
+        }
+        *icon_set = cursor.is_some();
+    }
+
+    // Only change the visibility of the cursor if we hid it, so as not to interfere with
+    // applications which hide the cursor themselves.
+    let hide = cursor == Some(Cursor::None);
+    if hide != *hidden {
+        window.cursor.visible = !hide;
+        *hidden = hide;
+    }
+}
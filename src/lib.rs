@@ -3,22 +3,70 @@
 //! a foundation of Bevy ECS state management.
 
 #![warn(missing_docs)]
+
+// Allows the code generated by `bevy_quill_macros` to be used within this crate.
+extern crate self as bevy_quill;
+
 mod cursor;
+mod material;
 mod node_span;
 mod plugin;
 mod scrolling;
 mod style;
 mod view;
 
-pub use cursor::Cursor;
+pub use cursor::{Cursor, ElementCursor};
+pub use material::{StyleMaterial, StyleMaterialParams};
 pub use node_span::NodeSpan;
 #[doc(inline)]
 pub use prelude::*;
 pub use scrolling::*;
 
+#[doc(hidden)]
+pub mod __private {
+    use std::sync::Arc;
+
+    pub use bevy::ecs::world::World;
+
+    /// Comparison by address, used by `#[props(ptr_eq)]`.
+    pub trait PtrEq {
+        fn ptr_eq(&self, other: &Self) -> bool;
+    }
+
+    impl<T: ?Sized> PtrEq for Arc<T> {
+        fn ptr_eq(&self, other: &Self) -> bool {
+            std::ptr::addr_eq(Arc::as_ptr(self), Arc::as_ptr(other))
+        }
+    }
+
+    impl<T: ?Sized> PtrEq for Box<T> {
+        fn ptr_eq(&self, other: &Self) -> bool {
+            std::ptr::addr_eq(self.as_ref(), other.as_ref())
+        }
+    }
+
+    impl<T: ?Sized> PtrEq for &T {
+        fn ptr_eq(&self, other: &Self) -> bool {
+            std::ptr::addr_eq(*self, *other)
+        }
+    }
+
+    impl<T: PtrEq> PtrEq for Option<T> {
+        fn ptr_eq(&self, other: &Self) -> bool {
+            match (self, other) {
+                (Some(a), Some(b)) => a.ptr_eq(b),
+                (None, None) => true,
+                _ => false,
+            }
+        }
+    }
+}
+
 /// Common imports
 pub mod prelude {
+    pub use crate::cursor::Cursor;
     pub use crate::plugin::QuillPlugin;
     pub use crate::style::*;
     pub use crate::view::*;
+    pub use bevy_quill_macros::{presenter, Props};
 }
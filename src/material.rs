@@ -0,0 +1,217 @@
+// The `ShaderType` derive generates layout checks which are reported as unused.
+#![allow(dead_code)]
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef, ShaderType},
+};
+use bevy_color::LinearRgba;
+
+use crate::style::{BorderRadius, BoxShadow, Gradient, MAX_GRADIENT_STOPS};
+
+pub(crate) const STYLE_MATERIAL_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(0x6d3f_1c0e_8a52_4b7e_9f21_c4d8_0b93_e5a7);
+
+/// Material used to draw elements which have rounded corners, a box shadow or a background
+/// gradient. It replaces the element's [`BackgroundColor`] and [`BorderColor`], which can't
+/// draw any of these.
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug, Default)]
+pub struct StyleMaterial {
+    /// The shader parameters
+    #[uniform(0)]
+    pub params: StyleMaterialParams,
+}
+
+impl UiMaterial for StyleMaterial {
+    fn vertex_shader() -> ShaderRef {
+        STYLE_MATERIAL_SHADER_HANDLE.into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        STYLE_MATERIAL_SHADER_HANDLE.into()
+    }
+}
+
+/// The uniform parameters of a [`StyleMaterial`]. Colors are in linear RGBA, and lengths are
+/// in logical pixels.
+#[derive(ShaderType, Clone, Copy, Debug, Default, PartialEq)]
+pub struct StyleMaterialParams {
+    /// Background color, used when there is no gradient.
+    pub background: Vec4,
+    /// Border color. Border widths are taken from the element's [`Style`].
+    pub border_color: Vec4,
+    /// Corner radii, in clockwise order starting from the top left.
+    pub radius: Vec4,
+    /// Shadow color, which is transparent if there is no shadow.
+    pub shadow_color: Vec4,
+    /// Offset of the shadow.
+    pub shadow_offset: Vec2,
+    /// Blur distance of the shadow.
+    pub shadow_blur: f32,
+    /// Spread distance of the shadow.
+    pub shadow_spread: f32,
+    /// Distance by which the drawn area extends past the edges of the element, to make room
+    /// for the shadow.
+    pub extent: f32,
+    /// 0 for no gradient, 1 for a linear gradient and 2 for a radial gradient.
+    pub gradient_kind: u32,
+    /// Angle of a linear gradient.
+    pub gradient_angle: f32,
+    /// Number of gradient stops in use.
+    pub stop_count: u32,
+    /// Gradient stop colors.
+    pub stop_colors: [Vec4; MAX_GRADIENT_STOPS],
+    /// Gradient stop positions, packed four to a vector.
+    pub stop_positions: [Vec4; MAX_GRADIENT_STOPS / 4],
+}
+
+impl StyleMaterialParams {
+    /// Compute the material parameters for an element's decorations.
+    pub fn new(
+        background: Option<Color>,
+        border_color: Option<Color>,
+        radius: Option<BorderRadius>,
+        shadow: Option<&BoxShadow>,
+        gradient: Option<&Gradient>,
+    ) -> Self {
+        let linear = |color: Option<Color>| linear_color(color.unwrap_or(Color::NONE));
+        let radius = radius.unwrap_or_default();
+        let mut params = Self {
+            background: linear(background),
+            border_color: linear(border_color),
+            radius: Vec4::new(
+                radius.top_left,
+                radius.top_right,
+                radius.bottom_right,
+                radius.bottom_left,
+            ),
+            ..default()
+        };
+
+        if let Some(shadow) = shadow {
+            params.shadow_color = linear(Some(shadow.color));
+            params.shadow_offset = shadow.offset;
+            params.shadow_blur = shadow.blur.max(0.);
+            params.shadow_spread = shadow.spread;
+            params.extent =
+                (shadow.spread + params.shadow_blur + shadow.offset.abs().max_element())
+                    .max(0.)
+                    .ceil();
+        }
+
+        if let Some(gradient) = gradient {
+            let (kind, angle) = match gradient {
+                Gradient::Linear { angle, .. } => (1, *angle),
+                Gradient::Radial { .. } => (2, 0.),
+            };
+            params.gradient_kind = kind;
+            params.gradient_angle = angle;
+            let stops = &gradient.stops()[..gradient.stops().len().min(MAX_GRADIENT_STOPS)];
+            params.stop_count = stops.len() as u32;
+            for (index, stop) in stops.iter().enumerate() {
+                params.stop_colors[index] = linear(Some(stop.color));
+                params.stop_positions[index / 4][index % 4] = stop.position;
+            }
+        }
+
+        params
+    }
+}
+
+/// Convert a color to the linear RGBA used by the material parameters.
+pub(crate) fn linear_color(color: Color) -> Vec4 {
+    let color = LinearRgba::from(color);
+    Vec4::new(color.red, color.green, color.blue, color.alpha)
+}
+
+/// Returns the background and border colors drawn by an entity's material, if it has one.
+pub(crate) fn material_colors(world: &World, entity: Entity) -> Option<(Color, Color)> {
+    let handle = world.get::<Handle<StyleMaterial>>(entity)?;
+    let params = world
+        .get_resource::<Assets<StyleMaterial>>()?
+        .get(handle)?
+        .params;
+    let color = |c: Vec4| Color::rgba_linear(c.x, c.y, c.z, c.w);
+    Some((color(params.background), color(params.border_color)))
+}
+
+/// Update the material asset with new parameters, or create a new one if there is no existing
+/// material. Returns the handle of the new material, if one was created.
+pub(crate) fn update_material(
+    world: &mut World,
+    handle: Option<Handle<StyleMaterial>>,
+    params: StyleMaterialParams,
+) -> Option<Handle<StyleMaterial>> {
+    let mut materials = world.get_resource_mut::<Assets<StyleMaterial>>()?;
+    if let Some(handle) = handle {
+        if let Some(material) = materials.get(&handle) {
+            if material.params != params {
+                materials.get_mut(&handle).unwrap().params = params;
+            }
+            return None;
+        }
+    }
+    Some(materials.add(StyleMaterial { params }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+    use crate::style::ColorStop;
+
+    #[test]
+    fn test_material_params() {
+        let params = StyleMaterialParams::new(
+            Some(Color::WHITE),
+            None,
+            Some(BorderRadius::new(1., 2., 3., 4.)),
+            None,
+            None,
+        );
+        assert_eq!(params.background, Vec4::ONE);
+        assert_eq!(params.border_color, Vec4::ZERO);
+        assert_eq!(params.radius, Vec4::new(1., 2., 3., 4.));
+        assert_eq!(params.shadow_color.w, 0.);
+        assert_eq!(params.extent, 0.);
+        assert_eq!(params.gradient_kind, 0);
+    }
+
+    #[test]
+    fn test_shadow_extent() {
+        let shadow = BoxShadow {
+            offset: Vec2::new(3., -5.),
+            blur: 4.,
+            spread: 1.5,
+            color: Color::rgba(0., 0., 0., 0.5),
+        };
+        let params = StyleMaterialParams::new(None, None, None, Some(&shadow), None);
+        assert_eq!(params.shadow_color, Vec4::new(0., 0., 0., 0.5));
+        assert_eq!(params.shadow_offset, Vec2::new(3., -5.));
+        // Room for the offset, blur and spread on every side.
+        assert_eq!(params.extent, 11.);
+    }
+
+    #[test]
+    fn test_gradient_stops() {
+        let gradient = Gradient::linear(PI / 2., &[Color::BLACK, Color::WHITE, Color::BLACK]);
+        let params = StyleMaterialParams::new(None, None, None, None, Some(&gradient));
+        assert_eq!(params.gradient_kind, 1);
+        assert_eq!(params.gradient_angle, PI / 2.);
+        assert_eq!(params.stop_count, 3);
+        assert_eq!(params.stop_colors[1], Vec4::ONE);
+        assert_eq!(params.stop_positions[0], Vec4::new(0., 0.5, 1., 0.));
+
+        // Stops past the limit are ignored.
+        let gradient = Gradient::Radial {
+            stops: (0..10)
+                .map(|i| ColorStop::new(Color::WHITE, i as f32 / 9.))
+                .collect(),
+        };
+        let params = StyleMaterialParams::new(None, None, None, None, Some(&gradient));
+        assert_eq!(params.gradient_kind, 2);
+        assert_eq!(params.stop_count, MAX_GRADIENT_STOPS as u32);
+        assert_eq!(params.stop_positions[1].w, 7. / 9.);
+    }
+}
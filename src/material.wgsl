@@ -0,0 +1,138 @@
+// Draws the background, border and shadow of elements with rounded corners, box shadows or
+// background gradients.
+
+#import bevy_render::view::View
+#import bevy_ui::ui_vertex_output::UiVertexOutput
+
+@group(0) @binding(0)
+var<uniform> view: View;
+
+struct StyleMaterial {
+    background: vec4<f32>,
+    border_color: vec4<f32>,
+    // Top left, top right, bottom right, bottom left.
+    radius: vec4<f32>,
+    shadow_color: vec4<f32>,
+    shadow_offset: vec2<f32>,
+    shadow_blur: f32,
+    shadow_spread: f32,
+    extent: f32,
+    gradient_kind: u32,
+    gradient_angle: f32,
+    stop_count: u32,
+    stop_colors: array<vec4<f32>, 8>,
+    stop_positions: array<vec4<f32>, 2>,
+}
+
+@group(1) @binding(0)
+var<uniform> material: StyleMaterial;
+
+@vertex
+fn vertex(
+    @location(0) vertex_position: vec3<f32>,
+    @location(1) vertex_uv: vec2<f32>,
+    @location(2) size: vec2<f32>,
+    @location(3) border_widths: vec4<f32>,
+) -> UiVertexOutput {
+    // Grow the quad so that the shadow can be drawn outside of the element. The UVs are still
+    // relative to the element, so they extend past [0, 1] in the added area.
+    let corner = vertex_uv * 2.0 - 1.0;
+    let position = vertex_position + vec3<f32>(corner * material.extent, 0.0);
+
+    var out: UiVertexOutput;
+    out.position = view.view_proj * vec4<f32>(position, 1.0);
+    out.uv = vertex_uv + corner * material.extent / size;
+    out.size = size;
+    out.border_widths = border_widths;
+    return out;
+}
+
+// Signed distance from a point to a rounded rectangle centered on the origin.
+fn rounded_box(point: vec2<f32>, half_size: vec2<f32>, radius: vec4<f32>) -> f32 {
+    var r = select(radius.z, radius.w, point.x < 0.0);
+    if point.y < 0.0 {
+        r = select(radius.y, radius.x, point.x < 0.0);
+    }
+    r = clamp(r, 0.0, min(half_size.x, half_size.y));
+    let q = abs(point) - half_size + r;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - r;
+}
+
+fn stop_position(index: u32) -> f32 {
+    return material.stop_positions[index / 4u][index % 4u];
+}
+
+fn fill_color(uv: vec2<f32>, size: vec2<f32>) -> vec4<f32> {
+    if material.gradient_kind == 0u || material.stop_count == 0u {
+        return material.background;
+    }
+
+    var t: f32;
+    if material.gradient_kind == 1u {
+        // Angles are measured clockwise from the top, and the gradient line is long enough
+        // for the corners to reach the first and last stops.
+        let direction = vec2<f32>(sin(material.gradient_angle), -cos(material.gradient_angle));
+        let length = abs(size.x * direction.x) + abs(size.y * direction.y);
+        t = dot((uv - 0.5) * size, direction) / length + 0.5;
+    } else {
+        t = length((uv - 0.5) * 2.0) / sqrt(2.0);
+    }
+
+    var color = material.stop_colors[0];
+    for (var i = 1u; i < material.stop_count; i++) {
+        let start = stop_position(i - 1u);
+        let end = stop_position(i);
+        if t > start {
+            let f = clamp((t - start) / max(end - start, 0.0001), 0.0, 1.0);
+            color = mix(material.stop_colors[i - 1u], material.stop_colors[i], f);
+        }
+    }
+    return color;
+}
+
+@fragment
+fn fragment(in: UiVertexOutput) -> @location(0) vec4<f32> {
+    let half_size = in.size * 0.5;
+    let point = (in.uv - 0.5) * in.size;
+    let outer = rounded_box(point, half_size, material.radius);
+
+    // Border widths in pixels: left, right, top, bottom.
+    let border = in.border_widths * vec4<f32>(in.size.x, in.size.x, in.size.y, in.size.y);
+    let inner_center = vec2<f32>(border.x - border.y, border.z - border.w) * 0.5;
+    let inner_half_size = half_size - vec2<f32>(border.x + border.y, border.z + border.w) * 0.5;
+    let inner_radius = max(
+        material.radius - vec4<f32>(
+            max(border.x, border.z),
+            max(border.y, border.z),
+            max(border.y, border.w),
+            max(border.x, border.w),
+        ),
+        vec4<f32>(0.0),
+    );
+    let inner = rounded_box(point - inner_center, inner_half_size, inner_radius);
+
+    // Anti-aliased coverage of the element and of the area inside the border.
+    let outer_coverage = clamp(0.5 - outer, 0.0, 1.0);
+    let inner_coverage = clamp(0.5 - inner, 0.0, 1.0);
+    var color = mix(material.border_color, fill_color(in.uv, in.size), inner_coverage);
+    color.a *= outer_coverage;
+
+    // The shadow is only visible outside of the element.
+    let blur = max(material.shadow_blur, 0.5);
+    let shadow_distance = rounded_box(
+        point - material.shadow_offset,
+        half_size + material.shadow_spread,
+        material.radius + material.shadow_spread,
+    );
+    let shadow_alpha = material.shadow_color.a
+        * (1.0 - smoothstep(-blur, blur, shadow_distance))
+        * (1.0 - outer_coverage);
+
+    let alpha = color.a + shadow_alpha * (1.0 - color.a);
+    if alpha <= 0.0 {
+        discard;
+    }
+    let rgb = (color.rgb * color.a + material.shadow_color.rgb * shadow_alpha * (1.0 - color.a))
+        / alpha;
+    return vec4<f32>(rgb, alpha);
+}
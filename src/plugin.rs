@@ -1,36 +1,83 @@
-use bevy::{prelude::*, utils::HashSet};
+use bevy::{
+    asset::load_internal_asset, prelude::*, transform::TransformSystem, ui::UiSystem,
+    utils::HashSet,
+};
 use bevy_mod_picking::prelude::EventListenerPlugin;
 
 use crate::{
-    animate_bg_colors, animate_border_colors, animate_layout, animate_transforms,
+    animate_colors, animate_keyframes, animate_layout, animate_moves, animate_opacity,
+    animate_transforms,
+    cursor::update_cursor,
     handle_scroll_events,
-    presenter_state::{PresenterGraphChanged, PresenterStateChanged},
+    material::STYLE_MATERIAL_SHADER_HANDLE,
+    presenter_state::{PresenterGraphChanged, PresenterStateChanged, PresenterSuspended},
+    reload_style_sheets,
+    show::{is_presenter_suspended, ViewScope},
     tracked_resources::TrackedResources,
     tracking::TrackedComponents,
     update::{update_styles, PreviousFocus},
-    update_scroll_positions, BuildContext, ScrollWheel, ViewHandle,
+    update_container_sizes, update_image_fit, update_scroll_positions, update_text_bindings,
+    update_transition_groups, update_virtual_lists, AnimationEnd, AnimationStart, BuildContext,
+    ColorScheme, ScrollWheel, StyleMaterial, StyleSheet, StyleSheetLoader, ViewHandle,
 };
 
 /// Plugin which initializes the Quill library.
+///
+/// The [`StyleMaterial`] is only registered if the app renders, so this should be added after
+/// Bevy's `RenderPlugin`. Headless apps draw no decorations.
 pub struct QuillPlugin;
 
 impl Plugin for QuillPlugin {
     fn build(&self, app: &mut App) {
+        if app.world.contains_resource::<Assets<Shader>>() {
+            load_internal_asset!(
+                app,
+                STYLE_MATERIAL_SHADER_HANDLE,
+                "material.wgsl",
+                Shader::from_wgsl
+            );
+            app.add_plugins(UiMaterialPlugin::<StyleMaterial>::default());
+        }
         app.init_resource::<PreviousFocus>()
+            .init_resource::<ColorScheme>()
+            .init_asset::<StyleSheet>()
+            .init_asset_loader::<StyleSheetLoader>()
             .add_systems(
                 Update,
                 (
-                    (render_views, update_styles).chain(),
+                    (
+                        update_virtual_lists,
+                        render_views,
+                        update_text_bindings,
+                        update_transition_groups,
+                        reload_style_sheets,
+                        animate_opacity,
+                        update_container_sizes,
+                        update_styles,
+                    )
+                        .chain(),
                     animate_transforms,
-                    animate_bg_colors,
-                    animate_border_colors,
+                    animate_colors,
                     animate_layout,
+                    animate_keyframes.after(update_styles),
                     update_scroll_positions,
                     handle_scroll_events,
+                    update_image_fit,
+                    update_cursor.after(update_styles),
                 ),
             )
+            .add_systems(
+                PostUpdate,
+                animate_moves
+                    .after(UiSystem::Layout)
+                    .before(TransformSystem::TransformPropagate),
+            )
             .add_plugins(EventListenerPlugin::<ScrollWheel>::default())
-            .add_event::<ScrollWheel>();
+            .add_event::<ScrollWheel>()
+            .add_plugins(EventListenerPlugin::<AnimationStart>::default())
+            .add_event::<AnimationStart>()
+            .add_plugins(EventListenerPlugin::<AnimationEnd>::default())
+            .add_event::<AnimationEnd>();
     }
 }
 
@@ -110,6 +157,12 @@ fn render_views(world: &mut World) {
         // phase 2
         if change_ct > 0 {
             for e in v.drain() {
+                // Presenters inside a hidden `Show` are skipped until it becomes visible.
+                if is_presenter_suspended(world, e) {
+                    world.entity_mut(e).insert(PresenterSuspended);
+                    continue;
+                }
+
                 let mut entt = world.entity_mut(e);
                 // Clear tracking lists for presenters to be re-rendered.
                 if let Some(mut tracked_resources) = entt.get_mut::<TrackedResources>() {
@@ -145,6 +198,14 @@ fn render_views(world: &mut World) {
             let mut ent = world.entity_mut(e);
             ent.remove::<PresenterGraphChanged>();
             let Some(view_handle) = world.get_mut::<ViewHandle>(e) else {
+                // Scope entities don't have a display graph of their own, so pass the change
+                // on to the presenter that owns them.
+                if world.get::<ViewScope>(e).is_some() {
+                    if let Some(parent) = world.get::<Parent>(e) {
+                        let parent = parent.get();
+                        world.entity_mut(parent).insert(PresenterGraphChanged);
+                    }
+                }
                 continue;
             };
             let inner = view_handle.inner.clone();
@@ -153,3 +214,36 @@ fn render_views(world: &mut World) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::mouse::MouseWheel;
+    use bevy_mod_picking::focus::{HoverMap, PreviousHoverMap};
+
+    use super::*;
+    use crate::{Cx, Element, StyleHandle, View};
+
+    fn rounded(_cx: Cx) -> impl View {
+        Element::new().styled(StyleHandle::build(|ss| {
+            ss.border_radius(4.).background_color(Color::WHITE)
+        }))
+    }
+
+    #[test]
+    fn test_headless_app() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), QuillPlugin))
+            .init_resource::<HoverMap>()
+            .init_resource::<PreviousHoverMap>()
+            .init_resource::<bevy::a11y::Focus>()
+            .add_event::<MouseWheel>();
+        app.world.spawn(ViewHandle::new(rounded, ()));
+        app.update();
+        app.update();
+
+        // There is no material without a renderer, but the element is still styled.
+        let mut query = app.world.query_filtered::<Entity, With<Style>>();
+        assert_eq!(query.iter(&app.world).count(), 1);
+        assert!(!app.world.contains_resource::<Assets<StyleMaterial>>());
+    }
+}
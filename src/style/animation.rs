@@ -0,0 +1,515 @@
+use std::mem::discriminant;
+
+use bevy::{ecs::system::Command, prelude::*, ui};
+use bevy_mod_picking::prelude::EntityEvent;
+
+use super::{
+    builder::StyleBuilder,
+    computed::{ComputedStyle, UpdateComputedStyle},
+    style_props::{StyleProp, StyleSet},
+    timing::{self, Timing, TimingFunction},
+    transition::{lerp_val, ColorSpace},
+};
+
+/// A single step of a keyframe animation: the style properties which should be in effect at
+/// a given point in the animation.
+#[derive(Clone, Debug)]
+pub struct Keyframe {
+    /// Position of the keyframe within one iteration of the animation, from 0 to 1.
+    pub offset: f32,
+
+    /// The properties set by this keyframe. Properties which are not set here are interpolated
+    /// between the nearest keyframes which do set them.
+    pub props: Vec<StyleProp>,
+}
+
+impl Keyframe {
+    /// Construct a keyframe using a builder callback.
+    pub fn build(
+        offset: f32,
+        builder_fn: impl FnOnce(&mut StyleBuilder) -> &mut StyleBuilder,
+    ) -> Self {
+        let mut builder = StyleBuilder::new();
+        builder_fn(&mut builder);
+        Self {
+            offset,
+            props: builder.props,
+        }
+    }
+}
+
+/// Whether successive iterations of an animation play forwards or backwards.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AnimationDirection {
+    /// Every iteration plays forwards.
+    #[default]
+    Normal,
+    /// Every iteration plays backwards.
+    Reverse,
+    /// Iterations alternate, starting forwards.
+    Alternate,
+    /// Iterations alternate, starting backwards.
+    AlternateReverse,
+}
+
+/// Whether the keyframe values are applied to the element before an animation starts and after
+/// it ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AnimationFillMode {
+    /// Keyframes only apply while the animation is playing.
+    #[default]
+    None,
+    /// The last keyframe remains in effect after the animation ends.
+    Forwards,
+    /// The first keyframe is in effect during the delay before the animation starts.
+    Backwards,
+    /// Both `Forwards` and `Backwards`.
+    Both,
+}
+
+/// Defines a CSS-like keyframe animation. The animation starts when a style containing it is
+/// applied to an element, and stops when the style no longer applies.
+#[derive(Clone, Debug)]
+pub struct Animation {
+    /// Name of the animation. Animations on the same element are identified by their name, so
+    /// an animation is not restarted when the element's styles are re-computed, as long as it
+    /// is still present. The name is also reported by [`AnimationStart`] and [`AnimationEnd`].
+    pub name: String,
+
+    /// List of keyframes, in order of increasing offset.
+    pub keyframes: Vec<Keyframe>,
+
+    /// Delay before animation starts
+    pub delay: f32,
+
+    /// Length of a single iteration of the animation
+    pub duration: f32,
+
+    /// How many times the animation should play. Use `f32::INFINITY` to loop forever.
+    pub iterations: f32,
+
+    /// Direction in which each iteration plays
+    pub direction: AnimationDirection,
+
+    /// Whether keyframe values apply outside of the active period of the animation
+    pub fill_mode: AnimationFillMode,
+
+    /// Easing function, applied separately between each pair of keyframes
+    pub timing: Timing,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            keyframes: Vec::new(),
+            delay: 0.,
+            duration: 0.,
+            iterations: 1.,
+            direction: AnimationDirection::Normal,
+            fill_mode: AnimationFillMode::None,
+            timing: timing::LINEAR,
+        }
+    }
+}
+
+impl Animation {
+    /// Total length of the active period of the animation, not including the delay.
+    fn active_duration(&self) -> f32 {
+        if self.iterations <= 0. {
+            0.
+        } else {
+            self.duration * self.iterations
+        }
+    }
+
+    /// Returns the position within the keyframes for a given clock value, or `None` if no
+    /// keyframes should be applied.
+    fn progress(&self, clock: f32) -> Option<f32> {
+        let elapsed = clock - self.delay;
+        let (iteration, fraction) = if elapsed < 0. {
+            if !matches!(
+                self.fill_mode,
+                AnimationFillMode::Backwards | AnimationFillMode::Both
+            ) {
+                return None;
+            }
+            (0., 0.)
+        } else if elapsed >= self.active_duration() {
+            if !matches!(
+                self.fill_mode,
+                AnimationFillMode::Forwards | AnimationFillMode::Both
+            ) {
+                return None;
+            }
+            // Hold the position at which the last iteration ended.
+            let iterations = self.iterations.max(0.);
+            let fraction = iterations.fract();
+            if fraction == 0. && iterations > 0. {
+                (iterations - 1., 1.)
+            } else {
+                (iterations.floor(), fraction)
+            }
+        } else if self.duration > 0. {
+            let position = elapsed / self.duration;
+            (position.floor(), position.fract())
+        } else {
+            (0., 1.)
+        };
+
+        let is_odd = iteration % 2. == 1.;
+        let forwards = match self.direction {
+            AnimationDirection::Normal => true,
+            AnimationDirection::Reverse => false,
+            AnimationDirection::Alternate => !is_odd,
+            AnimationDirection::AlternateReverse => is_odd,
+        };
+        Some(if forwards { fraction } else { 1. - fraction })
+    }
+
+    /// Compute the interpolated property values at the given position within the keyframes.
+    fn sample(&self, progress: f32) -> Vec<StyleProp> {
+        let mut result: Vec<StyleProp> = Vec::new();
+        for keyframe in self.keyframes.iter() {
+            for prop in keyframe.props.iter() {
+                let kind = discriminant(prop);
+                if result.iter().any(|p| discriminant(p) == kind) {
+                    continue;
+                }
+
+                // Find the keyframes on either side of the current position which set this
+                // property. If there is only one, its value is held.
+                let mut before: Option<(f32, &StyleProp)> = None;
+                let mut after: Option<(f32, &StyleProp)> = None;
+                for kf in self.keyframes.iter() {
+                    let Some(value) = kf.props.iter().rev().find(|p| discriminant(*p) == kind)
+                    else {
+                        continue;
+                    };
+                    if kf.offset <= progress {
+                        before = Some((kf.offset, value));
+                    } else if after.is_none() {
+                        after = Some((kf.offset, value));
+                    }
+                }
+
+                result.push(match (before, after) {
+                    (Some((start, from)), Some((end, to))) => {
+                        let t = self.timing.eval((progress - start) / (end - start));
+                        interpolate(from, to, t)
+                    }
+                    (Some((_, value)), None) | (None, Some((_, value))) => value.clone(),
+                    (None, None) => unreachable!(),
+                });
+            }
+        }
+        result
+    }
+}
+
+/// Entity event sent when a keyframe animation begins playing, after its delay.
+#[derive(Clone, Event, EntityEvent)]
+pub struct AnimationStart {
+    /// Event target
+    #[target]
+    pub target: Entity,
+
+    /// Name of the animation
+    pub name: String,
+}
+
+/// Entity event sent when a keyframe animation has finished its last iteration.
+#[derive(Clone, Event, EntityEvent)]
+pub struct AnimationEnd {
+    /// Event target
+    #[target]
+    pub target: Entity,
+
+    /// Name of the animation
+    pub name: String,
+}
+
+pub(crate) struct AnimationState {
+    animation: Animation,
+    clock: f32,
+    started: bool,
+    ended: bool,
+}
+
+/// The keyframe animations running on an element, along with the computed style that the
+/// animated values are applied on top of.
+#[derive(Component)]
+#[doc(hidden)]
+pub struct KeyframeAnimations {
+    pub(crate) base: ComputedStyle,
+    pub(crate) animations: Vec<AnimationState>,
+}
+
+impl KeyframeAnimations {
+    /// Returns the computed style with the current keyframe values applied.
+    fn animated_style(&self) -> ComputedStyle {
+        let mut computed = self.base.clone();
+        for state in self.animations.iter() {
+            if let Some(progress) = state.animation.progress(state.clock) {
+                StyleSet::apply_attrs_to(&state.animation.sample(progress), &mut computed);
+            }
+        }
+        computed
+    }
+}
+
+/// Start or stop keyframe animations on an element when the set of animations in its computed
+/// style changes. Returns the style with the current keyframe values applied.
+pub(crate) fn update_animations(
+    world: &mut World,
+    entity: Entity,
+    computed: ComputedStyle,
+) -> ComputedStyle {
+    let Some(mut e) = world.get_entity_mut(entity) else {
+        return computed;
+    };
+    if computed.animations.is_empty() {
+        e.remove::<KeyframeAnimations>();
+        return computed;
+    }
+
+    let mut prev = e
+        .take::<KeyframeAnimations>()
+        .map(|anims| anims.animations)
+        .unwrap_or_default();
+    let animations = computed
+        .animations
+        .iter()
+        .map(|animation| {
+            match prev
+                .iter()
+                .position(|state| state.animation.name == animation.name)
+            {
+                Some(index) => {
+                    // Keep the clock of an animation which is already playing.
+                    let mut state = prev.swap_remove(index);
+                    state.animation = animation.clone();
+                    state
+                }
+                None => AnimationState {
+                    animation: animation.clone(),
+                    clock: 0.,
+                    started: false,
+                    ended: false,
+                },
+            }
+        })
+        .collect();
+
+    let anims = KeyframeAnimations {
+        base: computed,
+        animations,
+    };
+    let animated = anims.animated_style();
+    e.insert(anims);
+    animated
+}
+
+/// Command which applies the current frame of an element's keyframe animations.
+struct ApplyAnimationFrame {
+    entity: Entity,
+}
+
+impl Command for ApplyAnimationFrame {
+    fn apply(self, world: &mut World) {
+        let Some(anims) = world.get::<KeyframeAnimations>(self.entity) else {
+            return;
+        };
+        UpdateComputedStyle {
+            entity: self.entity,
+            computed: anims.animated_style(),
+        }
+        .apply_styles(world);
+    }
+}
+
+#[doc(hidden)]
+pub fn animate_keyframes(
+    mut query: Query<(Entity, &mut KeyframeAnimations)>,
+    mut commands: Commands,
+    mut start_events: EventWriter<AnimationStart>,
+    mut end_events: EventWriter<AnimationEnd>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut anims) in query.iter_mut() {
+        let mut changed = false;
+        for state in anims.animations.iter_mut() {
+            if state.ended {
+                continue;
+            }
+            state.clock += delta;
+            changed = true;
+            if !state.started && state.clock >= state.animation.delay {
+                state.started = true;
+                start_events.send(AnimationStart {
+                    target: entity,
+                    name: state.animation.name.clone(),
+                });
+            }
+            if state.clock >= state.animation.delay + state.animation.active_duration() {
+                state.ended = true;
+                end_events.send(AnimationEnd {
+                    target: entity,
+                    name: state.animation.name.clone(),
+                });
+            }
+        }
+        if changed {
+            commands.add(ApplyAnimationFrame { entity });
+        }
+    }
+}
+
+/// Interpolate between two values of the same style property. Properties which can't be
+/// interpolated switch from one value to the other halfway through.
+fn interpolate(from: &StyleProp, to: &StyleProp, t: f32) -> StyleProp {
+    let lerp = |a: f32, b: f32| a + (b - a) * t;
+    let color = |a: &Option<Color>, b: &Option<Color>| {
+        Some(ColorSpace::Oklab.mix(a.unwrap_or(Color::NONE), b.unwrap_or(Color::NONE), t))
+    };
+    let val = |a: &ui::Val, b: &ui::Val| lerp_val(*a, *b, t);
+    let rect = |a: &ui::UiRect, b: &ui::UiRect| ui::UiRect {
+        left: val(&a.left, &b.left),
+        right: val(&a.right, &b.right),
+        top: val(&a.top, &b.top),
+        bottom: val(&a.bottom, &b.bottom),
+    };
+    match (from, to) {
+        (StyleProp::BackgroundColor(a), StyleProp::BackgroundColor(b)) => {
+            StyleProp::BackgroundColor(color(a, b))
+        }
+        (StyleProp::BorderColor(a), StyleProp::BorderColor(b)) => {
+            StyleProp::BorderColor(color(a, b))
+        }
+        (StyleProp::Color(a), StyleProp::Color(b)) => StyleProp::Color(color(a, b)),
+        (StyleProp::Opacity(a), StyleProp::Opacity(b)) => StyleProp::Opacity(lerp(*a, *b)),
+        (StyleProp::OutlineColor(a), StyleProp::OutlineColor(b)) => {
+            StyleProp::OutlineColor(color(a, b))
+        }
+
+        (StyleProp::Left(a), StyleProp::Left(b)) => StyleProp::Left(val(a, b)),
+        (StyleProp::Right(a), StyleProp::Right(b)) => StyleProp::Right(val(a, b)),
+        (StyleProp::Top(a), StyleProp::Top(b)) => StyleProp::Top(val(a, b)),
+        (StyleProp::Bottom(a), StyleProp::Bottom(b)) => StyleProp::Bottom(val(a, b)),
+        (StyleProp::Width(a), StyleProp::Width(b)) => StyleProp::Width(val(a, b)),
+        (StyleProp::Height(a), StyleProp::Height(b)) => StyleProp::Height(val(a, b)),
+        (StyleProp::MinWidth(a), StyleProp::MinWidth(b)) => StyleProp::MinWidth(val(a, b)),
+        (StyleProp::MinHeight(a), StyleProp::MinHeight(b)) => StyleProp::MinHeight(val(a, b)),
+        (StyleProp::MaxWidth(a), StyleProp::MaxWidth(b)) => StyleProp::MaxWidth(val(a, b)),
+        (StyleProp::MaxHeight(a), StyleProp::MaxHeight(b)) => StyleProp::MaxHeight(val(a, b)),
+
+        (StyleProp::Margin(a), StyleProp::Margin(b)) => StyleProp::Margin(rect(a, b)),
+        (StyleProp::MarginLeft(a), StyleProp::MarginLeft(b)) => StyleProp::MarginLeft(val(a, b)),
+        (StyleProp::MarginRight(a), StyleProp::MarginRight(b)) => StyleProp::MarginRight(val(a, b)),
+        (StyleProp::MarginTop(a), StyleProp::MarginTop(b)) => StyleProp::MarginTop(val(a, b)),
+        (StyleProp::MarginBottom(a), StyleProp::MarginBottom(b)) => {
+            StyleProp::MarginBottom(val(a, b))
+        }
+        (StyleProp::Padding(a), StyleProp::Padding(b)) => StyleProp::Padding(rect(a, b)),
+        (StyleProp::PaddingLeft(a), StyleProp::PaddingLeft(b)) => StyleProp::PaddingLeft(val(a, b)),
+        (StyleProp::PaddingRight(a), StyleProp::PaddingRight(b)) => {
+            StyleProp::PaddingRight(val(a, b))
+        }
+        (StyleProp::PaddingTop(a), StyleProp::PaddingTop(b)) => StyleProp::PaddingTop(val(a, b)),
+        (StyleProp::PaddingBottom(a), StyleProp::PaddingBottom(b)) => {
+            StyleProp::PaddingBottom(val(a, b))
+        }
+        (StyleProp::Border(a), StyleProp::Border(b)) => StyleProp::Border(rect(a, b)),
+        (StyleProp::BorderLeft(a), StyleProp::BorderLeft(b)) => StyleProp::BorderLeft(val(a, b)),
+        (StyleProp::BorderRight(a), StyleProp::BorderRight(b)) => StyleProp::BorderRight(val(a, b)),
+        (StyleProp::BorderTop(a), StyleProp::BorderTop(b)) => StyleProp::BorderTop(val(a, b)),
+        (StyleProp::BorderBottom(a), StyleProp::BorderBottom(b)) => {
+            StyleProp::BorderBottom(val(a, b))
+        }
+
+        (StyleProp::FlexGrow(a), StyleProp::FlexGrow(b)) => StyleProp::FlexGrow(lerp(*a, *b)),
+        (StyleProp::FlexShrink(a), StyleProp::FlexShrink(b)) => StyleProp::FlexShrink(lerp(*a, *b)),
+        (StyleProp::FlexBasis(a), StyleProp::FlexBasis(b)) => StyleProp::FlexBasis(val(a, b)),
+        (StyleProp::RowGap(a), StyleProp::RowGap(b)) => StyleProp::RowGap(val(a, b)),
+        (StyleProp::ColumnGap(a), StyleProp::ColumnGap(b)) => StyleProp::ColumnGap(val(a, b)),
+        (StyleProp::Gap(a), StyleProp::Gap(b)) => StyleProp::Gap(val(a, b)),
+
+        (StyleProp::FontSize(a), StyleProp::FontSize(b)) => StyleProp::FontSize(lerp(*a, *b)),
+        (StyleProp::OutlineWidth(a), StyleProp::OutlineWidth(b)) => {
+            StyleProp::OutlineWidth(val(a, b))
+        }
+        (StyleProp::OutlineOffset(a), StyleProp::OutlineOffset(b)) => {
+            StyleProp::OutlineOffset(val(a, b))
+        }
+
+        (StyleProp::Scale(a), StyleProp::Scale(b)) => StyleProp::Scale(lerp(*a, *b)),
+        (StyleProp::ScaleX(a), StyleProp::ScaleX(b)) => StyleProp::ScaleX(lerp(*a, *b)),
+        (StyleProp::ScaleY(a), StyleProp::ScaleY(b)) => StyleProp::ScaleY(lerp(*a, *b)),
+        (StyleProp::Rotation(a), StyleProp::Rotation(b)) => StyleProp::Rotation(lerp(*a, *b)),
+        (StyleProp::Translation(a), StyleProp::Translation(b)) => {
+            StyleProp::Translation(a.lerp(*b, t))
+        }
+
+        _ if t < 0.5 => from.clone(),
+        _ => to.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pulse() -> Animation {
+        Animation {
+            name: "pulse".to_string(),
+            keyframes: vec![
+                Keyframe::build(0., |ss| ss.scale(1.).width(10.)),
+                Keyframe::build(0.5, |ss| ss.scale(2.)),
+                Keyframe::build(1., |ss| ss.scale(1.).width(20.)),
+            ],
+            duration: 2.,
+            ..default()
+        }
+    }
+
+    fn scale(props: &[StyleProp]) -> f32 {
+        props
+            .iter()
+            .find_map(|p| match p {
+                StyleProp::Scale(s) => Some(*s),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sample() {
+        let anim = pulse();
+        let props = anim.sample(0.25);
+        assert_eq!(props.len(), 2);
+        assert_eq!(scale(&props), 1.5);
+        assert!(matches!(props[1], StyleProp::Width(ui::Val::Px(w)) if w == 12.5));
+        assert_eq!(scale(&anim.sample(0.75)), 1.5);
+        assert_eq!(scale(&anim.sample(1.)), 1.);
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut anim = pulse();
+        anim.iterations = 3.;
+        anim.direction = AnimationDirection::Alternate;
+        assert_eq!(anim.progress(0.5), Some(0.25));
+        assert_eq!(anim.progress(2.5), Some(0.75));
+        assert_eq!(anim.progress(4.5), Some(0.25));
+        assert_eq!(anim.progress(6.5), None);
+
+        anim.fill_mode = AnimationFillMode::Forwards;
+        assert_eq!(anim.progress(6.5), Some(1.));
+
+        anim.delay = 1.;
+        assert_eq!(anim.progress(0.5), None);
+        anim.fill_mode = AnimationFillMode::Both;
+        assert_eq!(anim.progress(0.5), Some(0.));
+    }
+}
@@ -3,18 +3,30 @@
 use bevy::{
     asset::AssetPath,
     log::error,
-    math::Vec3,
+    math::{IVec2, Vec3},
     prelude::Color,
     ui::{self, ZIndex},
 };
 
-use crate::{PointerEvents, StyleProp};
+use crate::{Cursor, PointerEvents, StyleProp};
 
-use super::{selector::Selector, style_props::SelectorList, transition::Transition};
+use super::{
+    animation::Animation,
+    decoration::{BorderRadius, BoxShadow, Gradient},
+    selector::Selector,
+    style_props::SelectorList,
+    transition::Transition,
+    vars::{StyleVar, VarProperty, VarValue},
+};
 
 /// Trait that represents a CSS color
 pub trait ColorParam {
     fn to_val(self) -> Option<Color>;
+
+    /// If this is a reference to a style variable, returns the name of the variable.
+    fn var_name(&self) -> Option<&str> {
+        None
+    }
 }
 
 impl ColorParam for Option<Color> {
@@ -35,9 +47,24 @@ impl ColorParam for &str {
     }
 }
 
+impl ColorParam for StyleVar {
+    fn to_val(self) -> Option<Color> {
+        None
+    }
+
+    fn var_name(&self) -> Option<&str> {
+        Some(&self.0)
+    }
+}
+
 /// Trait that represents a CSS "length"
 pub trait LengthParam {
     fn to_val(self) -> ui::Val;
+
+    /// If this is a reference to a style variable, returns the name of the variable.
+    fn var_name(&self) -> Option<&str> {
+        None
+    }
 }
 
 impl LengthParam for ui::Val {
@@ -58,6 +85,16 @@ impl LengthParam for i32 {
     }
 }
 
+impl LengthParam for StyleVar {
+    fn to_val(self) -> ui::Val {
+        ui::Val::Auto
+    }
+
+    fn var_name(&self) -> Option<&str> {
+        Some(&self.0)
+    }
+}
+
 /// Trait that represents a CSS Z-index
 pub trait ZIndexParam {
     fn to_val(self) -> Option<ZIndex>;
@@ -78,6 +115,11 @@ impl ZIndexParam for i32 {
 /// Trait that represents CSS edge widths (margin, padding, etc.)
 pub trait UiRectParam {
     fn to_uirect(self) -> ui::UiRect;
+
+    /// If this is a reference to a style variable, returns the name of the variable.
+    fn var_name(&self) -> Option<&str> {
+        None
+    }
 }
 
 impl UiRectParam for ui::UiRect {
@@ -110,6 +152,16 @@ impl<H: LengthParam, V: LengthParam> UiRectParam for (H, V) {
     }
 }
 
+impl UiRectParam for StyleVar {
+    fn to_uirect(self) -> ui::UiRect {
+        ui::UiRect::default()
+    }
+
+    fn var_name(&self) -> Option<&str> {
+        Some(&self.0)
+    }
+}
+
 pub struct StyleBuilder {
     pub(crate) props: Vec<StyleProp>,
     pub(crate) selectors: SelectorList,
@@ -123,24 +175,65 @@ impl StyleBuilder {
         }
     }
 
+    fn push_color(
+        &mut self,
+        color: impl ColorParam,
+        prop: fn(Option<Color>) -> StyleProp,
+    ) -> &mut Self {
+        self.props.push(match color.var_name() {
+            Some(name) => StyleProp::VarRef(name.to_string(), VarProperty::Color(prop)),
+            None => prop(color.to_val()),
+        });
+        self
+    }
+
+    fn push_length(
+        &mut self,
+        length: impl LengthParam,
+        prop: fn(ui::Val) -> StyleProp,
+    ) -> &mut Self {
+        self.props.push(match length.var_name() {
+            Some(name) => StyleProp::VarRef(name.to_string(), VarProperty::Length(prop)),
+            None => prop(length.to_val()),
+        });
+        self
+    }
+
+    fn push_rect(
+        &mut self,
+        rect: impl UiRectParam,
+        prop: fn(ui::UiRect) -> StyleProp,
+    ) -> &mut Self {
+        self.props.push(match rect.var_name() {
+            Some(name) => StyleProp::VarRef(name.to_string(), VarProperty::Rect(prop)),
+            None => prop(rect.to_uirect()),
+        });
+        self
+    }
+
+    /// Define a style variable, which can be referenced by this element and its descendants
+    /// using [`var`](super::vars::var).
+    pub fn var(&mut self, name: &str, value: impl Into<VarValue>) -> &mut Self {
+        self.props
+            .push(StyleProp::Var(name.to_string(), value.into()));
+        self
+    }
+
     pub fn background_image(&mut self, img: Option<AssetPath<'static>>) -> &mut Self {
         self.props.push(StyleProp::BackgroundImage(img));
         self
     }
 
     pub fn background_color(&mut self, color: impl ColorParam) -> &mut Self {
-        self.props.push(StyleProp::BackgroundColor(color.to_val()));
-        self
+        self.push_color(color, StyleProp::BackgroundColor)
     }
 
     pub fn border_color(&mut self, color: impl ColorParam) -> &mut Self {
-        self.props.push(StyleProp::BorderColor(color.to_val()));
-        self
+        self.push_color(color, StyleProp::BorderColor)
     }
 
     pub fn color(&mut self, color: impl ColorParam) -> &mut Self {
-        self.props.push(StyleProp::Color(color.to_val()));
-        self
+        self.push_color(color, StyleProp::Color)
     }
 
     pub fn z_index(&mut self, index: impl ZIndexParam) -> &mut Self {
@@ -179,130 +272,105 @@ impl StyleBuilder {
     }
 
     pub fn left(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::Left(length.to_val()));
-        self
+        self.push_length(length, StyleProp::Left)
     }
 
     pub fn right(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::Right(length.to_val()));
-        self
+        self.push_length(length, StyleProp::Right)
     }
 
     pub fn top(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::Top(length.to_val()));
-        self
+        self.push_length(length, StyleProp::Top)
     }
 
     pub fn bottom(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::Bottom(length.to_val()));
-        self
+        self.push_length(length, StyleProp::Bottom)
     }
 
     pub fn width(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::Width(length.to_val()));
-        self
+        self.push_length(length, StyleProp::Width)
     }
 
     pub fn height(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::Height(length.to_val()));
-        self
+        self.push_length(length, StyleProp::Height)
     }
 
     pub fn min_width(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::MinWidth(length.to_val()));
-        self
+        self.push_length(length, StyleProp::MinWidth)
     }
 
     pub fn min_height(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::MinHeight(length.to_val()));
-        self
+        self.push_length(length, StyleProp::MinHeight)
     }
 
     pub fn max_width(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::MaxWidth(length.to_val()));
-        self
+        self.push_length(length, StyleProp::MaxWidth)
     }
 
     pub fn max_height(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::MaxHeight(length.to_val()));
-        self
+        self.push_length(length, StyleProp::MaxHeight)
     }
 
     // pub aspect_ratio: StyleProp<f32>,
 
     pub fn margin(&mut self, rect: impl UiRectParam) -> &mut Self {
-        self.props.push(StyleProp::Margin(rect.to_uirect()));
-        self
+        self.push_rect(rect, StyleProp::Margin)
     }
 
     pub fn margin_left(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::MarginLeft(length.to_val()));
-        self
+        self.push_length(length, StyleProp::MarginLeft)
     }
 
     pub fn margin_right(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::MarginRight(length.to_val()));
-        self
+        self.push_length(length, StyleProp::MarginRight)
     }
 
     pub fn margin_top(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::MarginTop(length.to_val()));
-        self
+        self.push_length(length, StyleProp::MarginTop)
     }
 
     pub fn margin_bottom(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::MarginBottom(length.to_val()));
-        self
+        self.push_length(length, StyleProp::MarginBottom)
     }
 
     pub fn padding(&mut self, rect: impl UiRectParam) -> &mut Self {
-        self.props.push(StyleProp::Padding(rect.to_uirect()));
-        self
+        self.push_rect(rect, StyleProp::Padding)
     }
 
     pub fn padding_left(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::PaddingLeft(length.to_val()));
-        self
+        self.push_length(length, StyleProp::PaddingLeft)
     }
 
     pub fn padding_right(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::PaddingRight(length.to_val()));
-        self
+        self.push_length(length, StyleProp::PaddingRight)
     }
 
     pub fn padding_top(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::PaddingTop(length.to_val()));
-        self
+        self.push_length(length, StyleProp::PaddingTop)
     }
 
     pub fn padding_bottom(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::PaddingBottom(length.to_val()));
-        self
+        self.push_length(length, StyleProp::PaddingBottom)
     }
 
     pub fn border(&mut self, rect: impl UiRectParam) -> &mut Self {
-        self.props.push(StyleProp::Border(rect.to_uirect()));
-        self
+        self.push_rect(rect, StyleProp::Border)
     }
 
     pub fn border_left(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::BorderLeft(length.to_val()));
-        self
+        self.push_length(length, StyleProp::BorderLeft)
     }
 
     pub fn border_right(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::BorderRight(length.to_val()));
-        self
+        self.push_length(length, StyleProp::BorderRight)
     }
 
     pub fn border_top(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::BorderTop(length.to_val()));
-        self
+        self.push_length(length, StyleProp::BorderTop)
     }
 
     pub fn border_bottom(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::BorderBottom(length.to_val()));
-        self
+        self.push_length(length, StyleProp::BorderBottom)
     }
 
     pub fn flex_direction(&mut self, dir: ui::FlexDirection) -> &mut Self {
@@ -328,23 +396,19 @@ impl StyleBuilder {
     }
 
     pub fn flex_basis(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::FlexBasis(length.to_val()));
-        self
+        self.push_length(length, StyleProp::FlexBasis)
     }
 
     pub fn row_gap(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::RowGap(length.to_val()));
-        self
+        self.push_length(length, StyleProp::RowGap)
     }
 
     pub fn column_gap(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::ColumnGap(length.to_val()));
-        self
+        self.push_length(length, StyleProp::ColumnGap)
     }
 
     pub fn gap(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::Gap(length.to_val()));
-        self
+        self.push_length(length, StyleProp::Gap)
     }
 
     pub fn align_items(&mut self, align: ui::AlignItems) -> &mut Self {
@@ -445,18 +509,15 @@ impl StyleBuilder {
     // LineBreak(BreakLineOn),
 
     pub fn outline_color(&mut self, color: impl ColorParam) -> &mut Self {
-        self.props.push(StyleProp::OutlineColor(color.to_val()));
-        self
+        self.push_color(color, StyleProp::OutlineColor)
     }
 
     pub fn outline_width(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::OutlineWidth(length.to_val()));
-        self
+        self.push_length(length, StyleProp::OutlineWidth)
     }
 
     pub fn outline_offset(&mut self, length: impl LengthParam) -> &mut Self {
-        self.props.push(StyleProp::OutlineOffset(length.to_val()));
-        self
+        self.push_length(length, StyleProp::OutlineOffset)
     }
 
     pub fn pointer_events(&mut self, pe: PointerEvents) -> &mut Self {
@@ -464,11 +525,54 @@ impl StyleBuilder {
         self
     }
 
+    pub fn cursor(&mut self, cursor: Cursor) -> &mut Self {
+        self.props.push(StyleProp::Cursor(cursor));
+        self
+    }
+
+    pub fn cursor_image(&mut self, path: AssetPath<'static>) -> &mut Self {
+        self.props.push(StyleProp::CursorImage(path));
+        self
+    }
+
+    pub fn cursor_offset(&mut self, offset: IVec2) -> &mut Self {
+        self.props.push(StyleProp::CursorOffset(offset));
+        self
+    }
+
     pub fn font(&mut self, path: Option<AssetPath<'static>>) -> &mut Self {
         self.props.push(StyleProp::Font(path));
         self
     }
 
+    /// Set the opacity of the element and all of its descendants, from 0 (transparent) to 1
+    /// (opaque). Opacity is inherited multiplicatively, so a child with an opacity of 0.5
+    /// inside a parent with an opacity of 0.5 is drawn at 25% opacity.
+    pub fn opacity(&mut self, opacity: f32) -> &mut Self {
+        self.props.push(StyleProp::Opacity(opacity));
+        self
+    }
+
+    /// Round the corners of the element's background and border.
+    pub fn border_radius(&mut self, radius: impl Into<BorderRadius>) -> &mut Self {
+        self.props
+            .push(StyleProp::BorderRadius(Some(radius.into())));
+        self
+    }
+
+    /// Draw a shadow around the outside of the element.
+    pub fn box_shadow(&mut self, shadow: impl Into<Option<BoxShadow>>) -> &mut Self {
+        self.props.push(StyleProp::BoxShadow(shadow.into()));
+        self
+    }
+
+    /// Fill the element's background with a gradient instead of a solid color.
+    pub fn background_gradient(&mut self, gradient: impl Into<Option<Gradient>>) -> &mut Self {
+        self.props
+            .push(StyleProp::BackgroundGradient(gradient.into()));
+        self
+    }
+
     pub fn font_size(&mut self, val: f32) -> &mut Self {
         self.props.push(StyleProp::FontSize(val));
         self
@@ -505,6 +609,34 @@ impl StyleBuilder {
         self
     }
 
+    /// Play a keyframe animation on the element. This replaces any animations set previously;
+    /// use [`animations`](Self::animations) to play several at once.
+    pub fn animation(&mut self, animation: Animation) -> &mut Self {
+        self.props.push(StyleProp::Animation(vec![animation]));
+        self
+    }
+
+    /// Play several keyframe animations on the element at once.
+    pub fn animations(&mut self, animations: &[Animation]) -> &mut Self {
+        self.props.push(StyleProp::Animation(Vec::from(animations)));
+        self
+    }
+
+    /// Mark properties as important, so that they take precedence over all properties which
+    /// aren't, regardless of which style they come from and how specific its selector is.
+    pub fn important(
+        &mut self,
+        builder_fn: impl FnOnce(&mut StyleBuilder) -> &mut StyleBuilder,
+    ) -> &mut Self {
+        let mut builder = StyleBuilder::new();
+        builder_fn(&mut builder);
+        if !builder.selectors.is_empty() {
+            error!("Selectors can't be nested within important properties");
+        }
+        self.props.push(StyleProp::Important(builder.props));
+        self
+    }
+
     /// Add a selector expression to this style declaration.
     pub fn selector(
         &mut self,
@@ -523,4 +655,31 @@ impl StyleBuilder {
         }
         self
     }
+
+    /// Add styles which only apply when the window matches a media query, such as
+    /// `(max-width: 800px) and (orientation: portrait)`. This is equivalent to a selector
+    /// which begins with `@media`.
+    pub fn media(
+        &mut self,
+        query: &str,
+        builder_fn: impl FnOnce(&mut StyleBuilder) -> &mut StyleBuilder,
+    ) -> &mut Self {
+        self.selector(&format!("@media {}", query), builder_fn)
+    }
+
+    /// Add styles which only apply when the nearest [`Container`](crate::Container) ancestor
+    /// satisfies a condition, such as `">= 300px width"` or `"width < 300px"`. Multiple
+    /// conditions can be combined with `and` if each is enclosed in parentheses. This is
+    /// equivalent to a selector which begins with `@container`.
+    pub fn container(
+        &mut self,
+        query: &str,
+        builder_fn: impl FnOnce(&mut StyleBuilder) -> &mut StyleBuilder,
+    ) -> &mut Self {
+        if query.starts_with('(') {
+            self.selector(&format!("@container {}", query), builder_fn)
+        } else {
+            self.selector(&format!("@container ({})", query), builder_fn)
+        }
+    }
 }
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+
+use super::{
+    computed::ComputedStyle,
+    selector::Specificity,
+    selector_matcher::SelectorMatcher,
+    style_props::{StyleProp, StyleSet},
+};
+
+/// A block of style properties which applies to an element, along with the information needed
+/// to decide its precedence.
+struct CascadeRule<'a> {
+    origin: usize,
+    specificity: Specificity,
+    props: &'a [StyleProp],
+}
+
+/// Collects the style rules which apply to an element from all of its styles, and applies
+/// them in cascade order:
+///
+/// * Rules from a later origin take precedence over rules from an earlier one. Descendant
+///   styles inherited from ancestors are one origin, and the element's own styles are another.
+/// * Within an origin, more specific selectors take precedence over less specific ones.
+///   Unconditional properties have the lowest specificity.
+/// * Rules with the same origin and specificity are applied in declaration order, so the last
+///   one wins.
+///
+/// Properties marked as important are applied after all of the other properties, in the same
+/// order, so that they take precedence over any property which isn't important.
+#[derive(Default)]
+pub(crate) struct Cascade<'a> {
+    rules: Vec<CascadeRule<'a>>,
+    origin: usize,
+}
+
+impl<'a> Cascade<'a> {
+    /// Start a new origin. Rules added after this take precedence over all of the rules added
+    /// before it, regardless of their specificity.
+    pub fn next_origin(&mut self) {
+        self.origin += 1;
+    }
+
+    /// Add the unconditional properties of a style, and the properties of each of its selectors
+    /// which match the entity.
+    pub fn add(&mut self, style: &'a StyleSet, matcher: &SelectorMatcher, entity: &Entity) {
+        self.push(Specificity::default(), &style.props);
+        for (selector, props) in style.selectors.iter() {
+            if matcher.selector_match(selector, entity) {
+                self.push(selector.specificity(), props);
+            }
+        }
+    }
+
+    fn push(&mut self, specificity: Specificity, props: &'a [StyleProp]) {
+        if !props.is_empty() {
+            self.rules.push(CascadeRule {
+                origin: self.origin,
+                specificity,
+                props,
+            });
+        }
+    }
+
+    /// Apply the rules to a computed style. Variable definitions are applied first, so that
+    /// variable references can be resolved regardless of where they appear.
+    pub fn apply_to(mut self, computed: &mut ComputedStyle) {
+        // The sort is stable, so rules with equal precedence stay in declaration order.
+        self.rules
+            .sort_by_key(|rule| (rule.origin, rule.specificity));
+        for rule in self.rules.iter() {
+            StyleSet::apply_vars(rule.props, computed);
+        }
+        for rule in self.rules.iter() {
+            StyleSet::apply_attrs_to(rule.props, computed);
+        }
+        for rule in self.rules.iter() {
+            for prop in rule.props.iter() {
+                if let StyleProp::Important(props) = prop {
+                    StyleSet::apply_attrs_to(props, computed);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn background(cascade: Cascade) -> Option<Color> {
+        let mut computed = ComputedStyle::new();
+        cascade.apply_to(&mut computed);
+        computed.background_color
+    }
+
+    #[test]
+    fn test_specificity_order() {
+        let hover = [StyleProp::BackgroundColor(Some(Color::RED))];
+        let pressed = [StyleProp::BackgroundColor(Some(Color::BLUE))];
+        let base = [StyleProp::BackgroundColor(Some(Color::GRAY))];
+
+        // The more specific rule wins, even though it was declared first.
+        let mut cascade = Cascade::default();
+        cascade.push(Specificity::new(2, 0), &pressed);
+        cascade.push(Specificity::new(1, 0), &hover);
+        cascade.push(Specificity::default(), &base);
+        assert_eq!(background(cascade), Some(Color::BLUE));
+
+        // With equal specificity, the last rule wins.
+        let mut cascade = Cascade::default();
+        cascade.push(Specificity::new(1, 0), &pressed);
+        cascade.push(Specificity::new(1, 0), &hover);
+        assert_eq!(background(cascade), Some(Color::RED));
+    }
+
+    #[test]
+    fn test_origin_order() {
+        let inherited = [StyleProp::BackgroundColor(Some(Color::RED))];
+        let own = [StyleProp::BackgroundColor(Some(Color::BLUE))];
+
+        // A later origin wins regardless of specificity.
+        let mut cascade = Cascade::default();
+        cascade.push(Specificity::new(3, 1), &inherited);
+        cascade.next_origin();
+        cascade.push(Specificity::default(), &own);
+        assert_eq!(background(cascade), Some(Color::BLUE));
+    }
+
+    #[test]
+    fn test_important() {
+        let important = [StyleProp::Important(vec![StyleProp::BackgroundColor(
+            Some(Color::RED),
+        )])];
+        let own = [StyleProp::BackgroundColor(Some(Color::BLUE))];
+
+        let mut cascade = Cascade::default();
+        cascade.push(Specificity::default(), &important);
+        cascade.next_origin();
+        cascade.push(Specificity::new(1, 0), &own);
+        assert_eq!(background(cascade), Some(Color::RED));
+    }
+
+    #[test]
+    fn test_vars() {
+        let var = [StyleProp::Var("bg".into(), Color::GREEN.into())];
+        let var_ref = [StyleProp::VarRef(
+            "bg".into(),
+            crate::VarProperty::Color(StyleProp::BackgroundColor),
+        )];
+
+        // Variables are resolved even when defined by a rule which is applied later.
+        let mut cascade = Cascade::default();
+        cascade.push(Specificity::default(), &var_ref);
+        cascade.push(Specificity::new(1, 0), &var);
+        assert_eq!(background(cascade), Some(Color::GREEN));
+    }
+}
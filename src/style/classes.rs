@@ -8,15 +8,25 @@ use impl_trait_for_tuples::*;
 #[derive(Component, Default)]
 pub struct ElementClasses(pub HashSet<String>);
 
+/// Marker component which indicates that an element is disabled. Style selectors can use the
+/// `:disabled` pseudo-class to match it.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct Disabled;
+
+/// Marker component which indicates that an element, such as a checkbox, is checked. Style
+/// selectors can use the `:checked` pseudo-class to match it.
+#[derive(Component, Default, Clone, Copy, Debug)]
+pub struct Checked;
+
 impl ElementClasses {
-    /// Add a classname to this element. Be careful using this method with `.class_names()`,
-    /// because the latter will overwrite any changes you make with this method.
+    /// Add a classname to this element. If the same class name is also set with
+    /// `.class_names()`, the latter will remove it when it is no longer listed.
     pub fn add_class(&mut self, cls: &str) {
         self.0.insert(cls.to_string());
     }
 
-    /// Remove a classname from this element. Be careful using this method with `.class_names()`,
-    /// because the latter will overwrite any changes you make with this method.
+    /// Remove a classname from this element. If the same class name is also set with
+    /// `.class_names()`, the latter will add it back the next time it is updated.
     pub fn remove_class(&mut self, cls: &str) {
         self.0.remove(cls);
     }
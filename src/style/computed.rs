@@ -1,8 +1,11 @@
+use super::animation::{update_animations, Animation};
+use super::decoration::{BorderRadius, BoxShadow, Gradient};
 use super::style_props::PointerEvents;
 use super::transition::{
-    AnimatedBackgroundColor, AnimatedBorderColor, AnimatedLayout, AnimatedLayoutProp,
-    AnimatedTransform, Transition, TransitionProperty, TransitionState,
+    AnimatedColor, AnimatedColors, AnimatedLayout, AnimatedLayoutProp, AnimatedTransform,
+    LayoutContext, Transition, TransitionProperty, TransitionState,
 };
+use super::vars::VarValue;
 use bevy::asset::AssetPath;
 use bevy::ecs::system::Command;
 use bevy::prelude::*;
@@ -12,6 +15,10 @@ use bevy::ui::ContentSize;
 use bevy::utils::HashMap;
 use bevy_mod_picking::prelude::Pickable;
 
+use crate::material::{material_colors, update_material, StyleMaterial, StyleMaterialParams};
+use crate::view::view_hidden::ViewHidden;
+use crate::{Cursor, ElementCursor, RichTextSpans};
+
 /// A computed style represents the composition of one or more `ElementStyle`s.
 #[derive(Default, Clone, Debug)]
 #[doc(hidden)]
@@ -26,6 +33,10 @@ pub struct ComputedStyle {
     pub font_handle: Option<Handle<Font>>,
     pub line_break: Option<BreakLineOn>,
 
+    // Opacity of this element, and the combined opacity of its ancestors
+    pub opacity: Option<f32>,
+    pub inherited_opacity: Option<f32>,
+
     // pub text_style: TextStyle,
     pub border_color: Option<Color>,
     pub background_color: Option<Color>,
@@ -34,6 +45,11 @@ pub struct ComputedStyle {
     pub outline_offset: Val,
     pub z_index: Option<ZIndex>,
 
+    // Decorations drawn by the style material
+    pub border_radius: Option<BorderRadius>,
+    pub box_shadow: Option<BoxShadow>,
+    pub background_gradient: Option<Gradient>,
+
     // Transform properties
     pub scale_x: Option<f32>,
     pub scale_y: Option<f32>,
@@ -49,8 +65,19 @@ pub struct ComputedStyle {
     // Picking properties
     pub pickable: Option<PointerEvents>,
 
+    // Cursor properties
+    pub cursor: Option<Cursor>,
+    pub cursor_image: Option<AssetPath<'static>>,
+    pub cursor_offset: Option<IVec2>,
+
     // Transitiions
     pub transitions: Vec<Transition>,
+
+    // Keyframe animations
+    pub animations: Vec<Animation>,
+
+    // Style variables visible to this element
+    pub vars: HashMap<String, VarValue>,
 }
 
 impl ComputedStyle {
@@ -58,6 +85,18 @@ impl ComputedStyle {
     pub fn new() -> Self {
         Self { ..default() }
     }
+
+    /// The opacity that the element is drawn with, including the opacity of its ancestors.
+    pub fn effective_opacity(&self) -> f32 {
+        self.inherited_opacity.unwrap_or(1.) * self.opacity.unwrap_or(1.)
+    }
+
+    /// Whether the element has any decorations which need to be drawn with a [`StyleMaterial`].
+    pub fn is_decorated(&self) -> bool {
+        self.border_radius.is_some()
+            || self.box_shadow.is_some()
+            || self.background_gradient.is_some()
+    }
 }
 
 /// Custom command that updates the style of an entity.
@@ -67,38 +106,121 @@ pub struct UpdateComputedStyle {
 }
 
 impl Command for UpdateComputedStyle {
-    fn apply(self, world: &mut World) {
+    fn apply(mut self, world: &mut World) {
+        self.computed = update_animations(world, self.entity, self.computed);
+        self.apply_styles(world);
+    }
+}
+
+impl UpdateComputedStyle {
+    /// Apply the computed style to the entity's components, without affecting the keyframe
+    /// animations.
+    pub(crate) fn apply_styles(mut self, world: &mut World) {
+        let layout = if self
+            .computed
+            .transitions
+            .iter()
+            .any(|tr| tr.property.is_layout())
+        {
+            LayoutContext::new(world, self.entity)
+        } else {
+            LayoutContext::default()
+        };
+
+        let opacity = self.computed.effective_opacity();
+        let is_decorated = self.computed.is_decorated();
+
         let Some(mut e) = world.get_entity_mut(self.entity) else {
             return;
         };
 
-        let mut is_animated_bg_color = false;
-        let mut is_animated_border_color = false;
+        let mut is_animated_color = false;
         let mut is_animated_transform = false;
         let mut is_animated_layout = false;
 
         let mut next_style = self.computed.style;
 
+        // If the element has been hidden by a view, remember the computed display mode so that
+        // it can be restored later, but keep the element hidden.
+        if let Some(mut hidden) = e.get_mut::<ViewHidden>() {
+            hidden.display = next_style.display;
+            next_style.display = Display::None;
+        }
+
         self.computed
             .transitions
             .iter()
             .for_each(|tr| match tr.property {
                 TransitionProperty::Transform => is_animated_transform = true,
-                TransitionProperty::BackgroundColor => is_animated_bg_color = true,
-                TransitionProperty::BorderColor => is_animated_border_color = true,
-                TransitionProperty::Height
-                | TransitionProperty::Width
-                | TransitionProperty::Left
-                | TransitionProperty::Top
-                | TransitionProperty::Bottom
-                | TransitionProperty::Right
-                | TransitionProperty::BorderLeft
-                | TransitionProperty::BorderTop
-                | TransitionProperty::BorderRight
-                | TransitionProperty::BorderBottom => is_animated_layout = true,
+                TransitionProperty::BackgroundColor
+                | TransitionProperty::BorderColor
+                | TransitionProperty::Color
+                | TransitionProperty::OutlineColor => is_animated_color = true,
+                // Opacity is animated while computing styles, so that it can be inherited.
+                TransitionProperty::Opacity => {}
+                _ => is_animated_layout = true,
             });
 
-        let bg_image = self.computed.image_handle;
+        // The style material draws the background itself, so it can't be combined with an
+        // image.
+        let bg_image = self.computed.image_handle.filter(|_| !is_decorated);
+
+        // Opacity fades all of the element's colors. Images are tinted by the background
+        // color, so an image without one needs a white background to be faded.
+        if opacity < 1. {
+            let fade = |color: Color| color.with_a(color.a() * opacity);
+            if let Some(shadow) = self.computed.box_shadow.as_mut() {
+                shadow.color = fade(shadow.color);
+            }
+            if let Some(gradient) = self.computed.background_gradient.as_mut() {
+                for stop in gradient.stops_mut() {
+                    stop.color = fade(stop.color);
+                }
+            }
+            if self.computed.background_color.is_none() && bg_image.is_some() {
+                self.computed.background_color = Some(Color::WHITE);
+            }
+            self.computed.background_color = self.computed.background_color.map(fade);
+            self.computed.border_color = self.computed.border_color.map(fade);
+            self.computed.outline_color = self.computed.outline_color.map(fade);
+            self.computed.color = Some(fade(self.computed.color.unwrap_or(Color::WHITE)));
+        }
+
+        // Colors which are animated start from the color currently displayed, and an absent
+        // color is treated as transparent so that the element can fade in and out.
+        let is_rich_text = e.contains::<RichTextSpans>();
+        let mut prev_colors: Vec<(TransitionProperty, Option<Color>)> = Vec::new();
+        if is_animated_color {
+            let drawn_colors = material_colors(e.world(), self.entity);
+            for tr in self.computed.transitions.iter() {
+                let prev = match tr.property {
+                    TransitionProperty::BackgroundColor => {
+                        if self.computed.background_color.is_none() && bg_image.is_none() {
+                            self.computed.background_color = Some(Color::NONE);
+                        }
+                        e.get::<BackgroundColor>()
+                            .map(|bg| bg.0)
+                            .or_else(|| drawn_colors.map(|(bg, _)| bg))
+                    }
+                    TransitionProperty::BorderColor => {
+                        self.computed.border_color.get_or_insert(Color::NONE);
+                        e.get::<BorderColor>()
+                            .map(|bc| bc.0)
+                            .or_else(|| drawn_colors.map(|(_, bc)| bc))
+                    }
+                    TransitionProperty::Color if !is_rich_text => e
+                        .get::<Text>()
+                        .and_then(|text| text.sections.first())
+                        .map(|section| section.style.color),
+                    TransitionProperty::OutlineColor => {
+                        self.computed.outline_color.get_or_insert(Color::NONE);
+                        e.get::<Outline>().map(|outline| outline.color)
+                    }
+                    _ => continue,
+                };
+                prev_colors.push((tr.property, prev));
+            }
+        }
 
         // If any layout properties are animated, insert animation components and mutate
         // the style that's going to get inserted
@@ -109,42 +231,23 @@ impl Command for UpdateComputedStyle {
                 None => next_style.clone(),
             };
 
-            // TODO: Make sure the set of transitions hasn't changed.
-
-            // If there's already animations
-            if let Some(mut anim) = e.get_mut::<AnimatedLayout>() {
-                for (prop, trans) in anim.0.iter_mut() {
-                    trans.restart_if_changed(*prop, &prev_style, &next_style);
-                    trans.update(*prop, &mut next_style, 0., true);
-                }
-            } else {
-                let mut anim =
-                    AnimatedLayout(HashMap::with_capacity(self.computed.transitions.len()));
-                self.computed
-                    .transitions
-                    .iter()
-                    .for_each(|tr| match tr.property {
-                        TransitionProperty::Left
-                        | TransitionProperty::Top
-                        | TransitionProperty::Right
-                        | TransitionProperty::Bottom
-                        | TransitionProperty::Height
-                        | TransitionProperty::Width
-                        | TransitionProperty::BorderLeft
-                        | TransitionProperty::BorderTop
-                        | TransitionProperty::BorderRight
-                        | TransitionProperty::BorderBottom => {
-                            let mut ap = AnimatedLayoutProp::new(TransitionState {
-                                transition: tr.clone(),
-                                clock: 0.,
-                            });
-                            ap.update(tr.property, &mut next_style, 0., true);
-                            anim.0.insert(tr.property, ap);
-                        }
-                        _ => (),
-                    });
-                e.insert(anim);
+            let mut anim = e
+                .take::<AnimatedLayout>()
+                .unwrap_or_else(|| AnimatedLayout(HashMap::new()));
+            let transitions = &self.computed.transitions;
+            anim.0
+                .retain(|prop, _| transitions.iter().any(|tr| tr.property == *prop));
+            for tr in transitions.iter().filter(|tr| tr.property.is_layout()) {
+                let ap = anim.0.entry(tr.property).or_insert_with(|| {
+                    AnimatedLayoutProp::new(tr.clone(), tr.property.layout_value(&prev_style))
+                });
+                ap.state.transition = tr.clone();
+                ap.restart_if_changed(tr.property, &prev_style, &next_style, &layout);
+                ap.update(tr.property, &mut next_style, 0., true);
             }
+            e.insert(anim);
+        } else {
+            e.remove::<AnimatedLayout>();
         }
 
         if let Some(mut existing_style) = e.get_mut::<Style>() {
@@ -157,7 +260,16 @@ impl Command for UpdateComputedStyle {
             e.insert(next_style);
         }
 
+        // The sections of rich text nodes are styled individually by their spans.
         if let Some(mut text) = e.get_mut::<Text>() {
+            if let Some(ws) = self.computed.line_break {
+                if text.linebreak_behavior != ws {
+                    text.linebreak_behavior = ws;
+                }
+            }
+        }
+
+        if let Some(mut text) = e.get_mut::<Text>().filter(|_| !is_rich_text) {
             // White is the default.
             let color = self.computed.color.unwrap_or(Color::WHITE);
             for section in text.sections.iter_mut() {
@@ -166,12 +278,6 @@ impl Command for UpdateComputedStyle {
                 }
             }
 
-            if let Some(ws) = self.computed.line_break {
-                if text.linebreak_behavior != ws {
-                    text.linebreak_behavior = ws;
-                }
-            }
-
             if let Some(font_size) = self.computed.font_size {
                 for section in text.sections.iter_mut() {
                     if section.style.font_size != font_size {
@@ -189,13 +295,17 @@ impl Command for UpdateComputedStyle {
             }
         }
 
-        if is_animated_bg_color {
-            match e.get_mut::<AnimatedBackgroundColor>() {
-                Some(_) => todo!(),
-                None => todo!(),
-            }
-        } else {
-            e.remove::<AnimatedBackgroundColor>();
+        // Decorated elements are drawn by the style material, which only renders nodes that
+        // have no background color. The border color is drawn by the material as well. Both
+        // are held back until color transitions have been started below.
+        let mut decoration_colors = is_decorated.then(|| {
+            (
+                self.computed.background_color.take(),
+                self.computed.border_color.take(),
+            )
+        });
+
+        {
             match e.get_mut::<BackgroundColor>() {
                 Some(mut bg_comp) => {
                     if self.computed.background_color.is_none() {
@@ -224,13 +334,7 @@ impl Command for UpdateComputedStyle {
             }
         }
 
-        if is_animated_border_color {
-            match e.get_mut::<AnimatedBorderColor>() {
-                Some(_) => todo!(),
-                None => todo!(),
-            }
-        } else {
-            e.remove::<AnimatedBorderColor>();
+        {
             match e.get_mut::<BorderColor>() {
                 Some(mut bc_comp) => {
                     if self.computed.border_color.is_none() {
@@ -310,6 +414,102 @@ impl Command for UpdateComputedStyle {
             (None, None) => {}
         }
 
+        // Start color animations from the previously displayed colors. The target colors have
+        // already been written to the components above, or held for the material, so replace
+        // them with the color at the start of the animation.
+        if prev_colors.is_empty() {
+            e.remove::<AnimatedColors>();
+        } else {
+            let mut anim = e.take::<AnimatedColors>().unwrap_or_default();
+            anim.0
+                .retain(|prop, _| prev_colors.iter().any(|(p, _)| p == prop));
+            for (prop, prev) in prev_colors {
+                let target = match prop {
+                    TransitionProperty::BackgroundColor => match decoration_colors {
+                        Some((bg, _)) => bg,
+                        None => e.get::<BackgroundColor>().map(|bg| bg.0),
+                    },
+                    TransitionProperty::BorderColor => match decoration_colors {
+                        Some((_, bc)) => bc,
+                        None => e.get::<BorderColor>().map(|bc| bc.0),
+                    },
+                    TransitionProperty::Color => self.computed.color.or(Some(Color::WHITE)),
+                    TransitionProperty::OutlineColor => e.get::<Outline>().map(|o| o.color),
+                    _ => None,
+                };
+                let Some(target) = target else {
+                    anim.0.remove(&prop);
+                    continue;
+                };
+                let transition = self
+                    .computed
+                    .transitions
+                    .iter()
+                    .find(|tr| tr.property == prop)
+                    .unwrap();
+                let color_anim = anim.0.entry(prop).or_insert_with(|| {
+                    AnimatedColor::new(transition.clone(), prev.unwrap_or(target))
+                });
+                color_anim.state.transition = transition.clone();
+                color_anim.restart_if_changed(prev.unwrap_or(target), target);
+                let color = color_anim.state.transition.color_space.mix(
+                    color_anim.origin,
+                    color_anim.target,
+                    color_anim.state.t(),
+                );
+                match prop {
+                    TransitionProperty::BackgroundColor => {
+                        if let Some((bg, _)) = decoration_colors.as_mut() {
+                            *bg = Some(color);
+                        } else if let Some(mut bg) = e.get_mut::<BackgroundColor>() {
+                            bg.0 = color;
+                        }
+                    }
+                    TransitionProperty::BorderColor => {
+                        if let Some((_, bc)) = decoration_colors.as_mut() {
+                            *bc = Some(color);
+                        } else if let Some(mut bc) = e.get_mut::<BorderColor>() {
+                            bc.0 = color;
+                        }
+                    }
+                    TransitionProperty::Color => {
+                        if let Some(mut text) = e.get_mut::<Text>() {
+                            for section in text.sections.iter_mut() {
+                                section.style.color = color;
+                            }
+                        }
+                    }
+                    TransitionProperty::OutlineColor => {
+                        if let Some(mut outline) = e.get_mut::<Outline>() {
+                            outline.color = color;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            e.insert(anim);
+        }
+
+        match decoration_colors {
+            Some((background, border)) => {
+                let params = StyleMaterialParams::new(
+                    background,
+                    border,
+                    self.computed.border_radius,
+                    self.computed.box_shadow.as_ref(),
+                    self.computed.background_gradient.as_ref(),
+                );
+                let handle = e.get::<Handle<StyleMaterial>>().cloned();
+                if let Some(handle) = e.world_scope(|world| update_material(world, handle, params))
+                {
+                    e.insert(handle);
+                }
+            }
+            None => {
+                e.remove::<Handle<StyleMaterial>>();
+            }
+        }
+
         // Update Z-Index
         match (self.computed.z_index, e.get::<ZIndex>()) {
             // Don't change if value is the same
@@ -345,6 +545,23 @@ impl Command for UpdateComputedStyle {
             (None, None) => {}
         }
 
+        // Update cursor
+        let next_cursor = self.computed.cursor.map(|cursor| ElementCursor {
+            cursor,
+            image: self.computed.cursor_image.clone(),
+            offset: self.computed.cursor_offset.unwrap_or_default(),
+        });
+        match (next_cursor, e.get::<ElementCursor>()) {
+            (Some(next), Some(prev)) if next == *prev => {}
+            (Some(next), _) => {
+                e.insert(next);
+            }
+            (None, Some(_)) => {
+                e.remove::<ElementCursor>();
+            }
+            (None, None) => {}
+        }
+
         let mut transform = Transform::default();
         transform.translation = self.computed.translation.unwrap_or(transform.translation);
         transform.scale.x = self.computed.scale_x.unwrap_or(1.);
@@ -0,0 +1,365 @@
+use std::fmt;
+
+use bevy::prelude::*;
+
+/// Marks an element as a query container: container queries in the styles of its descendants
+/// are evaluated against the size of the nearest container ancestor.
+///
+/// The size is taken from the element's [`Node`] as of the most recent layout, so a change to
+/// the size of the container takes effect on the following frame. To stop styles which change
+/// the size of their own container from toggling on every frame, a change in size which
+/// reverses the previous change by no more than [`CONTAINER_HYSTERESIS`] is delayed until the
+/// layout size has held steady for [`CONTAINER_SETTLE_FRAMES`] frames.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Container;
+
+/// The distance, in logical pixels, by which a container must shrink after growing, or grow
+/// after shrinking, before container queries see the new size immediately.
+pub const CONTAINER_HYSTERESIS: f32 = 16.;
+
+/// The number of frames for which a smaller reversal in the size of a container must hold
+/// steady before container queries see the new size.
+pub const CONTAINER_SETTLE_FRAMES: u32 = 4;
+
+/// Component added to each [`Container`], which holds the size that container queries are
+/// evaluated against.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct ContainerSize {
+    width: AxisSize,
+    height: AxisSize,
+}
+
+impl ContainerSize {
+    fn new(size: Vec2) -> Self {
+        Self {
+            width: AxisSize::new(size.x),
+            height: AxisSize::new(size.y),
+        }
+    }
+
+    /// The size which container queries are evaluated against.
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width.size, self.height.size)
+    }
+
+    /// Follow the layout size of the container, delaying small changes which reverse the
+    /// direction of the previous change.
+    fn follow(&self, layout_size: Vec2) -> Self {
+        Self {
+            width: self.width.follow(layout_size.x),
+            height: self.height.follow(layout_size.y),
+        }
+    }
+}
+
+/// The size of a container in one dimension.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct AxisSize {
+    size: f32,
+    /// Whether the size last changed by growing, if it has changed.
+    growing: Option<bool>,
+    /// A layout size which reverses the previous change, waiting to be applied.
+    pending: f32,
+    /// The number of consecutive frames for which the layout size has been `pending`.
+    held: u32,
+}
+
+impl AxisSize {
+    fn new(size: f32) -> Self {
+        Self {
+            size,
+            growing: None,
+            pending: size,
+            held: 0,
+        }
+    }
+
+    fn follow(self, layout_size: f32) -> Self {
+        let grows = layout_size > self.size;
+        if layout_size == self.size {
+            Self {
+                pending: self.size,
+                held: 0,
+                ..self
+            }
+        } else if self.growing != Some(!grows)
+            || (layout_size - self.size).abs() > CONTAINER_HYSTERESIS
+            || (layout_size == self.pending && self.held >= CONTAINER_SETTLE_FRAMES)
+        {
+            Self {
+                growing: Some(grows),
+                ..Self::new(layout_size)
+            }
+        } else if layout_size == self.pending {
+            Self {
+                held: self.held + 1,
+                ..self
+            }
+        } else {
+            Self {
+                pending: layout_size,
+                held: 1,
+                ..self
+            }
+        }
+    }
+}
+
+/// System which updates the [`ContainerSize`] of each container from its layout size.
+pub(crate) fn update_container_sizes(
+    mut commands: Commands,
+    mut query: Query<(Entity, &Node, Option<&mut ContainerSize>), With<Container>>,
+) {
+    for (entity, node, container_size) in query.iter_mut() {
+        match container_size {
+            Some(mut container_size) => {
+                let next = container_size.follow(node.size());
+                if container_size.size() != next.size() {
+                    *container_size = next;
+                } else if *container_size != next {
+                    // Only a change in size restyles the container's descendants.
+                    *container_size.bypass_change_detection() = next;
+                }
+            }
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(ContainerSize::new(node.size()));
+            }
+        }
+    }
+}
+
+/// Which dimension of the container a [`ContainerCondition`] tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerAxis {
+    /// The width of the container in logical pixels.
+    Width,
+    /// The height of the container in logical pixels.
+    Height,
+}
+
+/// Comparison operator used in a [`ContainerCondition`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// `<`
+    Less,
+    /// `<=`
+    LessEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterEqual,
+}
+
+impl Comparison {
+    fn parse(op: &str) -> Option<Self> {
+        Some(match op {
+            "<" => Comparison::Less,
+            "<=" => Comparison::LessEqual,
+            ">" => Comparison::Greater,
+            ">=" => Comparison::GreaterEqual,
+            _ => return None,
+        })
+    }
+
+    /// Returns the operator with its operands swapped, so that `a < b` becomes `b > a`.
+    fn reverse(self) -> Self {
+        match self {
+            Comparison::Less => Comparison::Greater,
+            Comparison::LessEqual => Comparison::GreaterEqual,
+            Comparison::Greater => Comparison::Less,
+            Comparison::GreaterEqual => Comparison::LessEqual,
+        }
+    }
+}
+
+/// A single condition of a container query, such as `(width >= 300px)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContainerCondition {
+    /// The dimension being tested.
+    pub axis: ContainerAxis,
+    /// How the dimension is compared with the value.
+    pub comparison: Comparison,
+    /// The value to compare against, in logical pixels.
+    pub value: f32,
+}
+
+impl ContainerCondition {
+    /// Parse the text of a condition, without the surrounding parentheses. The condition can
+    /// be written as `width >= 300px`, `300px <= width`, `>= 300px width` or
+    /// `min-width: 300px`.
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        if let Some((name, value)) = text.split_once(':') {
+            let (comparison, axis) = match name.trim() {
+                "min-width" => (Comparison::GreaterEqual, ContainerAxis::Width),
+                "max-width" => (Comparison::LessEqual, ContainerAxis::Width),
+                "min-height" => (Comparison::GreaterEqual, ContainerAxis::Height),
+                "max-height" => (Comparison::LessEqual, ContainerAxis::Height),
+                _ => return None,
+            };
+            return Some(Self {
+                axis,
+                comparison,
+                value: parse_pixels(value.trim())?,
+            });
+        }
+
+        let start = text.find(['<', '>'])?;
+        let end = text[start..]
+            .find(|c| !matches!(c, '<' | '>' | '='))
+            .map_or(text.len(), |len| start + len);
+        let comparison = Comparison::parse(&text[start..end])?;
+        let lhs = text[..start].trim();
+        let rhs = text[end..].trim();
+        if lhs.is_empty() {
+            // Prefix form: `>= 300px width`.
+            let (value, axis) = rhs.split_once(char::is_whitespace)?;
+            Some(Self {
+                axis: parse_axis(axis.trim())?,
+                comparison,
+                value: parse_pixels(value)?,
+            })
+        } else if let Some(axis) = parse_axis(lhs) {
+            Some(Self {
+                axis,
+                comparison,
+                value: parse_pixels(rhs)?,
+            })
+        } else {
+            Some(Self {
+                axis: parse_axis(rhs)?,
+                comparison: comparison.reverse(),
+                value: parse_pixels(lhs)?,
+            })
+        }
+    }
+
+    /// Returns whether the condition is true for a container of the given size.
+    pub(crate) fn matches(&self, size: Vec2) -> bool {
+        let dimension = match self.axis {
+            ContainerAxis::Width => size.x,
+            ContainerAxis::Height => size.y,
+        };
+        match self.comparison {
+            Comparison::Less => dimension < self.value,
+            Comparison::LessEqual => dimension <= self.value,
+            Comparison::Greater => dimension > self.value,
+            Comparison::GreaterEqual => dimension >= self.value,
+        }
+    }
+}
+
+fn parse_axis(name: &str) -> Option<ContainerAxis> {
+    match name {
+        "width" => Some(ContainerAxis::Width),
+        "height" => Some(ContainerAxis::Height),
+        _ => None,
+    }
+}
+
+/// Parse a length in logical pixels, such as `300px`. The unit may be omitted.
+fn parse_pixels(value: &str) -> Option<f32> {
+    value.strip_suffix("px").unwrap_or(value).parse().ok()
+}
+
+impl fmt::Display for ContainerCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let axis = match self.axis {
+            ContainerAxis::Width => "width",
+            ContainerAxis::Height => "height",
+        };
+        let op = match self.comparison {
+            Comparison::Less => "<",
+            Comparison::LessEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterEqual => ">=",
+        };
+        write!(f, "({} {} {}px)", axis, op, self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_condition() {
+        let wide = ContainerCondition {
+            axis: ContainerAxis::Width,
+            comparison: Comparison::GreaterEqual,
+            value: 300.,
+        };
+        assert_eq!(ContainerCondition::parse("width >= 300px"), Some(wide));
+        assert_eq!(ContainerCondition::parse(">= 300px width"), Some(wide));
+        assert_eq!(ContainerCondition::parse("300px <= width"), Some(wide));
+        assert_eq!(ContainerCondition::parse("min-width: 300px"), Some(wide));
+        assert_eq!(
+            ContainerCondition::parse("height<200"),
+            Some(ContainerCondition {
+                axis: ContainerAxis::Height,
+                comparison: Comparison::Less,
+                value: 200.,
+            })
+        );
+        assert_eq!(ContainerCondition::parse("depth > 10px"), None);
+        assert_eq!(ContainerCondition::parse("width => 10px"), None);
+    }
+
+    #[test]
+    fn test_matches() {
+        let narrow = ContainerCondition::parse("width < 300px").unwrap();
+        assert!(narrow.matches(Vec2::new(299., 1000.)));
+        assert!(!narrow.matches(Vec2::new(300., 0.)));
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        let wide = ContainerCondition::parse("width >= 300px").unwrap();
+        let mut container = ContainerSize::new(Vec2::new(299., 100.));
+        assert!(!wide.matches(container.size()));
+
+        // The container grows past the breakpoint, and the matching style shrinks it again.
+        container = container.follow(Vec2::new(305., 100.));
+        assert!(wide.matches(container.size()));
+        for _ in 0..3 {
+            container = container.follow(Vec2::new(295., 100.));
+            assert!(wide.matches(container.size()));
+            container = container.follow(Vec2::new(305., 100.));
+            assert!(wide.matches(container.size()));
+        }
+
+        // Larger changes are followed in either direction.
+        container = container.follow(Vec2::new(250., 100.));
+        assert_eq!(container.size(), Vec2::new(250., 100.));
+        container = container.follow(Vec2::new(240., 100.));
+        assert_eq!(container.size(), Vec2::new(240., 100.));
+        container = container.follow(Vec2::new(300., 100.));
+        assert!(wide.matches(container.size()));
+    }
+
+    #[test]
+    fn test_settle() {
+        let wide = ContainerCondition::parse("width >= 300px").unwrap();
+        let mut container = ContainerSize::new(Vec2::new(280., 100.));
+
+        // The container grows past the breakpoint, then settles just below it.
+        container = container.follow(Vec2::new(305., 100.));
+        assert!(wide.matches(container.size()));
+        for _ in 0..CONTAINER_SETTLE_FRAMES {
+            container = container.follow(Vec2::new(295., 100.));
+            assert!(wide.matches(container.size()));
+        }
+        container = container.follow(Vec2::new(295., 100.));
+        assert!(!wide.matches(container.size()));
+        assert_eq!(container.size(), Vec2::new(295., 100.));
+
+        // Growing back is a reversal as well.
+        for _ in 0..CONTAINER_SETTLE_FRAMES {
+            container = container.follow(Vec2::new(305., 100.));
+            assert!(!wide.matches(container.size()));
+        }
+        container = container.follow(Vec2::new(305., 100.));
+        assert!(wide.matches(container.size()));
+    }
+}
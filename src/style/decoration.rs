@@ -0,0 +1,151 @@
+use bevy::prelude::*;
+
+/// The radii of the four corners of an element, in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BorderRadius {
+    /// Radius of the top left corner.
+    pub top_left: f32,
+    /// Radius of the top right corner.
+    pub top_right: f32,
+    /// Radius of the bottom right corner.
+    pub bottom_right: f32,
+    /// Radius of the bottom left corner.
+    pub bottom_left: f32,
+}
+
+impl BorderRadius {
+    /// Construct a radius which is the same for all four corners.
+    pub const fn all(radius: f32) -> Self {
+        Self {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+
+    /// Construct a radius for each corner, in clockwise order starting from the top left.
+    pub const fn new(top_left: f32, top_right: f32, bottom_right: f32, bottom_left: f32) -> Self {
+        Self {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+}
+
+impl From<f32> for BorderRadius {
+    fn from(radius: f32) -> Self {
+        Self::all(radius)
+    }
+}
+
+impl From<i32> for BorderRadius {
+    fn from(radius: i32) -> Self {
+        Self::all(radius as f32)
+    }
+}
+
+/// A shadow drawn around the outside of an element, like the CSS `box-shadow` property.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoxShadow {
+    /// Offset of the shadow from the element, in logical pixels.
+    pub offset: Vec2,
+    /// Distance over which the edge of the shadow fades out.
+    pub blur: f32,
+    /// Distance by which the shadow is larger than the element.
+    pub spread: f32,
+    /// Color of the shadow.
+    pub color: Color,
+}
+
+impl Default for BoxShadow {
+    fn default() -> Self {
+        Self {
+            offset: Vec2::ZERO,
+            blur: 0.,
+            spread: 0.,
+            color: Color::BLACK,
+        }
+    }
+}
+
+/// A color at a given position along a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorStop {
+    /// The color at this stop.
+    pub color: Color,
+    /// Position of the stop along the gradient, from 0 to 1.
+    pub position: f32,
+}
+
+impl ColorStop {
+    /// Construct a new color stop.
+    pub fn new(color: Color, position: f32) -> Self {
+        Self { color, position }
+    }
+}
+
+/// A background which blends smoothly between colors. Colors are interpolated in linear RGB.
+/// At most [`MAX_GRADIENT_STOPS`] stops are used.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gradient {
+    /// Colors change along a straight line through the center of the element. The angle is in
+    /// radians, measured clockwise from the top of the element, so an angle of 0 goes from
+    /// bottom to top and an angle of `PI / 2` goes from left to right.
+    Linear {
+        /// Direction of the gradient line.
+        angle: f32,
+        /// The colors along the line.
+        stops: Vec<ColorStop>,
+    },
+    /// Colors change outwards from the center of the element, reaching the last stop at the
+    /// corners.
+    Radial {
+        /// The colors from the center outwards.
+        stops: Vec<ColorStop>,
+    },
+}
+
+impl Gradient {
+    /// Construct a linear gradient with colors evenly spaced along the gradient line.
+    pub fn linear(angle: f32, colors: &[Color]) -> Self {
+        Self::Linear {
+            angle,
+            stops: even_stops(colors),
+        }
+    }
+
+    /// Construct a radial gradient with colors evenly spaced from the center outwards.
+    pub fn radial(colors: &[Color]) -> Self {
+        Self::Radial {
+            stops: even_stops(colors),
+        }
+    }
+
+    /// The color stops of the gradient.
+    pub fn stops(&self) -> &[ColorStop] {
+        match self {
+            Gradient::Linear { stops, .. } | Gradient::Radial { stops } => stops,
+        }
+    }
+
+    pub(crate) fn stops_mut(&mut self) -> &mut [ColorStop] {
+        match self {
+            Gradient::Linear { stops, .. } | Gradient::Radial { stops } => stops,
+        }
+    }
+}
+
+/// The maximum number of color stops in a [`Gradient`].
+pub const MAX_GRADIENT_STOPS: usize = 8;
+
+fn even_stops(colors: &[Color]) -> Vec<ColorStop> {
+    let last = colors.len().saturating_sub(1).max(1) as f32;
+    colors
+        .iter()
+        .enumerate()
+        .map(|(index, color)| ColorStop::new(*color, index as f32 / last))
+        .collect()
+}
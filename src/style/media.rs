@@ -0,0 +1,204 @@
+use std::fmt;
+
+use bevy::prelude::*;
+
+/// The color scheme preferred by the user, which is matched by the `prefers-color-scheme`
+/// media feature. Insert or modify this resource to switch between light and dark styles.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorScheme {
+    /// Light backgrounds with dark text.
+    #[default]
+    Light,
+    /// Dark backgrounds with light text.
+    Dark,
+}
+
+/// Orientation of a window, matched by the `orientation` media feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// The window is at least as tall as it is wide.
+    Portrait,
+    /// The window is wider than it is tall.
+    Landscape,
+}
+
+/// A single condition of a media query, such as `(max-width: 800px)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MediaFeature {
+    /// Window width in logical pixels is at least this value.
+    MinWidth(f32),
+    /// Window width in logical pixels is at most this value.
+    MaxWidth(f32),
+    /// Window height in logical pixels is at least this value.
+    MinHeight(f32),
+    /// Window height in logical pixels is at most this value.
+    MaxHeight(f32),
+    /// Window scale factor is at least this value.
+    MinResolution(f32),
+    /// Window scale factor is at most this value.
+    MaxResolution(f32),
+    /// Window has the given orientation.
+    Orientation(Orientation),
+    /// The [`ColorScheme`] resource has the given value.
+    PrefersColorScheme(ColorScheme),
+}
+
+impl MediaFeature {
+    /// Construct a media feature from its name and value, returning `None` if either is not
+    /// valid.
+    pub(crate) fn parse(name: &str, value: &str) -> Option<Self> {
+        Some(match name {
+            "min-width" => MediaFeature::MinWidth(parse_pixels(value)?),
+            "max-width" => MediaFeature::MaxWidth(parse_pixels(value)?),
+            "min-height" => MediaFeature::MinHeight(parse_pixels(value)?),
+            "max-height" => MediaFeature::MaxHeight(parse_pixels(value)?),
+            "min-resolution" => MediaFeature::MinResolution(parse_resolution(value)?),
+            "max-resolution" => MediaFeature::MaxResolution(parse_resolution(value)?),
+            "orientation" => MediaFeature::Orientation(match value {
+                "portrait" => Orientation::Portrait,
+                "landscape" => Orientation::Landscape,
+                _ => return None,
+            }),
+            "prefers-color-scheme" => MediaFeature::PrefersColorScheme(match value {
+                "light" => ColorScheme::Light,
+                "dark" => ColorScheme::Dark,
+                _ => return None,
+            }),
+            _ => return None,
+        })
+    }
+
+    /// Returns whether the condition is true for the given window.
+    pub(crate) fn matches(&self, media: &MediaContext) -> bool {
+        match *self {
+            MediaFeature::MinWidth(width) => media.width >= width,
+            MediaFeature::MaxWidth(width) => media.width <= width,
+            MediaFeature::MinHeight(height) => media.height >= height,
+            MediaFeature::MaxHeight(height) => media.height <= height,
+            MediaFeature::MinResolution(scale) => media.scale_factor >= scale,
+            MediaFeature::MaxResolution(scale) => media.scale_factor <= scale,
+            MediaFeature::Orientation(orientation) => media.orientation() == orientation,
+            MediaFeature::PrefersColorScheme(scheme) => media.color_scheme == scheme,
+        }
+    }
+}
+
+/// Parse a length in logical pixels, such as `800px`. The unit may be omitted.
+fn parse_pixels(value: &str) -> Option<f32> {
+    value.strip_suffix("px").unwrap_or(value).parse().ok()
+}
+
+/// Parse a resolution, such as `2x`, `2dppx` or `192dpi`, as a scale factor.
+fn parse_resolution(value: &str) -> Option<f32> {
+    if let Some(dpi) = value.strip_suffix("dpi") {
+        return dpi.parse::<f32>().ok().map(|dpi| dpi / 96.);
+    }
+    let scale = value
+        .strip_suffix("dppx")
+        .or_else(|| value.strip_suffix('x'))?;
+    scale.parse().ok()
+}
+
+impl fmt::Display for MediaFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MediaFeature::MinWidth(width) => write!(f, "(min-width: {}px)", width),
+            MediaFeature::MaxWidth(width) => write!(f, "(max-width: {}px)", width),
+            MediaFeature::MinHeight(height) => write!(f, "(min-height: {}px)", height),
+            MediaFeature::MaxHeight(height) => write!(f, "(max-height: {}px)", height),
+            MediaFeature::MinResolution(scale) => write!(f, "(min-resolution: {}x)", scale),
+            MediaFeature::MaxResolution(scale) => write!(f, "(max-resolution: {}x)", scale),
+            MediaFeature::Orientation(Orientation::Portrait) => {
+                write!(f, "(orientation: portrait)")
+            }
+            MediaFeature::Orientation(Orientation::Landscape) => {
+                write!(f, "(orientation: landscape)")
+            }
+            MediaFeature::PrefersColorScheme(ColorScheme::Light) => {
+                write!(f, "(prefers-color-scheme: light)")
+            }
+            MediaFeature::PrefersColorScheme(ColorScheme::Dark) => {
+                write!(f, "(prefers-color-scheme: dark)")
+            }
+        }
+    }
+}
+
+/// The properties of the window targeted by a UI hierarchy, which media queries are
+/// evaluated against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MediaContext {
+    /// Width of the window in logical pixels.
+    pub width: f32,
+    /// Height of the window in logical pixels.
+    pub height: f32,
+    /// Ratio of physical to logical pixels.
+    pub scale_factor: f32,
+    /// The user's preferred color scheme.
+    pub color_scheme: ColorScheme,
+}
+
+impl MediaContext {
+    /// Returns the orientation of the window.
+    pub fn orientation(&self) -> Orientation {
+        if self.width > self.height {
+            Orientation::Landscape
+        } else {
+            Orientation::Portrait
+        }
+    }
+}
+
+impl Default for MediaContext {
+    fn default() -> Self {
+        Self {
+            width: 0.,
+            height: 0.,
+            scale_factor: 1.,
+            color_scheme: ColorScheme::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_feature() {
+        assert_eq!(
+            MediaFeature::parse("max-width", "800px"),
+            Some(MediaFeature::MaxWidth(800.))
+        );
+        assert_eq!(
+            MediaFeature::parse("min-resolution", "2x"),
+            Some(MediaFeature::MinResolution(2.))
+        );
+        assert_eq!(
+            MediaFeature::parse("min-resolution", "192dpi"),
+            Some(MediaFeature::MinResolution(2.))
+        );
+        assert_eq!(
+            MediaFeature::parse("prefers-color-scheme", "dark"),
+            Some(MediaFeature::PrefersColorScheme(ColorScheme::Dark))
+        );
+        assert_eq!(MediaFeature::parse("orientation", "sideways"), None);
+        assert_eq!(MediaFeature::parse("color", "8"), None);
+    }
+
+    #[test]
+    fn test_matches() {
+        let media = MediaContext {
+            width: 1280.,
+            height: 800.,
+            scale_factor: 1.5,
+            color_scheme: ColorScheme::Dark,
+        };
+        assert!(MediaFeature::MaxWidth(1280.).matches(&media));
+        assert!(!MediaFeature::MaxWidth(800.).matches(&media));
+        assert!(MediaFeature::MinResolution(1.5).matches(&media));
+        assert!(!MediaFeature::MinResolution(2.).matches(&media));
+        assert!(MediaFeature::Orientation(Orientation::Landscape).matches(&media));
+        assert!(MediaFeature::PrefersColorScheme(ColorScheme::Dark).matches(&media));
+    }
+}
@@ -1,29 +1,57 @@
+mod animation;
 mod builder;
+mod cascade;
 mod classes;
 mod computed;
+mod container;
+mod decoration;
+mod media;
 mod selector;
 mod selector_matcher;
 mod style_handle;
 mod style_props;
 mod style_tuple;
+mod stylesheet;
+pub mod timing;
 mod transition;
 pub(crate) mod update;
+mod vars;
 
+pub use animation::animate_keyframes;
+pub use animation::{
+    Animation, AnimationDirection, AnimationEnd, AnimationFillMode, AnimationStart, Keyframe,
+};
+pub use classes::Checked;
 pub use classes::ClassNames;
+pub use classes::Disabled;
 pub use classes::ElementClasses;
 pub use computed::ComputedStyle;
 pub use computed::UpdateComputedStyle;
+pub(crate) use container::{update_container_sizes, ContainerSize};
+pub use container::{
+    Comparison, Container, ContainerAxis, ContainerCondition, CONTAINER_HYSTERESIS,
+    CONTAINER_SETTLE_FRAMES,
+};
+pub use decoration::{BorderRadius, BoxShadow, ColorStop, Gradient, MAX_GRADIENT_STOPS};
+pub use media::{ColorScheme, MediaContext, MediaFeature, Orientation};
 pub(crate) use selector::Selector;
 pub(crate) use selector_matcher::SelectorMatcher;
+pub use style_handle::DescendantStyles;
 pub use style_handle::ElementStyles;
 pub use style_handle::StyleHandle;
 pub use style_props::PointerEvents;
 pub use style_props::StyleProp;
 pub use style_tuple::StyleTuple;
-pub use transition::animate_bg_colors;
-pub use transition::animate_border_colors;
+pub(crate) use stylesheet::reload_style_sheets;
+pub use stylesheet::{StyleSheet, StyleSheetError, StyleSheetLoader};
+pub use timing::{Timing, TimingFunction};
+pub use transition::animate_colors;
 pub use transition::animate_layout;
+pub use transition::animate_opacity;
 pub use transition::animate_transforms;
-pub use transition::timing;
+pub use transition::ColorSpace;
 pub use transition::Transition;
 pub use transition::TransitionProperty;
+pub(crate) use transition::TransitionState;
+pub(crate) use update::load_image;
+pub use vars::{var, StyleVar, StyleVars, VarProperty, VarValue};
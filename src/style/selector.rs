@@ -1,19 +1,34 @@
 use std::fmt;
 
 use winnow::{
-    ascii::space0,
-    combinator::{alt, opt, preceded, repeat, separated},
+    ascii::{space0, space1},
+    combinator::{alt, delimited, opt, peek, preceded, repeat, separated, terminated},
     stream::AsChar,
-    token::{one_of, take_while},
+    token::{one_of, take_till, take_while},
     PResult, Parser,
 };
 
+use super::{container::ContainerCondition, media::MediaFeature};
+
 /// Represents a predicate which can be used to conditionally style a node.
 /// Selectors support a subset of CSS grammar:
 ///
 /// * Current element (`&`)
 /// * Classname matching
+/// * Type matching against the element's `Name`
+/// * Pseudo-classes: `:hover`, `:active`, `:focus`, `:focus-within`, `:focus-visible`,
+///   `:disabled`, `:checked`, `:first-child`, `:last-child`, `:nth-child(an+b)`, `:empty`
+///   and `:not(...)`
 /// * Parent element (`>`) pattern
+/// * Ancestor element (space) pattern
+/// * Previous sibling (`+`) and earlier sibling (`~`) patterns
+/// * Media queries (`@media (max-width: 800px) and (orientation: portrait)`), which can be
+///   followed by another pattern. The supported features are `min-width`, `max-width`,
+///   `min-height`, `max-height`, `min-resolution`, `max-resolution`, `orientation` and
+///   `prefers-color-scheme`.
+/// * Container queries (`@container (width >= 300px)`), which are evaluated against the size of
+///   the nearest ancestor with the [`Container`](crate::Container) component, and can also be
+///   followed by another pattern.
 /// * Multiple patterns can be specified by commas.
 ///
 /// Examples:
@@ -23,12 +38,18 @@ use winnow::{
 ///   :hover
 ///   .state > &
 ///   .state > * > &.name
+///   .dialog button.primary
+///   &:nth-child(2n+1):not(.selected)
+///   :checked + &
+///   @media (max-width: 800px)
+///   @media (prefers-color-scheme: dark) .panel > &
+///   @container (width < 300px) and (height >= 100px)
 /// ```
 ///
-/// Selectors must target the "current element": this means that the "`&`" selector is
-/// required, and it can only appear on the last term of the selector expression. This means
-/// that parent elements cannot implicitly style their children; child elements must have styles
-/// explicitly specified (although those styles can be conditional on the state of their parents).
+/// Selectors in element styles target the "current element": this means that the "`&`"
+/// selector can only appear on the last term of the selector expression. Parent elements can
+/// style their descendants using [`DescendantStyles`](crate::DescendantStyles), in which case
+/// the last term is matched against each descendant.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Selector {
     /// If we reach this state, it means the match was successful
@@ -37,6 +58,9 @@ pub enum Selector {
     /// Match an element with a specific class name.
     Class(String, Box<Selector>),
 
+    /// Match an element whose `Name` component has the given value.
+    Name(String, Box<Selector>),
+
     /// Element that is being hovered.
     Hover(Box<Selector>),
 
@@ -55,29 +79,138 @@ pub enum Selector {
     /// Element is the last child of its parent.
     LastChild(Box<Selector>),
 
+    /// Element's 1-based position among its siblings is `a * n + b` for some `n >= 0`.
+    NthChild(i32, i32, Box<Selector>),
+
+    /// Element has no children.
+    Empty(Box<Selector>),
+
+    /// Element is being pressed.
+    Active(Box<Selector>),
+
+    /// Element has the [`Disabled`](crate::Disabled) component.
+    Disabled(Box<Selector>),
+
+    /// Element has the [`Checked`](crate::Checked) component.
+    Checked(Box<Selector>),
+
+    /// Element does not match the first selector.
+    Not(Box<Selector>, Box<Selector>),
+
     /// Reference to the current element.
     Current(Box<Selector>),
 
     /// Reference to the parent of this element.
     Parent(Box<Selector>),
 
+    /// Match if any ancestor of this element matches.
+    Ancestor(Box<Selector>),
+
+    /// Reference to the sibling immediately before this element.
+    PreviousSibling(Box<Selector>),
+
+    /// Match if any sibling before this element matches.
+    EarlierSibling(Box<Selector>),
+
+    /// Match if the window targeted by the element's UI root satisfies all of the media
+    /// features.
+    Media(Vec<MediaFeature>, Box<Selector>),
+
+    /// Match if the nearest ancestor which is a [`Container`](crate::Container) satisfies all of
+    /// the conditions.
+    Container(Vec<ContainerCondition>, Box<Selector>),
+
     /// List of alternate choices.
     #[allow(clippy::vec_box)]
     Either(Vec<Box<Selector>>),
 }
 
+/// How specific a selector is. When several selectors set the same property on an element,
+/// the most specific one wins. Specificities are compared first by the number of class names
+/// and pseudo-classes, then by the number of type names, like CSS without ID selectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Specificity {
+    /// Number of class names and pseudo-classes.
+    pub classes: u32,
+    /// Number of type names.
+    pub names: u32,
+}
+
+impl Specificity {
+    /// Construct a new specificity.
+    pub const fn new(classes: u32, names: u32) -> Self {
+        Self { classes, names }
+    }
+}
+
+impl std::ops::Add for Specificity {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            classes: self.classes + rhs.classes,
+            names: self.names + rhs.names,
+        }
+    }
+}
+
 enum SelectorToken<'s> {
     Class(&'s str),
+    Name(&'s str),
     Hover,
     FirstChild,
     LastChild,
     Focus,
     FocusWithin,
     FocusVisible,
+    NthChild(i32, i32),
+    Empty,
+    Active,
+    Disabled,
+    Checked,
+    Not(Box<Selector>),
+}
+
+enum Combinator {
+    Parent,
+    Ancestor,
+    PreviousSibling,
+    EarlierSibling,
+}
+
+fn parent(input: &mut &str) -> PResult<Combinator> {
+    (space0, '>', space0)
+        .map(|_| Combinator::Parent)
+        .parse_next(input)
 }
 
-fn parent(input: &mut &str) -> PResult<()> {
-    (space0, '>', space0).void().parse_next(input)
+fn previous_sibling(input: &mut &str) -> PResult<Combinator> {
+    (space0, '+', space0)
+        .map(|_| Combinator::PreviousSibling)
+        .parse_next(input)
+}
+
+fn earlier_sibling(input: &mut &str) -> PResult<Combinator> {
+    (space0, '~', space0)
+        .map(|_| Combinator::EarlierSibling)
+        .parse_next(input)
+}
+
+fn ancestor(input: &mut &str) -> PResult<Combinator> {
+    // Whitespace is only a combinator if it's followed by another selector term.
+    terminated(space1, peek(one_of(('.', ':', '*', '&', AsChar::is_alpha))))
+        .map(|_| Combinator::Ancestor)
+        .parse_next(input)
+}
+
+fn type_name<'s>(input: &mut &'s str) -> PResult<SelectorToken<'s>> {
+    (
+        one_of(AsChar::is_alpha),
+        take_while(0.., (AsChar::is_alphanum, '-', '_')),
+    )
+        .recognize()
+        .map(SelectorToken::Name)
+        .parse_next(input)
 }
 
 fn class_name<'s>(input: &mut &'s str) -> PResult<SelectorToken<'s>> {
@@ -135,33 +268,156 @@ fn last_child<'s>(input: &mut &'s str) -> PResult<SelectorToken<'s>> {
         .parse_next(input)
 }
 
-fn simple_selector<'s>(input: &mut &'s str) -> PResult<(Option<char>, Vec<SelectorToken<'s>>)> {
-    (
-        opt(alt(('*', '&'))),
-        repeat(
-            0..,
-            alt((
-                class_name,
-                hover,
-                first_child,
-                last_child,
-                focus,
-                focus_within,
-                focus_visible,
-            )),
+fn nth_child<'s>(input: &mut &'s str) -> PResult<SelectorToken<'s>> {
+    delimited(
+        (":nth-child(", space0),
+        alt((
+            "odd".value((2, 1)),
+            "even".value((2, 0)),
+            take_while(1.., ('0'..='9', 'n', '+', '-', ' ')).verify_map(an_plus_b),
+        )),
+        (space0, ')'),
+    )
+    .map(|(a, b)| SelectorToken::NthChild(a, b))
+    .parse_next(input)
+}
+
+/// Parse the argument of `:nth-child()`, such as `2n+1`, `-n + 3` or `4`.
+fn an_plus_b(expr: &str) -> Option<(i32, i32)> {
+    let expr: String = expr.chars().filter(|c| !c.is_whitespace()).collect();
+    match expr.split_once('n') {
+        None => Some((0, expr.parse().ok()?)),
+        Some((a, b)) => {
+            let a = match a {
+                "" | "+" => 1,
+                "-" => -1,
+                _ => a.parse().ok()?,
+            };
+            let b = match b {
+                "" => 0,
+                _ => b.strip_prefix('+').unwrap_or(b).parse().ok()?,
+            };
+            Some((a, b))
+        }
+    }
+}
+
+fn empty<'s>(input: &mut &'s str) -> PResult<SelectorToken<'s>> {
+    ":empty"
+        .recognize()
+        .map(|_| SelectorToken::Empty)
+        .parse_next(input)
+}
+
+fn active<'s>(input: &mut &'s str) -> PResult<SelectorToken<'s>> {
+    ":active"
+        .recognize()
+        .map(|_| SelectorToken::Active)
+        .parse_next(input)
+}
+
+fn disabled<'s>(input: &mut &'s str) -> PResult<SelectorToken<'s>> {
+    ":disabled"
+        .recognize()
+        .map(|_| SelectorToken::Disabled)
+        .parse_next(input)
+}
+
+fn checked<'s>(input: &mut &'s str) -> PResult<SelectorToken<'s>> {
+    ":checked"
+        .recognize()
+        .map(|_| SelectorToken::Checked)
+        .parse_next(input)
+}
+
+fn not<'s>(input: &mut &'s str) -> PResult<SelectorToken<'s>> {
+    delimited((":not(", space0), Selector::either, (space0, ')'))
+        .map(SelectorToken::Not)
+        .parse_next(input)
+}
+
+fn media_feature(input: &mut &str) -> PResult<MediaFeature> {
+    delimited(
+        ('(', space0),
+        (
+            take_while(1.., (AsChar::is_alpha, '-')),
+            (space0, ':', space0),
+            take_till(1.., (')', ' ')),
         ),
+        (space0, ')'),
     )
+    .verify_map(|(name, _, value)| MediaFeature::parse(name, value))
+    .parse_next(input)
+}
+
+fn media_query(input: &mut &str) -> PResult<Vec<MediaFeature>> {
+    preceded(
+        ("@media", space1),
+        separated(1.., media_feature, (space1, "and", space1)),
+    )
+    .parse_next(input)
+}
+
+fn container_condition(input: &mut &str) -> PResult<ContainerCondition> {
+    delimited('(', take_till(1.., ')'), ')')
+        .verify_map(|cond: &str| ContainerCondition::parse(cond.trim()))
         .parse_next(input)
 }
 
-fn combo_selector(input: &mut &str) -> PResult<Box<Selector>> {
-    let mut sel = Box::new(Selector::Accept);
-    let (prefix, classes) = simple_selector.parse_next(input)?;
-    for tok in classes {
+fn container_query(input: &mut &str) -> PResult<Vec<ContainerCondition>> {
+    preceded(
+        ("@container", space1),
+        separated(1.., container_condition, (space1, "and", space1)),
+    )
+    .parse_next(input)
+}
+
+fn simple_selector<'s>(input: &mut &'s str) -> PResult<(Option<char>, Vec<SelectorToken<'s>>)> {
+    let prefix = opt(alt(('*', '&'))).parse_next(input)?;
+    let mut tokens = Vec::new();
+    if prefix.is_none() {
+        if let Some(name) = opt(type_name).parse_next(input)? {
+            tokens.push(name);
+        }
+    }
+    let rest: Vec<SelectorToken<'s>> = repeat(
+        0..,
+        alt((
+            class_name,
+            hover,
+            first_child,
+            last_child,
+            nth_child,
+            empty,
+            // `:focus` must come after the pseudo-classes it is a prefix of.
+            focus_within,
+            focus_visible,
+            focus,
+            active,
+            disabled,
+            checked,
+            not,
+        )),
+    )
+    .parse_next(input)?;
+    tokens.extend(rest);
+    Ok((prefix, tokens))
+}
+
+/// Wrap `sel` in the selectors for a single compound term, such as `&.name:hover`.
+fn compound(
+    mut sel: Box<Selector>,
+    prefix: Option<char>,
+    tokens: Vec<SelectorToken>,
+) -> Box<Selector> {
+    for tok in tokens {
         match tok {
             SelectorToken::Class(cls) => {
                 sel = Box::new(Selector::Class(cls.into(), sel));
             }
+            SelectorToken::Name(name) => {
+                sel = Box::new(Selector::Name(name.into(), sel));
+            }
             SelectorToken::Hover => {
                 sel = Box::new(Selector::Hover(sel));
             }
@@ -180,6 +436,24 @@ fn combo_selector(input: &mut &str) -> PResult<Box<Selector>> {
             SelectorToken::FocusVisible => {
                 sel = Box::new(Selector::FocusVisible(sel));
             }
+            SelectorToken::NthChild(a, b) => {
+                sel = Box::new(Selector::NthChild(a, b, sel));
+            }
+            SelectorToken::Empty => {
+                sel = Box::new(Selector::Empty(sel));
+            }
+            SelectorToken::Active => {
+                sel = Box::new(Selector::Active(sel));
+            }
+            SelectorToken::Disabled => {
+                sel = Box::new(Selector::Disabled(sel));
+            }
+            SelectorToken::Checked => {
+                sel = Box::new(Selector::Checked(sel));
+            }
+            SelectorToken::Not(inner) => {
+                sel = Box::new(Selector::Not(inner, sel));
+            }
         }
     }
     if let Some(ch) = prefix {
@@ -187,7 +461,7 @@ fn combo_selector(input: &mut &str) -> PResult<Box<Selector>> {
             sel = Box::new(Selector::Current(sel));
         }
     }
-    Ok(sel)
+    sel
 }
 
 impl Selector {
@@ -196,114 +470,213 @@ impl Selector {
     }
 
     fn either(input: &mut &str) -> PResult<Box<Selector>> {
-        separated(1.., Self::desc_selector, (space0, ',', space0))
-            .map(|mut items: Vec<Box<Selector>>| {
-                if items.len() == 1 {
-                    items.pop().unwrap()
-                } else {
-                    Box::new(Selector::Either(items))
-                }
-            })
-            .parse_next(input)
+        separated(
+            1..,
+            alt((
+                Self::media_selector,
+                Self::container_selector,
+                Self::desc_selector,
+            )),
+            (space0, ',', space0),
+        )
+        .map(|mut items: Vec<Box<Selector>>| {
+            if items.len() == 1 {
+                items.pop().unwrap()
+            } else {
+                Box::new(Selector::Either(items))
+            }
+        })
+        .parse_next(input)
+    }
+
+    fn media_selector(input: &mut &str) -> PResult<Box<Selector>> {
+        let features = media_query.parse_next(input)?;
+        let sel = Self::query_target(input)?;
+        Ok(Box::new(Selector::Media(features, sel)))
+    }
+
+    fn container_selector(input: &mut &str) -> PResult<Box<Selector>> {
+        let conditions = container_query.parse_next(input)?;
+        let sel = Self::query_target(input)?;
+        Ok(Box::new(Selector::Container(conditions, sel)))
+    }
+
+    /// Parse the selector which optionally follows a media or container query.
+    fn query_target(input: &mut &str) -> PResult<Box<Selector>> {
+        let sel = opt(preceded(ancestor, Self::desc_selector)).parse_next(input)?;
+        Ok(sel.unwrap_or_else(|| Box::new(Selector::Accept)))
     }
 
     fn desc_selector(input: &mut &str) -> PResult<Box<Selector>> {
-        let mut sel = combo_selector.parse_next(input)?;
-        while parent.parse_next(input).is_ok() {
-            sel = Box::new(Selector::Parent(sel));
-            let (prefix, classes) = simple_selector.parse_next(input)?;
-            for tok in classes {
-                match tok {
-                    SelectorToken::Class(cls) => {
-                        sel = Box::new(Selector::Class(cls.into(), sel));
-                    }
-                    SelectorToken::Hover => {
-                        sel = Box::new(Selector::Hover(sel));
-                    }
-                    SelectorToken::FirstChild => {
-                        sel = Box::new(Selector::FirstChild(sel));
-                    }
-                    SelectorToken::LastChild => {
-                        sel = Box::new(Selector::LastChild(sel));
-                    }
-                    SelectorToken::Focus => {
-                        sel = Box::new(Selector::Focus(sel));
-                    }
-                    SelectorToken::FocusWithin => {
-                        sel = Box::new(Selector::FocusWithin(sel));
-                    }
-                    SelectorToken::FocusVisible => {
-                        sel = Box::new(Selector::FocusVisible(sel));
-                    }
-                }
-            }
-            if let Some(ch) = prefix {
-                if ch == '&' {
-                    sel = Box::new(Selector::Current(sel));
-                }
-            }
+        let (prefix, tokens) = simple_selector.parse_next(input)?;
+        let mut sel = compound(Box::new(Selector::Accept), prefix, tokens);
+        while let Some(combinator) =
+            opt(alt((parent, previous_sibling, earlier_sibling, ancestor))).parse_next(input)?
+        {
+            sel = Box::new(match combinator {
+                Combinator::Parent => Selector::Parent(sel),
+                Combinator::Ancestor => Selector::Ancestor(sel),
+                Combinator::PreviousSibling => Selector::PreviousSibling(sel),
+                Combinator::EarlierSibling => Selector::EarlierSibling(sel),
+            });
+            let (prefix, tokens) = simple_selector.parse_next(input)?;
+            sel = compound(sel, prefix, tokens);
         }
 
         Ok(sel)
     }
 
     /// Returns a number indicating how many levels up the entity ancestor hierarchy we might
-    /// have to search to look for classes.
+    /// have to search to look for classes. The ancestors matched by a descendant combinator
+    /// aren't counted; use [`Selector::uses_ancestor`] to detect changes to those.
     pub(crate) fn depth(&self) -> usize {
         match self {
             Selector::Accept => 1,
-            Selector::Class(_, next) => next.depth(),
-            Selector::Hover(next)
+            Selector::Class(_, next)
+            | Selector::Name(_, next)
+            | Selector::Hover(next)
             | Selector::Focus(next)
             | Selector::FocusWithin(next)
             | Selector::FocusVisible(next)
             | Selector::FirstChild(next)
-            | Selector::LastChild(next) => next.depth(),
-            Selector::Current(next) => next.depth(),
+            | Selector::LastChild(next)
+            | Selector::NthChild(_, _, next)
+            | Selector::Empty(next)
+            | Selector::Active(next)
+            | Selector::Disabled(next)
+            | Selector::Checked(next)
+            | Selector::Current(next)
+            | Selector::PreviousSibling(next)
+            | Selector::EarlierSibling(next)
+            | Selector::Media(_, next)
+            | Selector::Container(_, next) => next.depth(),
+            Selector::Not(inner, next) => inner.depth().max(next.depth()),
             Selector::Parent(next) => next.depth() + 1,
+            Selector::Ancestor(_) => 1,
             Selector::Either(opts) => opts.iter().map(|next| next.depth()).max().unwrap_or(0),
         }
     }
 
-    /// Returns whether this selector uses the hover pseudo-class.
-    pub(crate) fn uses_hover(&self) -> bool {
+    /// Returns the specificity of this selector. Every term of the selector counts, including
+    /// the terms which match ancestors and siblings, and a `:not(...)` counts as its argument.
+    /// Media and container queries don't add to the specificity. For a list of alternatives,
+    /// this is the specificity of the most specific alternative.
+    pub(crate) fn specificity(&self) -> Specificity {
         match self {
-            Selector::Accept => false,
-            Selector::Class(_, next) => next.uses_hover(),
-            Selector::Hover(_) => true,
-            Selector::Focus(next)
+            Selector::Accept => Specificity::default(),
+            Selector::Class(_, next)
+            | Selector::Hover(next)
+            | Selector::Focus(next)
             | Selector::FocusWithin(next)
             | Selector::FocusVisible(next)
             | Selector::FirstChild(next)
             | Selector::LastChild(next)
-            | Selector::Current(next) => next.uses_hover(),
-            Selector::Parent(next) => next.uses_hover(),
+            | Selector::NthChild(_, _, next)
+            | Selector::Empty(next)
+            | Selector::Active(next)
+            | Selector::Disabled(next)
+            | Selector::Checked(next) => Specificity::new(1, 0) + next.specificity(),
+            Selector::Name(_, next) => Specificity::new(0, 1) + next.specificity(),
+            Selector::Not(inner, next) => inner.specificity() + next.specificity(),
+            Selector::Current(next)
+            | Selector::Parent(next)
+            | Selector::Ancestor(next)
+            | Selector::PreviousSibling(next)
+            | Selector::EarlierSibling(next)
+            | Selector::Media(_, next)
+            | Selector::Container(_, next) => next.specificity(),
             Selector::Either(opts) => opts
                 .iter()
-                .map(|next| next.uses_hover())
+                .map(|next| next.specificity())
                 .max()
-                .unwrap_or(false),
+                .unwrap_or_default(),
         }
     }
 
+    /// Returns whether this selector uses a descendant combinator, which means that it can be
+    /// affected by changes to any ancestor of the element.
+    pub(crate) fn uses_ancestor(&self) -> bool {
+        self.contains(&|sel| matches!(sel, Selector::Ancestor(_)))
+    }
+
     /// Returns whether this selector uses the hover pseudo-class.
+    pub(crate) fn uses_hover(&self) -> bool {
+        self.contains(&|sel| matches!(sel, Selector::Hover(_)))
+    }
+
+    /// Returns whether this selector uses the focus-within pseudo-class.
     pub(crate) fn uses_focus_within(&self) -> bool {
+        self.contains(&|sel| matches!(sel, Selector::FocusWithin(_)))
+    }
+
+    /// Returns whether this selector uses pseudo-classes which depend on the state of the
+    /// element: `:active`, `:disabled` or `:checked`.
+    pub(crate) fn uses_state(&self) -> bool {
+        self.contains(&|sel| {
+            matches!(
+                sel,
+                Selector::Active(_) | Selector::Disabled(_) | Selector::Checked(_)
+            )
+        })
+    }
+
+    /// Returns whether this selector depends on the element's siblings or children, which means
+    /// that it can be affected by changes to the hierarchy.
+    pub(crate) fn uses_structure(&self) -> bool {
+        self.contains(&|sel| {
+            matches!(
+                sel,
+                Selector::FirstChild(_)
+                    | Selector::LastChild(_)
+                    | Selector::NthChild(..)
+                    | Selector::Empty(_)
+                    | Selector::PreviousSibling(_)
+                    | Selector::EarlierSibling(_)
+            )
+        })
+    }
+
+    /// Returns whether this selector depends on media queries, which means that it can be
+    /// affected by changes to the window.
+    pub(crate) fn uses_media(&self) -> bool {
+        self.contains(&|sel| matches!(sel, Selector::Media(..)))
+    }
+
+    /// Returns whether this selector depends on container queries, which means that it can be
+    /// affected by changes to the layout of an ancestor.
+    pub(crate) fn uses_container(&self) -> bool {
+        self.contains(&|sel| matches!(sel, Selector::Container(..)))
+    }
+
+    /// Returns whether this selector, or any selector nested within it, satisfies `pred`.
+    fn contains(&self, pred: &impl Fn(&Selector) -> bool) -> bool {
+        if pred(self) {
+            return true;
+        }
         match self {
             Selector::Accept => false,
-            Selector::Class(_, next) => next.uses_hover(),
-            Selector::FocusWithin(_) => true,
-            Selector::Hover(next)
+            Selector::Class(_, next)
+            | Selector::Name(_, next)
+            | Selector::Hover(next)
             | Selector::Focus(next)
+            | Selector::FocusWithin(next)
             | Selector::FocusVisible(next)
             | Selector::FirstChild(next)
             | Selector::LastChild(next)
-            | Selector::Current(next) => next.uses_hover(),
-            Selector::Parent(next) => next.uses_hover(),
-            Selector::Either(opts) => opts
-                .iter()
-                .map(|next| next.uses_hover())
-                .max()
-                .unwrap_or(false),
+            | Selector::NthChild(_, _, next)
+            | Selector::Empty(next)
+            | Selector::Active(next)
+            | Selector::Disabled(next)
+            | Selector::Checked(next)
+            | Selector::Current(next)
+            | Selector::Parent(next)
+            | Selector::Ancestor(next)
+            | Selector::PreviousSibling(next)
+            | Selector::EarlierSibling(next)
+            | Selector::Media(_, next)
+            | Selector::Container(_, next) => next.contains(pred),
+            Selector::Not(inner, next) => inner.contains(pred) || next.contains(pred),
+            Selector::Either(opts) => opts.iter().any(|next| next.contains(pred)),
         }
     }
 }
@@ -319,34 +692,114 @@ impl std::str::FromStr for Selector {
     }
 }
 
+impl Selector {
+    /// When serializing, a combinator which immediately follows another needs a `*` term
+    /// in between.
+    fn wildcard(&self) -> &'static str {
+        match self {
+            Selector::Parent(_)
+            | Selector::Ancestor(_)
+            | Selector::PreviousSibling(_)
+            | Selector::EarlierSibling(_) => "* ",
+            _ => " ",
+        }
+    }
+
+    /// If this is one of the terms of a compound selector, such as a class name or a
+    /// pseudo-class, returns the selector that it applies to.
+    fn term_next(&self) -> Option<&Selector> {
+        match self {
+            Selector::Class(_, next)
+            | Selector::Name(_, next)
+            | Selector::Hover(next)
+            | Selector::Focus(next)
+            | Selector::FocusWithin(next)
+            | Selector::FocusVisible(next)
+            | Selector::FirstChild(next)
+            | Selector::LastChild(next)
+            | Selector::NthChild(_, _, next)
+            | Selector::Empty(next)
+            | Selector::Active(next)
+            | Selector::Disabled(next)
+            | Selector::Checked(next)
+            | Selector::Not(_, next) => Some(next),
+            _ => None,
+        }
+    }
+
+    /// Serialize the selector which follows a media or container query.
+    fn fmt_query_target(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Selector::Accept => Ok(()),
+            _ => write!(f, " {}", self),
+        }
+    }
+
+    /// Serialize a single term of a compound selector.
+    fn fmt_term(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Selector::Class(name, _) => write!(f, ".{}", name),
+            Selector::Name(name, _) => write!(f, "{}", name),
+            Selector::Hover(_) => write!(f, ":hover"),
+            Selector::Focus(_) => write!(f, ":focus"),
+            Selector::FocusWithin(_) => write!(f, ":focus-within"),
+            Selector::FocusVisible(_) => write!(f, ":focus-visible"),
+            Selector::FirstChild(_) => write!(f, ":first-child"),
+            Selector::LastChild(_) => write!(f, ":last-child"),
+            Selector::NthChild(a, b, _) => match (a, b) {
+                (0, b) => write!(f, ":nth-child({})", b),
+                (a, 0) => write!(f, ":nth-child({}n)", a),
+                (a, b) => write!(f, ":nth-child({}n{:+})", a, b),
+            },
+            Selector::Empty(_) => write!(f, ":empty"),
+            Selector::Active(_) => write!(f, ":active"),
+            Selector::Disabled(_) => write!(f, ":disabled"),
+            Selector::Checked(_) => write!(f, ":checked"),
+            Selector::Not(inner, _) => write!(f, ":not({})", inner),
+            _ => Ok(()),
+        }
+    }
+}
+
 impl fmt::Display for Selector {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Selector::Accept => Ok(()),
             Selector::Current(prev) => {
                 // Because 'current' comes first, reverse order
-                let mut str = String::with_capacity(64);
+                let mut terms = Vec::new();
                 let mut p = prev.as_ref();
-                while let Selector::Class(name, desc) = p {
-                    str.insert_str(0, name);
-                    str.insert(0, '.');
-                    p = desc.as_ref()
+                while let Some(next) = p.term_next() {
+                    terms.push(p);
+                    p = next;
                 }
-                str.insert(0, '&');
-                write!(f, "{}{}", p, str)
+                write!(f, "{}&", p)?;
+                terms.iter().rev().try_for_each(|term| term.fmt_term(f))
+            }
+            Selector::Parent(prev) => write!(f, "{}{}> ", prev, prev.wildcard()),
+            Selector::Ancestor(prev) => write!(f, "{}{}", prev, prev.wildcard()),
+            Selector::PreviousSibling(prev) => write!(f, "{}{}+ ", prev, prev.wildcard()),
+            Selector::EarlierSibling(prev) => write!(f, "{}{}~ ", prev, prev.wildcard()),
+            Selector::Media(features, next) => {
+                write!(f, "@media ")?;
+                for (index, feature) in features.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " and ")?;
+                    }
+                    feature.fmt(f)?;
+                }
+                next.fmt_query_target(f)
+            }
+            Selector::Container(conditions, next) => {
+                write!(f, "@container ")?;
+                for (index, condition) in conditions.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " and ")?;
+                    }
+                    condition.fmt(f)?;
+                }
+                next.fmt_query_target(f)
             }
-
-            Selector::Class(name, prev) => write!(f, "{}.{}", prev, name),
-            Selector::Hover(prev) => write!(f, "{}:hover", prev),
-            Selector::Focus(prev) => write!(f, "{}:focus", prev),
-            Selector::FocusWithin(prev) => write!(f, "{}:focus-within", prev),
-            Selector::FocusVisible(prev) => write!(f, "{}:focus-visible", prev),
-            Selector::FirstChild(prev) => write!(f, "{}:first-child", prev),
-            Selector::LastChild(prev) => write!(f, "{}:last-child", prev),
-            Selector::Parent(prev) => match prev.as_ref() {
-                Selector::Parent(_) => write!(f, "{}* > ", prev),
-                _ => write!(f, "{} > ", prev),
-            },
             Selector::Either(items) => {
                 for (index, item) in items.iter().enumerate() {
                     if index > 0 {
@@ -356,6 +809,12 @@ impl fmt::Display for Selector {
                 }
                 Ok(())
             }
+            _ => {
+                if let Some(prev) = self.term_next() {
+                    prev.fmt(f)?;
+                }
+                self.fmt_term(f)
+            }
         }
     }
 }
@@ -495,6 +954,231 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_descendant() {
+        assert_eq!(
+            ".foo .bar".parse::<Selector>().unwrap(),
+            Selector::Class(
+                "bar".into(),
+                Box::new(Selector::Ancestor(Box::new(Selector::Class(
+                    "foo".into(),
+                    Box::new(Selector::Accept)
+                ))))
+            )
+        );
+        assert_eq!(
+            "dialog > .body button.primary"
+                .parse::<Selector>()
+                .unwrap()
+                .to_string(),
+            "dialog > .body button.primary",
+        );
+        assert_eq!(
+            ".foo * > &".parse::<Selector>().unwrap().to_string(),
+            ".foo * > &",
+        );
+        // Trailing whitespace is not a combinator.
+        let mut input = ".foo .bar {";
+        assert_eq!(
+            Selector::parser(&mut input).unwrap().to_string(),
+            ".foo .bar"
+        );
+        assert_eq!(input, " {");
+        let sel = ".foo .bar".parse::<Selector>().unwrap();
+        assert_eq!(sel.depth(), 1);
+        assert!(sel.uses_ancestor());
+        let sel = ".foo .bar > .baz".parse::<Selector>().unwrap();
+        assert_eq!(sel.depth(), 2);
+        assert!(sel.uses_ancestor());
+        assert!(!".foo > .bar".parse::<Selector>().unwrap().uses_ancestor());
+    }
+
+    #[test]
+    fn test_parse_name() {
+        assert_eq!(
+            "button.primary:hover".parse::<Selector>().unwrap(),
+            Selector::Hover(Box::new(Selector::Class(
+                "primary".into(),
+                Box::new(Selector::Name("button".into(), Box::new(Selector::Accept)))
+            )))
+        );
+
+        // Pseudo-classes are found through name terms and descendant combinators.
+        let sel = "panel:focus-within button".parse::<Selector>().unwrap();
+        assert!(sel.uses_focus_within());
+        assert!(!sel.uses_hover());
+        let sel = "panel:hover button".parse::<Selector>().unwrap();
+        assert!(sel.uses_hover());
+        assert!(!sel.uses_focus_within());
+    }
+
+    #[test]
+    fn test_parse_state() {
+        assert_eq!(
+            "&:active:disabled".parse::<Selector>().unwrap(),
+            Selector::Current(Box::new(Selector::Disabled(Box::new(Selector::Active(
+                Box::new(Selector::Accept)
+            )))))
+        );
+        assert_eq!(
+            ":checked > &".parse::<Selector>().unwrap().to_string(),
+            ":checked > &",
+        );
+        assert_eq!(
+            ":focus-within".parse::<Selector>().unwrap(),
+            Selector::FocusWithin(Box::new(Selector::Accept))
+        );
+        assert_eq!(
+            ":focus-visible".parse::<Selector>().unwrap(),
+            Selector::FocusVisible(Box::new(Selector::Accept))
+        );
+
+        let sel = ".group:focus-within > &:checked"
+            .parse::<Selector>()
+            .unwrap();
+        assert!(sel.uses_focus_within());
+        assert!(sel.uses_state());
+        assert!(!sel.uses_hover());
+        assert!(!sel.uses_structure());
+    }
+
+    #[test]
+    fn test_parse_not() {
+        assert_eq!(
+            "&:not(.foo, :hover)".parse::<Selector>().unwrap(),
+            Selector::Current(Box::new(Selector::Not(
+                Box::new(Selector::Either(vec![
+                    Box::new(Selector::Class("foo".into(), Box::new(Selector::Accept))),
+                    Box::new(Selector::Hover(Box::new(Selector::Accept))),
+                ])),
+                Box::new(Selector::Accept)
+            )))
+        );
+        let sel = ":not(.a > .b)".parse::<Selector>().unwrap();
+        assert_eq!(sel.to_string(), ":not(.a > .b)");
+        assert_eq!(sel.depth(), 2);
+        assert!(":not(:hover)".parse::<Selector>().unwrap().uses_hover());
+    }
+
+    #[test]
+    fn test_parse_nth_child() {
+        let nth = |s: &str| match s.parse::<Selector>().unwrap() {
+            Selector::NthChild(a, b, _) => (a, b),
+            _ => panic!("expected nth-child"),
+        };
+        assert_eq!(nth(":nth-child(2n+1)"), (2, 1));
+        assert_eq!(nth(":nth-child( -n + 3 )"), (-1, 3));
+        assert_eq!(nth(":nth-child(4)"), (0, 4));
+        assert_eq!(nth(":nth-child(3n)"), (3, 0));
+        assert_eq!(nth(":nth-child(n-2)"), (1, -2));
+        assert_eq!(nth(":nth-child(odd)"), (2, 1));
+        assert_eq!(nth(":nth-child(even)"), (2, 0));
+        assert!(":nth-child(x)".parse::<Selector>().is_err());
+
+        assert_eq!(
+            "&:nth-child(2n-1):empty"
+                .parse::<Selector>()
+                .unwrap()
+                .to_string(),
+            "&:nth-child(2n-1):empty",
+        );
+        assert!(":empty".parse::<Selector>().unwrap().uses_structure());
+    }
+
+    #[test]
+    fn test_parse_siblings() {
+        assert_eq!(
+            ":checked + &".parse::<Selector>().unwrap(),
+            Selector::Current(Box::new(Selector::PreviousSibling(Box::new(
+                Selector::Checked(Box::new(Selector::Accept))
+            ))))
+        );
+        assert_eq!(
+            ".a ~ .b".parse::<Selector>().unwrap(),
+            Selector::Class(
+                "b".into(),
+                Box::new(Selector::EarlierSibling(Box::new(Selector::Class(
+                    "a".into(),
+                    Box::new(Selector::Accept)
+                ))))
+            )
+        );
+        let sel = ".a > .b + * ~ &".parse::<Selector>().unwrap();
+        assert_eq!(sel.to_string(), ".a > .b + * ~ &");
+        assert_eq!(sel.depth(), 2);
+        assert!(sel.uses_structure());
+    }
+
+    #[test]
+    fn test_parse_media() {
+        use crate::style::media::Orientation;
+
+        assert_eq!(
+            "@media (max-width: 800px)".parse::<Selector>().unwrap(),
+            Selector::Media(
+                vec![MediaFeature::MaxWidth(800.)],
+                Box::new(Selector::Accept)
+            )
+        );
+        assert_eq!(
+            "@media (min-resolution: 2x) and (orientation: portrait) .foo > &"
+                .parse::<Selector>()
+                .unwrap(),
+            Selector::Media(
+                vec![
+                    MediaFeature::MinResolution(2.),
+                    MediaFeature::Orientation(Orientation::Portrait)
+                ],
+                Box::new(Selector::Current(Box::new(Selector::Parent(Box::new(
+                    Selector::Class("foo".into(), Box::new(Selector::Accept))
+                )))))
+            )
+        );
+        assert_eq!(
+            "@media (prefers-color-scheme: dark) &:hover, .bar"
+                .parse::<Selector>()
+                .unwrap()
+                .to_string(),
+            "@media (prefers-color-scheme: dark) &:hover, .bar",
+        );
+        assert!("@media (max-width: wide)".parse::<Selector>().is_err());
+
+        // Trailing whitespace is not part of the selector.
+        let mut input = "@media (max-height: 600px) {";
+        let sel = Selector::parser(&mut input).unwrap();
+        assert_eq!(sel.to_string(), "@media (max-height: 600px)");
+        assert_eq!(input, " {");
+        assert!(sel.uses_media());
+        assert!(!sel.uses_structure());
+    }
+
+    #[test]
+    fn test_parse_container() {
+        use crate::style::container::{Comparison, ContainerAxis};
+
+        assert_eq!(
+            "@container (>= 300px width)".parse::<Selector>().unwrap(),
+            Selector::Container(
+                vec![ContainerCondition {
+                    axis: ContainerAxis::Width,
+                    comparison: Comparison::GreaterEqual,
+                    value: 300.
+                }],
+                Box::new(Selector::Accept)
+            )
+        );
+        let sel = "@container (width < 300px) and (height >= 100px) &.row"
+            .parse::<Selector>()
+            .unwrap();
+        assert_eq!(
+            sel.to_string(),
+            "@container (width < 300px) and (height >= 100px) &.row"
+        );
+        assert!(sel.uses_container());
+        assert!(!sel.uses_media());
+        assert!("@container (depth > 1px)".parse::<Selector>().is_err());
+    }
+
     #[test]
     fn test_either() {
         assert_eq!(
@@ -508,4 +1192,26 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn test_specificity() {
+        let spec = |sel: &str| sel.parse::<Selector>().unwrap().specificity();
+        assert_eq!(spec("&"), Specificity::new(0, 0));
+        assert_eq!(spec(":hover"), Specificity::new(1, 0));
+        assert_eq!(spec("&.pressed"), Specificity::new(1, 0));
+        assert_eq!(spec(":hover.pressed"), Specificity::new(2, 0));
+        assert_eq!(spec(".dialog button.primary"), Specificity::new(2, 1));
+        assert_eq!(
+            spec("&:nth-child(2n+1):not(.selected)"),
+            Specificity::new(2, 0)
+        );
+        assert_eq!(spec(":hover > &"), Specificity::new(1, 0));
+        assert_eq!(
+            spec("@media (max-width: 800px) &.wide"),
+            Specificity::new(1, 0)
+        );
+        assert_eq!(spec("&.foo, .bar.baz"), Specificity::new(2, 0));
+        assert!(spec("button") < spec(".primary"));
+        assert!(spec(".primary") < spec(".primary button"));
+    }
 }
@@ -1,33 +1,111 @@
+use bevy::ecs::query::Has;
 use bevy::prelude::*;
 use bevy::{ecs::entity::Entity, utils::HashMap};
 use bevy_mod_picking::backend::HitData;
+use bevy_mod_picking::focus::PickingInteraction;
 use bevy_mod_picking::pointer::PointerId;
 
-use crate::{ElementClasses, Selector};
+use crate::{Checked, Container, ContainerSize, Disabled, ElementClasses, Selector};
+
+use super::{
+    container::ContainerCondition,
+    media::{MediaContext, MediaFeature},
+};
+
+/// Query for the components which determine the state-based pseudo-classes of an element.
+pub(crate) type ElementStateQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Has<Disabled>,
+        Has<Checked>,
+        Option<&'static PickingInteraction>,
+    ),
+>;
+
+/// Query for the size of elements which are query containers.
+pub(crate) type ContainerQuery<'w, 's> =
+    Query<'w, 's, Ref<'static, ContainerSize>, With<Container>>;
 
 pub struct SelectorMatcher<'w, 's, 'h> {
     classes_query: &'h Query<'w, 's, Ref<'static, ElementClasses>>,
+    names_query: &'h Query<'w, 's, &'static Name>,
+    state_query: &'h ElementStateQuery<'w, 's>,
     parent_query: &'h Query<'w, 's, &'static Parent, (With<Node>, With<Visibility>)>,
-    children_query: &'h Query<'w, 's, &'static Children, (With<Node>, With<Visibility>)>,
+    children_query: &'h Query<'w, 's, Ref<'static, Children>, (With<Node>, With<Visibility>)>,
     hover_map: &'h HashMap<PointerId, HashMap<Entity, HitData>>,
     focus: Option<Entity>,
+    media: MediaContext,
+    containers: &'h ContainerQuery<'w, 's>,
 }
 
 impl<'w, 's, 'h> SelectorMatcher<'w, 's, 'h> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         query: &'h Query<'w, 's, Ref<'static, ElementClasses>>,
+        names_query: &'h Query<'w, 's, &'static Name>,
+        state_query: &'h ElementStateQuery<'w, 's>,
         parent_query: &'h Query<'w, 's, &'static Parent, (With<Node>, With<Visibility>)>,
-        children_query: &'h Query<'w, 's, &'static Children, (With<Node>, With<Visibility>)>,
+        children_query: &'h Query<'w, 's, Ref<'static, Children>, (With<Node>, With<Visibility>)>,
         hover_map: &'h HashMap<PointerId, HashMap<Entity, HitData>>,
         focus: Option<Entity>,
+        media: MediaContext,
+        containers: &'h ContainerQuery<'w, 's>,
     ) -> Self {
         Self {
             classes_query: query,
+            names_query,
+            state_query,
             parent_query,
             children_query,
             hover_map,
             focus,
+            media,
+            containers,
+        }
+    }
+
+    /// The properties of the window which media queries are evaluated against.
+    pub fn media(&self) -> &MediaContext {
+        &self.media
+    }
+
+    /// True if the window satisfies all of the media features.
+    pub fn is_media_match(&self, features: &[MediaFeature]) -> bool {
+        features.iter().all(|feature| feature.matches(&self.media))
+    }
+
+    /// Returns the nearest ancestor of this entity which is a query container.
+    fn container(&self, entity: &Entity) -> Option<Entity> {
+        let mut e = *entity;
+        while let Ok(parent) = self.parent_query.get(e) {
+            e = parent.get();
+            if self.containers.contains(e) {
+                return Some(e);
+            }
         }
+        None
+    }
+
+    /// True if the nearest container ancestor of this entity satisfies all of the conditions.
+    /// If there is no container, the conditions are never satisfied.
+    pub fn is_container_match(&self, entity: &Entity, conditions: &[ContainerCondition]) -> bool {
+        let Some(container_size) = self
+            .container(entity)
+            .and_then(|container| self.containers.get(container).ok())
+        else {
+            return false;
+        };
+        let size = container_size.size();
+        conditions.iter().all(|condition| condition.matches(size))
+    }
+
+    /// True if the size of the nearest container ancestor of this entity has changed since
+    /// the last time styles were updated, or if the container was just added.
+    pub fn is_container_changed(&self, entity: &Entity) -> bool {
+        self.container(entity)
+            .and_then(|container| self.containers.get(container).ok())
+            .is_some_and(|container_size| container_size.is_changed())
     }
 
     /// True if the given entity, or an ancestor of it, is in the hover map for PointerId::Mouse.
@@ -102,6 +180,59 @@ impl<'w, 's, 'h> SelectorMatcher<'w, 's, 'h> {
         }
     }
 
+    /// True if this entity is being pressed.
+    ///
+    /// This is used to determine whether to apply the :active pseudo-class.
+    pub fn is_active(&self, entity: &Entity) -> bool {
+        matches!(
+            self.state_query.get(*entity),
+            Ok((_, _, Some(PickingInteraction::Pressed)))
+        )
+    }
+
+    /// True if this entity has the [`Disabled`] component.
+    pub fn is_disabled(&self, entity: &Entity) -> bool {
+        self.state_query
+            .get(*entity)
+            .is_ok_and(|(disabled, _, _)| disabled)
+    }
+
+    /// True if this entity has the [`Checked`] component.
+    pub fn is_checked(&self, entity: &Entity) -> bool {
+        self.state_query
+            .get(*entity)
+            .is_ok_and(|(_, checked, _)| checked)
+    }
+
+    /// True if this entity has no UI node children.
+    pub fn is_empty(&self, entity: &Entity) -> bool {
+        self.children_query
+            .get(*entity)
+            .map_or(true, |children| children.is_empty())
+    }
+
+    /// Returns the siblings of this entity, and its index among them.
+    fn siblings(&self, entity: &Entity) -> Option<(&[Entity], usize)> {
+        let parent = self.parent_query.get(*entity).ok()?;
+        let children = self.children_query.get(parent.get()).ok()?;
+        let children: &[Entity] = children.into_inner();
+        let index = children.iter().position(|child| child == entity)?;
+        Some((children, index))
+    }
+
+    /// True if the 1-based position of this entity among its siblings is `a * n + b`, for
+    /// some `n >= 0`.
+    pub fn is_nth_child(&self, entity: &Entity, a: i32, b: i32) -> bool {
+        let Some((_, index)) = self.siblings(entity) else {
+            return false;
+        };
+        let offset = index as i32 + 1 - b;
+        match a {
+            0 => offset == 0,
+            _ => offset % a == 0 && offset / a >= 0,
+        }
+    }
+
     /// Given an array of match params representing the element's ancestor chain, match the
     /// selector expression with the params.
     pub(crate) fn selector_match(&self, selector: &Selector, entity: &Entity) -> bool {
@@ -111,6 +242,10 @@ impl<'w, 's, 'h> SelectorMatcher<'w, 's, 'h> {
                 Ok(classes) => classes.0.contains(cls) && self.selector_match(next, entity),
                 _ => false,
             },
+            Selector::Name(name, next) => match self.names_query.get(*entity) {
+                Ok(n) => n.as_str() == name && self.selector_match(next, entity),
+                _ => false,
+            },
             Selector::Hover(next) => self.is_hovering(entity) && self.selector_match(next, entity),
             Selector::Focus(next) => self.is_focused(entity) && self.selector_match(next, entity),
             Selector::FocusWithin(next) => {
@@ -125,11 +260,51 @@ impl<'w, 's, 'h> SelectorMatcher<'w, 's, 'h> {
             Selector::LastChild(next) => {
                 self.is_last_child(entity) && self.selector_match(next, entity)
             }
+            Selector::NthChild(a, b, next) => {
+                self.is_nth_child(entity, *a, *b) && self.selector_match(next, entity)
+            }
+            Selector::Empty(next) => self.is_empty(entity) && self.selector_match(next, entity),
+            Selector::Active(next) => self.is_active(entity) && self.selector_match(next, entity),
+            Selector::Disabled(next) => {
+                self.is_disabled(entity) && self.selector_match(next, entity)
+            }
+            Selector::Checked(next) => self.is_checked(entity) && self.selector_match(next, entity),
+            Selector::Not(inner, next) => {
+                !self.selector_match(inner, entity) && self.selector_match(next, entity)
+            }
             Selector::Current(next) => self.selector_match(next, entity),
             Selector::Parent(next) => match self.parent_query.get(*entity) {
                 Ok(parent) => self.selector_match(next, &parent.get()),
                 _ => false,
             },
+            Selector::Ancestor(next) => {
+                let mut e = *entity;
+                while let Ok(parent) = self.parent_query.get(e) {
+                    e = parent.get();
+                    if self.selector_match(next, &e) {
+                        return true;
+                    }
+                }
+                false
+            }
+            Selector::PreviousSibling(next) => match self.siblings(entity) {
+                Some((siblings, index)) if index > 0 => {
+                    self.selector_match(next, &siblings[index - 1])
+                }
+                _ => false,
+            },
+            Selector::EarlierSibling(next) => match self.siblings(entity) {
+                Some((siblings, index)) => siblings[..index]
+                    .iter()
+                    .any(|sibling| self.selector_match(next, sibling)),
+                None => false,
+            },
+            Selector::Media(features, next) => {
+                self.is_media_match(features) && self.selector_match(next, entity)
+            }
+            Selector::Container(conditions, next) => {
+                self.is_container_match(entity, conditions) && self.selector_match(next, entity)
+            }
             Selector::Either(opts) => opts.iter().any(|next| self.selector_match(next, entity)),
         }
     }
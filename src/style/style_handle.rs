@@ -38,6 +38,11 @@ impl StyleHandle {
         self.0.as_ref().depth()
     }
 
+    /// Return whether any of the selectors use a descendant combinator.
+    pub fn uses_ancestor(&self) -> bool {
+        self.0.as_ref().uses_ancestor()
+    }
+
     /// Return whether any of the selectors use the ':hover' pseudo-class.
     pub fn uses_hover(&self) -> bool {
         self.0.as_ref().uses_hover()
@@ -47,6 +52,32 @@ impl StyleHandle {
     pub fn uses_focus_within(&self) -> bool {
         self.0.as_ref().uses_focus_within()
     }
+
+    /// Return whether any of the selectors use the ':active', ':disabled' or ':checked'
+    /// pseudo-classes.
+    pub fn uses_state(&self) -> bool {
+        self.0.as_ref().uses_state()
+    }
+
+    /// Return whether any of the selectors depend on the siblings or children of an element.
+    pub fn uses_structure(&self) -> bool {
+        self.0.as_ref().uses_structure()
+    }
+
+    /// Return whether any of the selectors use media queries.
+    pub fn uses_media(&self) -> bool {
+        self.0.as_ref().uses_media()
+    }
+
+    /// Return whether any of the selectors use container queries.
+    pub fn uses_container(&self) -> bool {
+        self.0.as_ref().uses_container()
+    }
+
+    /// Return whether this style defines or references any style variables.
+    pub fn uses_vars(&self) -> bool {
+        self.0.as_ref().uses_vars()
+    }
 }
 
 impl PartialEq for StyleHandle {
@@ -57,7 +88,7 @@ impl PartialEq for StyleHandle {
 }
 
 /// List of [`StyleHandle`]s which are attached to a given UiNode.
-#[derive(Component, Default)]
+#[derive(Component, Default, Clone)]
 pub struct ElementStyles {
     /// The collection of styles associated with this element.
     pub styles: Vec<StyleHandle>,
@@ -65,39 +96,101 @@ pub struct ElementStyles {
     /// How far up the hierarchy the selectors need to search
     pub(crate) selector_depth: usize,
 
+    /// Whether any selectors use a descendant combinator
+    pub(crate) uses_ancestor: bool,
+
     /// Whether any selectors use the :hover pseudo-class
     pub(crate) uses_hover: bool,
 
     /// Whether any selectors use the :focus-within pseudo-class
     pub(crate) uses_focus_within: bool,
+
+    /// Whether any selectors use the :active, :disabled or :checked pseudo-classes
+    pub(crate) uses_state: bool,
+
+    /// Whether any selectors depend on siblings or children
+    pub(crate) uses_structure: bool,
+
+    /// Whether any selectors use media queries
+    pub(crate) uses_media: bool,
+
+    /// Whether any selectors use container queries
+    pub(crate) uses_container: bool,
+
+    /// Whether any styles define or reference style variables
+    pub(crate) uses_vars: bool,
 }
 
 impl ElementStyles {
     pub fn new(styles: &[StyleHandle]) -> Self {
         let selector_depth = styles.iter().map(|s| s.depth()).max().unwrap_or(0);
+        let uses_ancestor = styles.iter().any(|s| s.uses_ancestor());
         let uses_hover = styles.iter().any(|s| s.uses_hover());
         let uses_focus_within = styles.iter().any(|s| s.uses_focus_within());
+        let uses_state = styles.iter().any(|s| s.uses_state());
+        let uses_structure = styles.iter().any(|s| s.uses_structure());
+        let uses_media = styles.iter().any(|s| s.uses_media());
+        let uses_container = styles.iter().any(|s| s.uses_container());
+        let uses_vars = styles.iter().any(|s| s.uses_vars());
         Self {
             styles: styles.to_vec(),
             selector_depth,
+            uses_ancestor,
             uses_hover,
             uses_focus_within,
+            uses_state,
+            uses_structure,
+            uses_media,
+            uses_container,
+            uses_vars,
         }
     }
 
     pub fn update(&mut self, styles: &[StyleHandle]) {
         self.styles = styles.to_vec();
         self.selector_depth = self.styles.iter().map(|s| s.depth()).max().unwrap_or(0);
+        self.uses_ancestor = self.styles.iter().any(|s| s.uses_ancestor());
         self.uses_hover = self.styles.iter().any(|s| s.uses_hover());
         self.uses_focus_within = self.styles.iter().any(|s| s.uses_focus_within());
+        self.uses_state = self.styles.iter().any(|s| s.uses_state());
+        self.uses_structure = self.styles.iter().any(|s| s.uses_structure());
+        self.uses_media = self.styles.iter().any(|s| s.uses_media());
+        self.uses_container = self.styles.iter().any(|s| s.uses_container());
+        self.uses_vars = self.styles.iter().any(|s| s.uses_vars());
     }
 }
 
-/// Component used to store inherited text style properties. This is set whenever an element
-/// has one or more style properties which affect text rendering, even if the element is not
-/// a text node itself. This is used to calculate the inherited text style for child nodes,
-/// and also whether or not the text style has changed.
-#[derive(Component, Default, PartialEq, Clone)]
+/// Styles which are applied to the descendants of the element they are attached to, rather
+/// than to the element itself. Each descendant UI node is matched against the selectors of
+/// these styles, and unconditional properties apply to every descendant.
+///
+/// Styles attached to an element with [`ElementStyles`] always take precedence over the ones it
+/// inherits. Among inherited styles, more specific selectors win, and styles from nearer
+/// ancestors win over styles from outer ones with the same specificity.
+#[derive(Component, Default, Clone)]
+pub struct DescendantStyles {
+    /// The collection of styles to apply to descendants.
+    pub styles: Vec<StyleHandle>,
+}
+
+impl DescendantStyles {
+    pub fn new(styles: &[StyleHandle]) -> Self {
+        Self {
+            styles: styles.to_vec(),
+        }
+    }
+}
+
+/// Cache of the descendant styles which apply within the subtree of an element with
+/// [`DescendantStyles`]: the ones inherited from its ancestors, followed by its own.
+#[derive(Component)]
+pub(crate) struct ScopedStyles(pub(crate) ElementStyles);
+
+/// Component used to store inherited text style properties and opacity. This is set whenever an
+/// element has one or more style properties which affect text rendering or opacity, even if the
+/// element is not a text node itself. This is used to calculate the inherited text style for
+/// child nodes, and also whether or not the text style has changed.
+#[derive(Component, PartialEq, Clone)]
 pub struct TextStyles {
     /// The collection of styles associated with this element.
     pub font: Option<Handle<Font>>,
@@ -107,4 +200,18 @@ pub struct TextStyles {
 
     /// Text color
     pub color: Option<Color>,
+
+    /// Combined opacity of the element and all of its ancestors.
+    pub opacity: f32,
+}
+
+impl Default for TextStyles {
+    fn default() -> Self {
+        Self {
+            font: None,
+            font_size: None,
+            color: None,
+            opacity: 1.,
+        }
+    }
 }
@@ -11,8 +11,15 @@ use bevy::{
 use crate::Cursor;
 
 use super::{
-    builder::StyleBuilder, computed::ComputedStyle, selector::Selector,
-    selector_matcher::SelectorMatcher, transition::Transition,
+    animation::Animation,
+    builder::StyleBuilder,
+    cascade::Cascade,
+    computed::ComputedStyle,
+    decoration::{BorderRadius, BoxShadow, Gradient},
+    selector::Selector,
+    selector_matcher::SelectorMatcher,
+    transition::Transition,
+    vars::{VarProperty, VarValue},
 };
 
 /// Controls behavior of bevy_mod_picking
@@ -32,6 +39,10 @@ pub enum StyleProp {
     BackgroundColor(Option<Color>),
     BorderColor(Option<Color>),
     Color(Option<Color>),
+    Opacity(f32),
+    BorderRadius(Option<BorderRadius>),
+    BoxShadow(Option<BoxShadow>),
+    BackgroundGradient(Option<Gradient>),
 
     ZIndex(Option<ui::ZIndex>),
 
@@ -118,7 +129,7 @@ pub enum StyleProp {
     OutlineWidth(ui::Val),
     OutlineOffset(ui::Val),
 
-    // TODO: Future planned features
+    // Cursors
     Cursor(Cursor),
     CursorImage(AssetPath<'static>),
     CursorOffset(IVec2),
@@ -132,6 +143,17 @@ pub enum StyleProp {
 
     // Transitions
     Transition(Vec<Transition>),
+
+    // Keyframe animations
+    Animation(Vec<Animation>),
+
+    // Variables
+    Var(String, VarValue),
+    VarRef(String, VarProperty),
+
+    /// Properties which take precedence over all properties which aren't important, like the
+    /// CSS `!important` flag.
+    Important(Vec<StyleProp>),
 }
 
 pub(crate) type SelectorList = Vec<(Box<Selector>, Vec<StyleProp>)>;
@@ -176,6 +198,11 @@ impl StyleSet {
             .unwrap_or(0)
     }
 
+    /// Return whether any of the selectors use a descendant combinator.
+    pub fn uses_ancestor(&self) -> bool {
+        self.selectors.iter().any(|s| s.0.uses_ancestor())
+    }
+
     /// Return whether any of the selectors use the ':hover' pseudo-class.
     pub fn uses_hover(&self) -> bool {
         self.selectors.iter().any(|s| s.0.uses_hover())
@@ -186,25 +213,74 @@ impl StyleSet {
         self.selectors.iter().any(|s| s.0.uses_focus_within())
     }
 
-    /// Merge the style properties into a computed `Style` object.
+    /// Return whether any of the selectors use the ':active', ':disabled' or ':checked'
+    /// pseudo-classes.
+    pub fn uses_state(&self) -> bool {
+        self.selectors.iter().any(|s| s.0.uses_state())
+    }
+
+    /// Return whether any of the selectors depend on the siblings or children of an element.
+    pub fn uses_structure(&self) -> bool {
+        self.selectors.iter().any(|s| s.0.uses_structure())
+    }
+
+    /// Return whether any of the selectors use media queries.
+    pub fn uses_media(&self) -> bool {
+        self.selectors.iter().any(|s| s.0.uses_media())
+    }
+
+    /// Return whether any of the selectors use container queries.
+    pub fn uses_container(&self) -> bool {
+        self.selectors.iter().any(|s| s.0.uses_container())
+    }
+
+    /// Return whether this style defines or references any style variables.
+    pub fn uses_vars(&self) -> bool {
+        fn is_var(p: &StyleProp) -> bool {
+            match p {
+                StyleProp::Var(..) | StyleProp::VarRef(..) => true,
+                StyleProp::Important(props) => props.iter().any(is_var),
+                _ => false,
+            }
+        }
+        self.props.iter().any(is_var)
+            || self
+                .selectors
+                .iter()
+                .any(|(_, props)| props.iter().any(is_var))
+    }
+
+    /// Merge the style variable definitions in a list of properties into the computed style.
+    /// The [`Cascade`] does this for all rules before applying any properties, so that variable
+    /// references can be resolved.
+    pub(super) fn apply_vars(attrs: &[StyleProp], computed: &mut ComputedStyle) {
+        for attr in attrs.iter() {
+            match attr {
+                StyleProp::Var(name, value) => {
+                    computed.vars.insert(name.clone(), *value);
+                }
+                StyleProp::Important(props) => Self::apply_vars(props, computed),
+                _ => {}
+            }
+        }
+    }
+
+    /// Merge the style properties into a computed `Style` object. Selectors which match are
+    /// applied in order of specificity, see [`Cascade`].
     pub fn apply_to(
         &self,
         computed: &mut ComputedStyle,
         matcher: &SelectorMatcher,
         entity: &Entity,
     ) {
-        // Apply unconditional styles
-        self.apply_attrs_to(&self.props, computed);
-
-        // Apply conditional styles
-        for (selector, props) in self.selectors.iter() {
-            if matcher.selector_match(selector, entity) {
-                self.apply_attrs_to(props, computed);
-            }
-        }
+        let mut cascade = Cascade::default();
+        cascade.add(self, matcher, entity);
+        cascade.apply_to(computed);
     }
 
-    fn apply_attrs_to(&self, attrs: &[StyleProp], computed: &mut ComputedStyle) {
+    /// Apply a list of properties to a computed style, in order. Important properties are
+    /// skipped, since they are applied separately by the [`Cascade`].
+    pub(crate) fn apply_attrs_to(attrs: &[StyleProp], computed: &mut ComputedStyle) {
         for attr in attrs.iter() {
             match attr {
                 StyleProp::BackgroundImage(image) => {
@@ -219,6 +295,18 @@ impl StyleSet {
                 StyleProp::Color(expr) => {
                     computed.color = *expr;
                 }
+                StyleProp::Opacity(expr) => {
+                    computed.opacity = Some(*expr);
+                }
+                StyleProp::BorderRadius(expr) => {
+                    computed.border_radius = *expr;
+                }
+                StyleProp::BoxShadow(expr) => {
+                    computed.box_shadow = *expr;
+                }
+                StyleProp::BackgroundGradient(expr) => {
+                    computed.background_gradient = expr.clone();
+                }
                 StyleProp::ZIndex(expr) => {
                     computed.z_index = *expr;
                 }
@@ -436,9 +524,15 @@ impl StyleSet {
                     computed.font_size = Some(*expr);
                 }
 
-                StyleProp::Cursor(_) => todo!(),
-                StyleProp::CursorImage(_) => todo!(),
-                StyleProp::CursorOffset(_) => todo!(),
+                StyleProp::Cursor(expr) => {
+                    computed.cursor = Some(*expr);
+                }
+                StyleProp::CursorImage(expr) => {
+                    computed.cursor_image = Some(expr.clone());
+                }
+                StyleProp::CursorOffset(expr) => {
+                    computed.cursor_offset = Some(*expr);
+                }
 
                 StyleProp::Scale(expr) => {
                     computed.scale_x = Some(*expr);
@@ -458,6 +552,17 @@ impl StyleSet {
                 }
 
                 StyleProp::Transition(trans) => computed.transitions.clone_from(trans),
+                StyleProp::Animation(anims) => computed.animations.clone_from(anims),
+
+                StyleProp::Var(..) => {}
+                StyleProp::VarRef(name, prop) => {
+                    // References to undefined variables, or to values of the wrong type, are
+                    // ignored.
+                    if let Some(resolved) = computed.vars.get(name).and_then(|v| prop.resolve(v)) {
+                        Self::apply_attrs_to(std::slice::from_ref(&resolved), computed);
+                    }
+                }
+                StyleProp::Important(..) => {}
             }
         }
     }
@@ -0,0 +1,888 @@
+use std::{fmt, sync::Arc};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    ui,
+    utils::{BoxedFuture, HashMap},
+};
+use winnow::{
+    ascii::multispace1,
+    combinator::{alt, repeat},
+    stream::AsChar,
+    token::{one_of, take_till, take_until, take_while},
+    PResult, Parser,
+};
+
+use crate::{
+    BorderRadius, BoxShadow, Cursor, DescendantStyles, ElementStyles, PointerEvents, StyleHandle,
+    StyleProp,
+};
+
+use super::{selector::Selector, style_props::StyleSet};
+
+/// A collection of named styles, loaded from a `.qss` file. The file consists of a list of
+/// named style blocks, each of which contains property declarations and selector blocks:
+///
+/// ```css
+/// /* Comments look like this. */
+/// button {
+///     display: flex;
+///     padding: 0 12px;
+///     background-color: #2a2a2e;
+///
+///     &:hover {
+///         background-color: #3a3a3e;
+///     }
+///
+///     .disabled > & {
+///         color: rgba(255, 255, 255, 0.5);
+///     }
+///
+///     @media (max-width: 800px) {
+///         padding: 0 6px;
+///     }
+/// }
+/// ```
+///
+/// Property names are the kebab-case names of the [`StyleProp`] variants, and selectors use
+/// the same syntax as the selectors passed to [`StyleHandle::build`].
+#[derive(Asset, TypePath, Default, Clone)]
+pub struct StyleSheet {
+    styles: HashMap<String, StyleHandle>,
+}
+
+impl StyleSheet {
+    /// Parse a stylesheet from text.
+    pub fn parse(text: &str) -> Result<Self, StyleSheetError> {
+        let mut input = text;
+        let mut styles = HashMap::new();
+        loop {
+            skip_space(&mut input);
+            if input.is_empty() {
+                break;
+            }
+            let name = ident
+                .parse_next(&mut input)
+                .map_err(|_| StyleSheetError::syntax(text, input, "expected style name"))?;
+            if styles.contains_key(name) {
+                return Err(StyleSheetError::syntax(
+                    text,
+                    name,
+                    format!("duplicate style '{}'", name),
+                ));
+            }
+            expect_open_brace(text, &mut input)?;
+
+            let mut style = StyleSet::new();
+            loop {
+                skip_space(&mut input);
+                if input.is_empty() {
+                    return Err(StyleSheetError::syntax(text, input, "expected '}'"));
+                } else if let Some(rest) = input.strip_prefix('}') {
+                    input = rest;
+                    break;
+                } else if input.starts_with(AsChar::is_alpha) {
+                    style.props.push(declaration(text, &mut input)?);
+                } else {
+                    let start = input;
+                    let selector = Selector::parser
+                        .parse_next(&mut input)
+                        .map_err(|_| StyleSheetError::syntax(text, start, "invalid selector"))?;
+                    expect_open_brace(text, &mut input)?;
+                    let mut props = Vec::new();
+                    loop {
+                        skip_space(&mut input);
+                        if input.is_empty() {
+                            return Err(StyleSheetError::syntax(text, input, "expected '}'"));
+                        } else if let Some(rest) = input.strip_prefix('}') {
+                            input = rest;
+                            break;
+                        }
+                        props.push(declaration(text, &mut input)?);
+                    }
+                    style.selectors.push((selector, props));
+                }
+            }
+            styles.insert(name.to_string(), StyleHandle(Arc::new(style)));
+        }
+        Ok(Self { styles })
+    }
+
+    /// Look up a style by name.
+    pub fn get(&self, name: &str) -> Option<StyleHandle> {
+        self.styles.get(name).cloned()
+    }
+
+    /// Iterate over the names of all of the styles in the sheet.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.styles.keys().map(String::as_str)
+    }
+}
+
+/// Error produced when loading a [`StyleSheet`].
+#[derive(Debug)]
+pub enum StyleSheetError {
+    /// The file could not be read.
+    Io(std::io::Error),
+
+    /// The file is not a valid stylesheet.
+    Syntax {
+        /// The line number, starting from 1.
+        line: usize,
+        /// Description of the problem.
+        message: String,
+    },
+}
+
+impl StyleSheetError {
+    /// Construct a syntax error for the location of `at`, which must be a slice of `text`.
+    fn syntax(text: &str, at: &str, message: impl Into<String>) -> Self {
+        let offset = (at.as_ptr() as usize).saturating_sub(text.as_ptr() as usize);
+        Self::Syntax {
+            line: text[..offset.min(text.len())].matches('\n').count() + 1,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for StyleSheetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StyleSheetError::Io(err) => write!(f, "could not read stylesheet: {}", err),
+            StyleSheetError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for StyleSheetError {}
+
+impl From<std::io::Error> for StyleSheetError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Asset loader for `.qss` files.
+#[derive(Default)]
+pub struct StyleSheetLoader;
+
+impl AssetLoader for StyleSheetLoader {
+    type Asset = StyleSheet;
+    type Settings = ();
+    type Error = StyleSheetError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<StyleSheet, StyleSheetError>> {
+        Box::pin(async move {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+            StyleSheet::parse(&text)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["qss"]
+    }
+}
+
+/// When a stylesheet is reloaded, replace the old versions of its styles with the new ones
+/// in any elements which use them, which causes those elements to be re-styled.
+pub(crate) fn reload_style_sheets(
+    mut events: EventReader<AssetEvent<StyleSheet>>,
+    sheets: Res<Assets<StyleSheet>>,
+    mut loaded: Local<HashMap<AssetId<StyleSheet>, StyleSheet>>,
+    mut query_elements: Query<&mut ElementStyles>,
+    mut query_descendants: Query<&mut DescendantStyles>,
+) {
+    for event in events.read() {
+        match event {
+            AssetEvent::Added { id } => {
+                if let Some(sheet) = sheets.get(*id) {
+                    loaded.insert(*id, sheet.clone());
+                }
+            }
+            AssetEvent::Modified { id } => {
+                let Some(sheet) = sheets.get(*id) else {
+                    continue;
+                };
+                if let Some(old) = loaded.insert(*id, sheet.clone()) {
+                    let replacements: Vec<(StyleHandle, StyleHandle)> = old
+                        .styles
+                        .into_iter()
+                        .filter_map(|(name, prev)| Some((prev, sheet.get(&name)?)))
+                        .collect();
+                    for mut element_styles in query_elements.iter_mut() {
+                        if let Some(styles) = replace_styles(&element_styles.styles, &replacements)
+                        {
+                            element_styles.update(&styles);
+                        }
+                    }
+                    for mut descendant_styles in query_descendants.iter_mut() {
+                        if let Some(styles) =
+                            replace_styles(&descendant_styles.styles, &replacements)
+                        {
+                            descendant_styles.styles = styles;
+                        }
+                    }
+                }
+            }
+            AssetEvent::Removed { id } => {
+                loaded.remove(id);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the list of styles with any reloaded styles replaced, or `None` if there were none.
+fn replace_styles(
+    styles: &[StyleHandle],
+    replacements: &[(StyleHandle, StyleHandle)],
+) -> Option<Vec<StyleHandle>> {
+    let replacement = |style: &StyleHandle| {
+        replacements
+            .iter()
+            .find(|(prev, _)| prev == style)
+            .map(|(_, next)| next.clone())
+    };
+    if !styles.iter().any(|style| replacement(style).is_some()) {
+        return None;
+    }
+    Some(
+        styles
+            .iter()
+            .map(|style| replacement(style).unwrap_or_else(|| style.clone()))
+            .collect(),
+    )
+}
+
+fn comment(input: &mut &str) -> PResult<()> {
+    ("/*", take_until(0.., "*/"), "*/").void().parse_next(input)
+}
+
+fn skip_space(input: &mut &str) {
+    let _: PResult<()> = repeat(0.., alt((multispace1.void(), comment))).parse_next(input);
+}
+
+fn ident<'s>(input: &mut &'s str) -> PResult<&'s str> {
+    (
+        one_of(AsChar::is_alpha),
+        take_while(0.., (AsChar::is_alphanum, '-', '_')),
+    )
+        .recognize()
+        .parse_next(input)
+}
+
+fn expect_open_brace(text: &str, input: &mut &str) -> Result<(), StyleSheetError> {
+    skip_space(input);
+    match input.strip_prefix('{') {
+        Some(rest) => {
+            *input = rest;
+            Ok(())
+        }
+        None => Err(StyleSheetError::syntax(text, input, "expected '{'")),
+    }
+}
+
+/// Parse a `name: value;` declaration. The semicolon may be omitted before a closing brace.
+fn declaration(text: &str, input: &mut &str) -> Result<StyleProp, StyleSheetError> {
+    let name = ident
+        .parse_next(input)
+        .map_err(|_| StyleSheetError::syntax(text, input, "expected property name"))?;
+    skip_space(input);
+    *input = input
+        .strip_prefix(':')
+        .ok_or_else(|| StyleSheetError::syntax(text, input, "expected ':'"))?;
+    let value: &str = take_till::<_, _, winnow::error::ContextError>(0.., [';', '}'])
+        .parse_next(input)
+        .map_err(|_| StyleSheetError::syntax(text, input, "expected property value"))?;
+    if input.is_empty() {
+        return Err(StyleSheetError::syntax(
+            text,
+            input,
+            "unexpected end of file",
+        ));
+    }
+    if let Some(rest) = input.strip_prefix(';') {
+        *input = rest;
+    }
+    let value = value.trim();
+    let prop = match value.strip_suffix("!important") {
+        Some(value) => {
+            parse_property(name, value.trim_end()).map(|prop| StyleProp::Important(vec![prop]))
+        }
+        None => parse_property(name, value),
+    };
+    prop.map_err(|msg| StyleSheetError::syntax(text, name, msg))
+}
+
+/// Convert a property name and value into a [`StyleProp`].
+fn parse_property(name: &str, value: &str) -> Result<StyleProp, String> {
+    Ok(match name {
+        "background-image" => StyleProp::BackgroundImage(parse_path(value)?),
+        "background-color" => StyleProp::BackgroundColor(parse_color(value)?),
+        "border-color" => StyleProp::BorderColor(parse_color(value)?),
+        "border-radius" => StyleProp::BorderRadius(parse_border_radius(value)?),
+        "box-shadow" => StyleProp::BoxShadow(parse_box_shadow(value)?),
+        "color" => StyleProp::Color(parse_color(value)?),
+        "z-index" => StyleProp::ZIndex(match value {
+            "none" => None,
+            _ => Some(ui::ZIndex::Local(parse_number(value)?)),
+        }),
+
+        "display" => StyleProp::Display(keyword(
+            value,
+            &[
+                ("flex", ui::Display::Flex),
+                ("grid", ui::Display::Grid),
+                ("none", ui::Display::None),
+            ],
+        )?),
+        "position" => StyleProp::Position(keyword(
+            value,
+            &[
+                ("relative", ui::PositionType::Relative),
+                ("absolute", ui::PositionType::Absolute),
+            ],
+        )?),
+        "overflow" => StyleProp::Overflow(parse_overflow(value)?),
+        "overflow-x" => StyleProp::OverflowX(parse_overflow(value)?),
+        "overflow-y" => StyleProp::OverflowY(parse_overflow(value)?),
+        "direction" => StyleProp::Direction(keyword(
+            value,
+            &[
+                ("inherit", ui::Direction::Inherit),
+                ("ltr", ui::Direction::LeftToRight),
+                ("rtl", ui::Direction::RightToLeft),
+            ],
+        )?),
+
+        "left" => StyleProp::Left(parse_length(value)?),
+        "right" => StyleProp::Right(parse_length(value)?),
+        "top" => StyleProp::Top(parse_length(value)?),
+        "bottom" => StyleProp::Bottom(parse_length(value)?),
+
+        "width" => StyleProp::Width(parse_length(value)?),
+        "height" => StyleProp::Height(parse_length(value)?),
+        "min-width" => StyleProp::MinWidth(parse_length(value)?),
+        "min-height" => StyleProp::MinHeight(parse_length(value)?),
+        "max-width" => StyleProp::MaxWidth(parse_length(value)?),
+        "max-height" => StyleProp::MaxHeight(parse_length(value)?),
+
+        "margin" => StyleProp::Margin(parse_rect(value)?),
+        "margin-left" => StyleProp::MarginLeft(parse_length(value)?),
+        "margin-right" => StyleProp::MarginRight(parse_length(value)?),
+        "margin-top" => StyleProp::MarginTop(parse_length(value)?),
+        "margin-bottom" => StyleProp::MarginBottom(parse_length(value)?),
+
+        "padding" => StyleProp::Padding(parse_rect(value)?),
+        "padding-left" => StyleProp::PaddingLeft(parse_length(value)?),
+        "padding-right" => StyleProp::PaddingRight(parse_length(value)?),
+        "padding-top" => StyleProp::PaddingTop(parse_length(value)?),
+        "padding-bottom" => StyleProp::PaddingBottom(parse_length(value)?),
+
+        "border" => StyleProp::Border(parse_rect(value)?),
+        "border-left" => StyleProp::BorderLeft(parse_length(value)?),
+        "border-right" => StyleProp::BorderRight(parse_length(value)?),
+        "border-top" => StyleProp::BorderTop(parse_length(value)?),
+        "border-bottom" => StyleProp::BorderBottom(parse_length(value)?),
+
+        "flex-direction" => StyleProp::FlexDirection(keyword(
+            value,
+            &[
+                ("row", ui::FlexDirection::Row),
+                ("column", ui::FlexDirection::Column),
+                ("row-reverse", ui::FlexDirection::RowReverse),
+                ("column-reverse", ui::FlexDirection::ColumnReverse),
+            ],
+        )?),
+        "flex-wrap" => StyleProp::FlexWrap(keyword(
+            value,
+            &[
+                ("nowrap", ui::FlexWrap::NoWrap),
+                ("wrap", ui::FlexWrap::Wrap),
+                ("wrap-reverse", ui::FlexWrap::WrapReverse),
+            ],
+        )?),
+        "flex-grow" => StyleProp::FlexGrow(parse_number(value)?),
+        "flex-shrink" => StyleProp::FlexShrink(parse_number(value)?),
+        "flex-basis" => StyleProp::FlexBasis(parse_length(value)?),
+        "row-gap" => StyleProp::RowGap(parse_length(value)?),
+        "column-gap" => StyleProp::ColumnGap(parse_length(value)?),
+        "gap" => StyleProp::Gap(parse_length(value)?),
+
+        "align-items" => StyleProp::AlignItems(keyword(
+            value,
+            &[
+                ("default", ui::AlignItems::Default),
+                ("start", ui::AlignItems::Start),
+                ("end", ui::AlignItems::End),
+                ("flex-start", ui::AlignItems::FlexStart),
+                ("flex-end", ui::AlignItems::FlexEnd),
+                ("center", ui::AlignItems::Center),
+                ("baseline", ui::AlignItems::Baseline),
+                ("stretch", ui::AlignItems::Stretch),
+            ],
+        )?),
+        "align-self" => StyleProp::AlignSelf(keyword(
+            value,
+            &[
+                ("auto", ui::AlignSelf::Auto),
+                ("start", ui::AlignSelf::Start),
+                ("end", ui::AlignSelf::End),
+                ("flex-start", ui::AlignSelf::FlexStart),
+                ("flex-end", ui::AlignSelf::FlexEnd),
+                ("center", ui::AlignSelf::Center),
+                ("baseline", ui::AlignSelf::Baseline),
+                ("stretch", ui::AlignSelf::Stretch),
+            ],
+        )?),
+        "align-content" => StyleProp::AlignContent(keyword(
+            value,
+            &[
+                ("default", ui::AlignContent::Default),
+                ("start", ui::AlignContent::Start),
+                ("end", ui::AlignContent::End),
+                ("flex-start", ui::AlignContent::FlexStart),
+                ("flex-end", ui::AlignContent::FlexEnd),
+                ("center", ui::AlignContent::Center),
+                ("stretch", ui::AlignContent::Stretch),
+                ("space-between", ui::AlignContent::SpaceBetween),
+                ("space-evenly", ui::AlignContent::SpaceEvenly),
+                ("space-around", ui::AlignContent::SpaceAround),
+            ],
+        )?),
+        "justify-items" => StyleProp::JustifyItems(keyword(
+            value,
+            &[
+                ("default", ui::JustifyItems::Default),
+                ("start", ui::JustifyItems::Start),
+                ("end", ui::JustifyItems::End),
+                ("center", ui::JustifyItems::Center),
+                ("baseline", ui::JustifyItems::Baseline),
+                ("stretch", ui::JustifyItems::Stretch),
+            ],
+        )?),
+        "justify-self" => StyleProp::JustifySelf(keyword(
+            value,
+            &[
+                ("auto", ui::JustifySelf::Auto),
+                ("start", ui::JustifySelf::Start),
+                ("end", ui::JustifySelf::End),
+                ("center", ui::JustifySelf::Center),
+                ("baseline", ui::JustifySelf::Baseline),
+                ("stretch", ui::JustifySelf::Stretch),
+            ],
+        )?),
+        "justify-content" => StyleProp::JustifyContent(keyword(
+            value,
+            &[
+                ("default", ui::JustifyContent::Default),
+                ("start", ui::JustifyContent::Start),
+                ("end", ui::JustifyContent::End),
+                ("flex-start", ui::JustifyContent::FlexStart),
+                ("flex-end", ui::JustifyContent::FlexEnd),
+                ("center", ui::JustifyContent::Center),
+                ("stretch", ui::JustifyContent::Stretch),
+                ("space-between", ui::JustifyContent::SpaceBetween),
+                ("space-evenly", ui::JustifyContent::SpaceEvenly),
+                ("space-around", ui::JustifyContent::SpaceAround),
+            ],
+        )?),
+
+        "grid-auto-flow" => StyleProp::GridAutoFlow(keyword(
+            value,
+            &[
+                ("row", ui::GridAutoFlow::Row),
+                ("column", ui::GridAutoFlow::Column),
+                ("row-dense", ui::GridAutoFlow::RowDense),
+                ("column-dense", ui::GridAutoFlow::ColumnDense),
+            ],
+        )?),
+        "grid-row" => StyleProp::GridRow(parse_grid_placement(value)?),
+        "grid-row-start" => StyleProp::GridRowStart(parse_number(value)?),
+        "grid-row-span" => StyleProp::GridRowSpan(parse_number(value)?),
+        "grid-row-end" => StyleProp::GridRowEnd(parse_number(value)?),
+        "grid-column" => StyleProp::GridColumn(parse_grid_placement(value)?),
+        "grid-column-start" => StyleProp::GridColumnStart(parse_number(value)?),
+        "grid-column-span" => StyleProp::GridColumnSpan(parse_number(value)?),
+        "grid-column-end" => StyleProp::GridColumnEnd(parse_number(value)?),
+
+        "pointer-events" => StyleProp::PointerEvents(keyword(
+            value,
+            &[("none", PointerEvents::None), ("all", PointerEvents::All)],
+        )?),
+        "cursor" => StyleProp::Cursor(keyword(
+            value,
+            &[
+                ("none", Cursor::None),
+                ("custom", Cursor::CustomImage),
+                ("default", Cursor::Default),
+                ("pointer", Cursor::Pointer),
+                ("wait", Cursor::Wait),
+                ("crosshair", Cursor::Crosshair),
+                ("text", Cursor::Text),
+                ("vertical-text", Cursor::VerticalText),
+                ("move", Cursor::Move),
+                ("not-allowed", Cursor::NotAllowed),
+                ("grab", Cursor::Grab),
+                ("col-resize", Cursor::ColResize),
+                ("row-resize", Cursor::RowResize),
+                ("zoom-in", Cursor::ZoomIn),
+                ("zoom-out", Cursor::ZoomOut),
+            ],
+        )?),
+        "cursor-image" => StyleProp::CursorImage(
+            parse_path(value)?.ok_or_else(|| "expected quoted path".to_string())?,
+        ),
+        "cursor-offset" => StyleProp::CursorOffset(match value.split_once(' ') {
+            Some((x, y)) => IVec2::new(
+                parse_number(x.trim_end_matches("px"))?,
+                parse_number(y.trim().trim_end_matches("px"))?,
+            ),
+            None => return Err(format!("expected x and y offsets, found '{}'", value)),
+        }),
+
+        "font" => StyleProp::Font(parse_path(value)?),
+        "font-size" => StyleProp::FontSize(parse_number(value.trim_end_matches("px"))?),
+        "opacity" => StyleProp::Opacity(match value.strip_suffix('%') {
+            Some(percent) => parse_number::<f32>(percent)? / 100.,
+            None => parse_number(value)?,
+        }),
+
+        "outline-color" => StyleProp::OutlineColor(parse_color(value)?),
+        "outline-width" => StyleProp::OutlineWidth(parse_length(value)?),
+        "outline-offset" => StyleProp::OutlineOffset(parse_length(value)?),
+
+        "scale" => StyleProp::Scale(parse_number(value)?),
+        "scale-x" => StyleProp::ScaleX(parse_number(value)?),
+        "scale-y" => StyleProp::ScaleY(parse_number(value)?),
+        "rotation" => StyleProp::Rotation(parse_angle(value)?),
+
+        _ => return Err(format!("unknown property '{}'", name)),
+    })
+}
+
+fn keyword<T: Copy>(value: &str, options: &[(&str, T)]) -> Result<T, String> {
+    options
+        .iter()
+        .find(|(name, _)| *name == value)
+        .map(|(_, v)| *v)
+        .ok_or_else(|| {
+            let names: Vec<&str> = options.iter().map(|(name, _)| *name).collect();
+            format!("expected one of {}, found '{}'", names.join(", "), value)
+        })
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid number '{}'", value))
+}
+
+fn parse_overflow(value: &str) -> Result<ui::OverflowAxis, String> {
+    keyword(
+        value,
+        &[
+            ("visible", ui::OverflowAxis::Visible),
+            ("clip", ui::OverflowAxis::Clip),
+        ],
+    )
+}
+
+/// Parse an angle, in radians by default.
+fn parse_angle(value: &str) -> Result<f32, String> {
+    if let Some(deg) = value.strip_suffix("deg") {
+        Ok(parse_number::<f32>(deg)?.to_radians())
+    } else {
+        parse_number(value.trim_end_matches("rad"))
+    }
+}
+
+/// Parse a length such as `10px`, `50%` or `auto`. Unitless numbers are treated as pixels.
+fn parse_length(value: &str) -> Result<ui::Val, String> {
+    if value == "auto" {
+        return Ok(ui::Val::Auto);
+    }
+    let units = [
+        ("px", ui::Val::Px as fn(f32) -> ui::Val),
+        ("%", ui::Val::Percent),
+        ("vw", ui::Val::Vw),
+        ("vh", ui::Val::Vh),
+        ("vmin", ui::Val::VMin),
+        ("vmax", ui::Val::VMax),
+    ];
+    for (suffix, unit) in units {
+        if let Some(n) = value.strip_suffix(suffix) {
+            return n
+                .parse()
+                .map(unit)
+                .map_err(|_| format!("invalid length '{}'", value));
+        }
+    }
+    value
+        .parse()
+        .map(ui::Val::Px)
+        .map_err(|_| format!("invalid length '{}'", value))
+}
+
+/// Parse one to four lengths, in the same order as CSS: top, right, bottom, left.
+fn parse_rect(value: &str) -> Result<ui::UiRect, String> {
+    let lengths = value
+        .split_whitespace()
+        .map(parse_length)
+        .collect::<Result<Vec<_>, _>>()?;
+    match lengths[..] {
+        [all] => Ok(ui::UiRect::all(all)),
+        [v, h] => Ok(ui::UiRect::axes(h, v)),
+        [top, h, bottom] => Ok(ui::UiRect::new(h, h, top, bottom)),
+        [top, right, bottom, left] => Ok(ui::UiRect::new(left, right, top, bottom)),
+        _ => Err(format!("expected 1 to 4 lengths, found '{}'", value)),
+    }
+}
+
+/// Parse a pixel distance, with or without a `px` suffix.
+fn parse_px(value: &str) -> Result<f32, String> {
+    value
+        .trim_end_matches("px")
+        .parse()
+        .map_err(|_| format!("invalid length '{}'", value))
+}
+
+/// Parse one to four corner radii, in the same order as CSS: top left, top right, bottom
+/// right, bottom left.
+fn parse_border_radius(value: &str) -> Result<Option<BorderRadius>, String> {
+    if value == "none" {
+        return Ok(None);
+    }
+    let radii = value
+        .split_whitespace()
+        .map(parse_px)
+        .collect::<Result<Vec<_>, _>>()?;
+    match radii[..] {
+        [all] => Ok(Some(BorderRadius::all(all))),
+        [tl_br, tr_bl] => Ok(Some(BorderRadius::new(tl_br, tr_bl, tl_br, tr_bl))),
+        [tl, tr_bl, br] => Ok(Some(BorderRadius::new(tl, tr_bl, br, tr_bl))),
+        [tl, tr, br, bl] => Ok(Some(BorderRadius::new(tl, tr, br, bl))),
+        _ => Err(format!("expected 1 to 4 radii, found '{}'", value)),
+    }
+}
+
+/// Parse a box shadow: `none`, or an x and y offset, an optional blur and spread, and a color.
+fn parse_box_shadow(value: &str) -> Result<Option<BoxShadow>, String> {
+    if value == "none" {
+        return Ok(None);
+    }
+    let mut rest = value;
+    let mut lengths = Vec::new();
+    while lengths.len() < 4 {
+        let (word, tail) = rest.split_once(' ').unwrap_or((rest, ""));
+        let Ok(length) = parse_px(word) else {
+            break;
+        };
+        lengths.push(length);
+        rest = tail.trim_start();
+    }
+    let color = parse_color(rest)?.unwrap_or(Color::BLACK);
+    match lengths[..] {
+        [x, y, ref extra @ ..] => Ok(Some(BoxShadow {
+            offset: Vec2::new(x, y),
+            blur: extra.first().copied().unwrap_or(0.),
+            spread: extra.get(1).copied().unwrap_or(0.),
+            color,
+        })),
+        _ => Err(format!("expected shadow offsets, found '{}'", value)),
+    }
+}
+
+/// Parse a grid placement: `auto`, `span 2`, `3`, `3 / 5` or `3 / span 2`.
+fn parse_grid_placement(value: &str) -> Result<ui::GridPlacement, String> {
+    if value == "auto" {
+        return Ok(ui::GridPlacement::auto());
+    }
+    if let Some(span) = value.strip_prefix("span ") {
+        return Ok(ui::GridPlacement::span(parse_number(span)?));
+    }
+    match value.split_once('/') {
+        None => Ok(ui::GridPlacement::start(parse_number(value)?)),
+        Some((start, end)) => {
+            let start = parse_number(start)?;
+            match end.trim().strip_prefix("span ") {
+                Some(span) => Ok(ui::GridPlacement::start_span(start, parse_number(span)?)),
+                None => Ok(ui::GridPlacement::start_end(start, parse_number(end)?)),
+            }
+        }
+    }
+}
+
+/// Parse an optional asset path, which is either a quoted string or `none`.
+fn parse_path(value: &str) -> Result<Option<bevy::asset::AssetPath<'static>>, String> {
+    if value == "none" {
+        return Ok(None);
+    }
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .map(|path| Some(bevy::asset::AssetPath::parse(path).into_owned()))
+        .ok_or_else(|| format!("expected quoted path, found '{}'", value))
+}
+
+/// Parse a color: `#rgb`, `#rrggbb`, `#rrggbbaa`, `rgb()`, `rgba()`, `hsl()`, `hsla()`,
+/// `transparent`, or `none`.
+fn parse_color(value: &str) -> Result<Option<Color>, String> {
+    if value == "none" {
+        return Ok(None);
+    }
+    if value == "transparent" {
+        return Ok(Some(Color::NONE));
+    }
+    if let Some(hex) = value.strip_prefix('#') {
+        return Color::hex(hex)
+            .map(Some)
+            .map_err(|_| format!("invalid color '{}'", value));
+    }
+    let (func, args) = value
+        .strip_suffix(')')
+        .and_then(|v| v.split_once('('))
+        .ok_or_else(|| format!("invalid color '{}'", value))?;
+    let args = args
+        .split(',')
+        .map(|arg| match arg.trim().strip_suffix('%') {
+            Some(pct) => parse_number::<f32>(pct).map(|n| n / 100.),
+            None => parse_number::<f32>(arg),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match (func.trim(), &args[..]) {
+        ("rgb", &[r, g, b]) => Ok(Some(Color::rgb(r / 255., g / 255., b / 255.))),
+        ("rgba", &[r, g, b, a]) => Ok(Some(Color::rgba(r / 255., g / 255., b / 255., a))),
+        ("hsl", &[h, s, l]) => Ok(Some(Color::hsl(h, s, l))),
+        ("hsla", &[h, s, l, a]) => Ok(Some(Color::hsla(h, s, l, a))),
+        _ => Err(format!("invalid color '{}'", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sheet() {
+        let sheet = StyleSheet::parse(
+            r#"
+            /* A button. */
+            button {
+                display: flex;
+                padding: 0 12px;
+                min-height: 50%;
+                background-color: #ff0000;
+                font: "fonts/Ubuntu.ttf";
+
+                &:hover {
+                    background-color: rgba(0, 0, 255, 0.5)
+                }
+
+                .disabled > & {
+                    pointer-events: none !important;
+                }
+            }
+
+            label {}
+            "#,
+        )
+        .unwrap();
+
+        let mut names: Vec<&str> = sheet.names().collect();
+        names.sort();
+        assert_eq!(names, ["button", "label"]);
+
+        let button = sheet.get("button").unwrap();
+        assert_eq!(button.0.props.len(), 5);
+        assert!(matches!(
+            button.0.props[0],
+            StyleProp::Display(ui::Display::Flex)
+        ));
+        assert!(matches!(
+            button.0.props[1],
+            StyleProp::Padding(rect) if rect == ui::UiRect::axes(ui::Val::Px(12.), ui::Val::Px(0.))
+        ));
+        assert!(matches!(
+            button.0.props[2],
+            StyleProp::MinHeight(ui::Val::Percent(p)) if p == 50.
+        ));
+        assert!(matches!(
+            button.0.props[3],
+            StyleProp::BackgroundColor(Some(c)) if c == Color::rgb(1., 0., 0.)
+        ));
+
+        assert_eq!(button.0.selectors.len(), 2);
+        assert_eq!(
+            button.0.selectors[0].0,
+            Selector::parser.parse("&:hover").unwrap()
+        );
+        assert_eq!(
+            button.0.selectors[1].0,
+            Selector::parser.parse(".disabled > &").unwrap()
+        );
+        assert!(matches!(
+            &button.0.selectors[1].1[..],
+            [StyleProp::Important(props)]
+                if matches!(props[..], [StyleProp::PointerEvents(PointerEvents::None)])
+        ));
+        assert_eq!(button.depth(), 2);
+    }
+
+    #[test]
+    fn test_parse_cursor() {
+        assert!(matches!(
+            parse_property("cursor", "col-resize"),
+            Ok(StyleProp::Cursor(Cursor::ColResize))
+        ));
+        assert!(matches!(
+            parse_property("cursor-offset", "4px 8px"),
+            Ok(StyleProp::CursorOffset(offset)) if offset == IVec2::new(4, 8)
+        ));
+        assert!(parse_property("cursor", "hand").is_err());
+        assert!(matches!(
+            parse_property("opacity", "40%"),
+            Ok(StyleProp::Opacity(o)) if o == 0.4
+        ));
+        assert!(matches!(
+            parse_property("border-radius", "4px 8px"),
+            Ok(StyleProp::BorderRadius(Some(r))) if r == BorderRadius::new(4., 8., 4., 8.)
+        ));
+        assert!(matches!(
+            parse_property("box-shadow", "0 2px 6px rgba(0, 0, 0, 0.5)"),
+            Ok(StyleProp::BoxShadow(Some(shadow)))
+                if shadow.offset == Vec2::new(0., 2.)
+                    && shadow.blur == 6.
+                    && shadow.spread == 0.
+                    && shadow.color == Color::rgba(0., 0., 0., 0.5)
+        ));
+        assert!(parse_property("box-shadow", "#000").is_err());
+        assert!(parse_property("cursor-image", "none").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |text: &str| match StyleSheet::parse(text) {
+            Err(StyleSheetError::Syntax { line, message }) => (line, message),
+            _ => panic!("expected a syntax error"),
+        };
+        assert_eq!(
+            err("a {\n  widht: 10px;\n}"),
+            (2, "unknown property 'widht'".to_string())
+        );
+        assert_eq!(
+            err("a {\n\n  width: 10pt;\n}"),
+            (3, "invalid length '10pt'".to_string())
+        );
+        assert_eq!(err("a { width: 10px;"), (1, "expected '}'".into()));
+        assert_eq!(err("a {}\na {}"), (2, "duplicate style 'a'".into()));
+    }
+}
@@ -0,0 +1,531 @@
+//! Timing functions, which control the rate of change of transitions and animations.
+
+use std::{fmt, sync::Arc};
+
+/// Represents an animation timing function such as 'ease-in'.
+pub trait TimingFunction
+where
+    Self: Send + Sync + fmt::Debug,
+{
+    /// Map the elapsed fraction of the animation, from 0 to 1, to the fraction of the change
+    /// in value.
+    fn eval(&self, t: f32) -> f32;
+}
+
+/// A timing curve defined by a cubic Bézier curve from (0, 0) to (1, 1), with the control
+/// points (x1, y1) and (x2, y2), like the CSS `cubic-bezier()` function.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubicBezier {
+    /// X coordinate of the first control point, between 0 and 1.
+    pub x1: f32,
+    /// Y coordinate of the first control point.
+    pub y1: f32,
+    /// X coordinate of the second control point, between 0 and 1.
+    pub x2: f32,
+    /// Y coordinate of the second control point.
+    pub y2: f32,
+}
+
+impl CubicBezier {
+    /// Construct a new cubic Bézier curve from its control points.
+    pub const fn new(x1: f32, y1: f32, x2: f32, y2: f32) -> Self {
+        Self { x1, y1, x2, y2 }
+    }
+
+    /// Evaluate one coordinate of the curve at parameter `s`, given the coordinates of the
+    /// two control points.
+    fn sample(p1: f32, p2: f32, s: f32) -> f32 {
+        // Polynomial coefficients, with the end points fixed at 0 and 1.
+        let c = 3. * p1;
+        let b = 3. * (p2 - p1) - c;
+        let a = 1. - c - b;
+        ((a * s + b) * s + c) * s
+    }
+
+    /// Derivative of `sample` with respect to `s`.
+    fn sample_derivative(p1: f32, p2: f32, s: f32) -> f32 {
+        let c = 3. * p1;
+        let b = 3. * (p2 - p1) - c;
+        let a = 1. - c - b;
+        (3. * a * s + 2. * b) * s + c
+    }
+
+    /// Find the curve parameter for a given x coordinate.
+    fn solve_x(&self, x: f32) -> f32 {
+        const EPSILON: f32 = 1e-6;
+
+        // Newton's method converges quickly for most curves.
+        let mut s = x;
+        for _ in 0..8 {
+            let error = Self::sample(self.x1, self.x2, s) - x;
+            if error.abs() < EPSILON {
+                return s;
+            }
+            let slope = Self::sample_derivative(self.x1, self.x2, s);
+            if slope.abs() < EPSILON {
+                break;
+            }
+            s -= error / slope;
+        }
+
+        // Fall back to bisection, which is slower but always converges since x is monotonic
+        // in s when both control points are within [0, 1].
+        let (mut lo, mut hi) = (0., 1.);
+        s = x;
+        while hi - lo > EPSILON {
+            let value = Self::sample(self.x1, self.x2, s);
+            if (value - x).abs() < EPSILON {
+                break;
+            }
+            if value < x {
+                lo = s;
+            } else {
+                hi = s;
+            }
+            s = (lo + hi) / 2.;
+        }
+        s
+    }
+}
+
+impl TimingFunction for CubicBezier {
+    fn eval(&self, t: f32) -> f32 {
+        if t <= 0. {
+            return 0.;
+        }
+        if t >= 1. {
+            return 1.;
+        }
+        Self::sample(self.y1, self.y2, self.solve_x(t))
+    }
+}
+
+impl fmt::Display for CubicBezier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cubic-bezier({}, {}, {}, {})",
+            self.x1, self.y1, self.x2, self.y2
+        )
+    }
+}
+
+/// Where the jumps of a [`Steps`] timing function occur, like the second argument of the CSS
+/// `steps()` function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum StepPosition {
+    /// The first jump happens when the animation begins.
+    JumpStart,
+    /// The last jump happens when the animation ends.
+    #[default]
+    JumpEnd,
+    /// There are no jumps at either end, the value is held at 0 and 1 for one step each.
+    JumpNone,
+    /// There are jumps both when the animation begins and when it ends.
+    JumpBoth,
+}
+
+/// A timing function which divides the animation into equal intervals, holding the value
+/// constant within each interval, like the CSS `steps()` function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Steps {
+    /// Number of intervals.
+    pub count: u32,
+    /// Where the jumps between intervals occur.
+    pub position: StepPosition,
+}
+
+impl Steps {
+    /// Construct a new step function.
+    pub const fn new(count: u32, position: StepPosition) -> Self {
+        Self { count, position }
+    }
+}
+
+impl TimingFunction for Steps {
+    fn eval(&self, t: f32) -> f32 {
+        let count = self.count.max(1) as f32;
+        let mut step = (t.clamp(0., 1.) * count).floor();
+        if matches!(
+            self.position,
+            StepPosition::JumpStart | StepPosition::JumpBoth
+        ) {
+            step += 1.;
+        }
+        let jumps = match self.position {
+            StepPosition::JumpStart | StepPosition::JumpEnd => count,
+            StepPosition::JumpNone => (count - 1.).max(1.),
+            StepPosition::JumpBoth => count + 1.,
+        };
+        step.min(jumps) / jumps
+    }
+}
+
+impl fmt::Display for Steps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let position = match self.position {
+            StepPosition::JumpStart => "jump-start",
+            StepPosition::JumpEnd => "jump-end",
+            StepPosition::JumpNone => "jump-none",
+            StepPosition::JumpBoth => "jump-both",
+        };
+        write!(f, "steps({}, {})", self.count, position)
+    }
+}
+
+/// A timing function which follows the motion of a damped spring released from 0 towards 1.
+/// The curve is scaled so that the spring has settled by the end of the animation. Springs
+/// with little damping overshoot, producing values greater than 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    /// Stiffness of the spring; stiffer springs oscillate faster.
+    pub stiffness: f32,
+    /// Damping coefficient; higher values reduce the amount of overshoot.
+    pub damping: f32,
+    /// Mass attached to the spring.
+    pub mass: f32,
+}
+
+impl Spring {
+    /// Construct a new spring with a mass of 1.
+    pub const fn new(stiffness: f32, damping: f32) -> Self {
+        Self {
+            stiffness,
+            damping,
+            mass: 1.,
+        }
+    }
+
+    /// Returns the undamped angular frequency and the damping ratio.
+    fn params(&self) -> (f32, f32) {
+        let omega = (self.stiffness / self.mass).sqrt();
+        let zeta = self.damping / (2. * (self.stiffness * self.mass).sqrt());
+        (omega, zeta)
+    }
+
+    /// Time taken, in the spring's own units, for the spring to come within 0.1% of its rest
+    /// position.
+    fn settling_time(&self) -> f32 {
+        let (omega, zeta) = self.params();
+        let decay = if zeta < 1. {
+            zeta * omega
+        } else {
+            // The slower of the two exponential modes dominates.
+            omega * (zeta - (zeta * zeta - 1.).sqrt())
+        };
+        let mut time = 1000f32.ln() / decay;
+
+        // The estimate ignores the polynomial term of a critically damped spring, and the
+        // constant factors of the other cases, so extend it until the spring has settled.
+        for _ in 0..50 {
+            if (1. - self.position(time)).abs() < 0.001 {
+                break;
+            }
+            time *= 1.1;
+        }
+        time
+    }
+
+    /// Position of the spring at a given time.
+    fn position(&self, time: f32) -> f32 {
+        let (omega, zeta) = self.params();
+        if zeta < 1. {
+            let omega_d = omega * (1. - zeta * zeta).sqrt();
+            let envelope = (-zeta * omega * time).exp();
+            1. - envelope
+                * ((omega_d * time).cos() + (zeta * omega / omega_d) * (omega_d * time).sin())
+        } else if zeta == 1. {
+            1. - (-omega * time).exp() * (1. + omega * time)
+        } else {
+            let root = (zeta * zeta - 1.).sqrt();
+            let r1 = -omega * (zeta - root);
+            let r2 = -omega * (zeta + root);
+            1. - (r2 * (r1 * time).exp() - r1 * (r2 * time).exp()) / (r2 - r1)
+        }
+    }
+}
+
+impl TimingFunction for Spring {
+    fn eval(&self, t: f32) -> f32 {
+        if t <= 0. {
+            0.
+        } else if t >= 1. {
+            1.
+        } else {
+            self.position(t * self.settling_time())
+        }
+    }
+}
+
+impl fmt::Display for Spring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "spring({}, {}, {})",
+            self.stiffness, self.damping, self.mass
+        )
+    }
+}
+
+/// A timing function used by a transition or animation. The built-in curves can be written
+/// as constants, such as [`EASE_IN_OUT`], constructed at run time, or parsed from CSS syntax
+/// with [`Timing::parse`]. Any other [`TimingFunction`] can be used with [`Timing::custom`].
+#[derive(Clone, Debug, Default)]
+pub enum Timing {
+    /// The value changes at a constant rate.
+    #[default]
+    Linear,
+    /// A cubic Bézier curve.
+    CubicBezier(CubicBezier),
+    /// A step function.
+    Steps(Steps),
+    /// A damped spring.
+    Spring(Spring),
+    /// A user-defined timing function.
+    Custom(Arc<dyn TimingFunction>),
+}
+
+impl Timing {
+    /// Construct a timing from a user-defined timing function.
+    pub fn custom(function: impl TimingFunction + 'static) -> Self {
+        Self::Custom(Arc::new(function))
+    }
+
+    /// Parse a timing function written in CSS syntax: one of the keywords `linear`, `ease`,
+    /// `ease-in`, `ease-out`, `ease-in-out`, `step-start` or `step-end`, or one of the
+    /// functions `cubic-bezier(x1, y1, x2, y2)`, `steps(count, position)` or
+    /// `spring(stiffness, damping, mass)`, where the step position and the mass are optional.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        match text {
+            "linear" => return Some(LINEAR),
+            "ease" => return Some(EASE),
+            "ease-in" => return Some(EASE_IN),
+            "ease-out" => return Some(EASE_OUT),
+            "ease-in-out" => return Some(EASE_IN_OUT),
+            "step-start" => return Some(STEP_START),
+            "step-end" => return Some(STEP_END),
+            _ => {}
+        }
+
+        let (name, args) = text.strip_suffix(')')?.split_once('(')?;
+        let args: Vec<&str> = args.split(',').map(str::trim).collect();
+        let number = |index: usize| args.get(index)?.parse::<f32>().ok();
+        match (name.trim(), args.len()) {
+            ("cubic-bezier", 4) => {
+                let bezier = CubicBezier::new(number(0)?, number(1)?, number(2)?, number(3)?);
+                // The curve must be a function of x.
+                if !(0. ..=1.).contains(&bezier.x1) || !(0. ..=1.).contains(&bezier.x2) {
+                    return None;
+                }
+                Some(Timing::CubicBezier(bezier))
+            }
+            ("steps", 1 | 2) => {
+                let count = args[0].parse::<u32>().ok()?;
+                let position = match args.get(1).copied() {
+                    None | Some("jump-end") | Some("end") => StepPosition::JumpEnd,
+                    Some("jump-start") | Some("start") => StepPosition::JumpStart,
+                    Some("jump-none") => StepPosition::JumpNone,
+                    Some("jump-both") => StepPosition::JumpBoth,
+                    Some(_) => return None,
+                };
+                let min_count = if position == StepPosition::JumpNone {
+                    2
+                } else {
+                    1
+                };
+                (count >= min_count).then_some(Timing::Steps(Steps::new(count, position)))
+            }
+            ("spring", 2 | 3) => {
+                let spring = Spring {
+                    stiffness: number(0)?,
+                    damping: number(1)?,
+                    mass: if args.len() == 3 { number(2)? } else { 1. },
+                };
+                (spring.stiffness > 0. && spring.damping > 0. && spring.mass > 0.)
+                    .then_some(Timing::Spring(spring))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl TimingFunction for Timing {
+    fn eval(&self, t: f32) -> f32 {
+        match self {
+            Timing::Linear => t,
+            Timing::CubicBezier(bezier) => bezier.eval(t),
+            Timing::Steps(steps) => steps.eval(t),
+            Timing::Spring(spring) => spring.eval(t),
+            Timing::Custom(function) => function.eval(t),
+        }
+    }
+}
+
+impl PartialEq for Timing {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Timing::Linear, Timing::Linear) => true,
+            (Timing::CubicBezier(a), Timing::CubicBezier(b)) => a == b,
+            (Timing::Steps(a), Timing::Steps(b)) => a == b,
+            (Timing::Spring(a), Timing::Spring(b)) => a == b,
+            (Timing::Custom(a), Timing::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Timing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Timing::Linear => f.write_str("linear"),
+            Timing::CubicBezier(bezier) => bezier.fmt(f),
+            Timing::Steps(steps) => steps.fmt(f),
+            Timing::Spring(spring) => spring.fmt(f),
+            Timing::Custom(function) => write!(f, "{:?}", function),
+        }
+    }
+}
+
+impl From<CubicBezier> for Timing {
+    fn from(value: CubicBezier) -> Self {
+        Timing::CubicBezier(value)
+    }
+}
+
+impl From<Steps> for Timing {
+    fn from(value: Steps) -> Self {
+        Timing::Steps(value)
+    }
+}
+
+impl From<Spring> for Timing {
+    fn from(value: Spring) -> Self {
+        Timing::Spring(value)
+    }
+}
+
+/// Linear easing function
+pub const LINEAR: Timing = Timing::Linear;
+
+/// "ease" animation function, which starts quickly and slows down gradually
+pub const EASE: Timing = Timing::CubicBezier(CubicBezier::new(0.25, 0.1, 0.25, 1.));
+
+/// "ease-in" animation function
+pub const EASE_IN: Timing = Timing::CubicBezier(CubicBezier::new(0.42, 0., 1., 1.));
+
+/// "ease-out" animation function
+pub const EASE_OUT: Timing = Timing::CubicBezier(CubicBezier::new(0., 0., 0.58, 1.));
+
+/// "ease-in-out" animation function
+pub const EASE_IN_OUT: Timing = Timing::CubicBezier(CubicBezier::new(0.42, 0., 0.58, 1.));
+
+/// "step-start" animation function, which jumps to the end value immediately
+pub const STEP_START: Timing = Timing::Steps(Steps::new(1, StepPosition::JumpStart));
+
+/// "step-end" animation function, which holds the start value until the end
+pub const STEP_END: Timing = Timing::Steps(Steps::new(1, StepPosition::JumpEnd));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: f32, b: f32) {
+        assert!((a - b).abs() < 0.001, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_cubic_bezier() {
+        // A curve with control points on the diagonal is linear.
+        let linear = CubicBezier::new(0.25, 0.25, 0.75, 0.75);
+        for t in [0.1, 0.3, 0.5, 0.9] {
+            assert_near(linear.eval(t), t);
+        }
+
+        // Reference values for "ease", as computed by browsers.
+        assert_near(EASE.eval(0.25), 0.4094);
+        assert_near(EASE.eval(0.5), 0.8024);
+        assert_near(EASE_IN_OUT.eval(0.5), 0.5);
+        assert_eq!(EASE_IN.eval(0.), 0.);
+        assert_eq!(EASE_IN.eval(1.), 1.);
+
+        // Curves with a steep x at the ends need the bisection fallback.
+        let steep = CubicBezier::new(1., 0., 0., 1.);
+        assert_near(steep.eval(0.5), 0.5);
+        assert!(steep.eval(0.25) < 0.25);
+    }
+
+    #[test]
+    fn test_steps() {
+        let end = Steps::new(4, StepPosition::JumpEnd);
+        assert_eq!(end.eval(0.), 0.);
+        assert_eq!(end.eval(0.3), 0.25);
+        assert_eq!(end.eval(0.99), 0.75);
+        assert_eq!(end.eval(1.), 1.);
+
+        let start = Steps::new(4, StepPosition::JumpStart);
+        assert_eq!(start.eval(0.), 0.25);
+        assert_eq!(start.eval(0.99), 1.);
+
+        let none = Steps::new(3, StepPosition::JumpNone);
+        assert_eq!(none.eval(0.), 0.);
+        assert_eq!(none.eval(0.5), 0.5);
+        assert_eq!(none.eval(0.99), 1.);
+
+        let both = Steps::new(3, StepPosition::JumpBoth);
+        assert_eq!(both.eval(0.), 0.25);
+        assert_eq!(both.eval(1.), 1.);
+    }
+
+    #[test]
+    fn test_spring() {
+        let bouncy = Spring::new(100., 5.);
+        assert_eq!(bouncy.eval(0.), 0.);
+        assert_eq!(bouncy.eval(1.), 1.);
+        let max = (1..100)
+            .map(|i| bouncy.eval(i as f32 / 100.))
+            .fold(0., f32::max);
+        assert!(max > 1.1, "underdamped spring should overshoot");
+        assert_near(bouncy.eval(0.99), 1.);
+
+        // Critically damped and overdamped springs approach 1 without overshooting.
+        for spring in [Spring::new(100., 20.), Spring::new(100., 40.)] {
+            let mut prev = 0.;
+            for i in 1..100 {
+                let value = spring.eval(i as f32 / 100.);
+                assert!(value >= prev && value <= 1.);
+                prev = value;
+            }
+            assert_near(prev, 1.);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Timing::parse("ease-in-out"), Some(EASE_IN_OUT));
+        assert_eq!(
+            Timing::parse(" cubic-bezier(0.1, 0.7, 1.0, 0.1) "),
+            Some(Timing::CubicBezier(CubicBezier::new(0.1, 0.7, 1., 0.1)))
+        );
+        assert_eq!(
+            Timing::parse("steps(4)"),
+            Some(Timing::Steps(Steps::new(4, StepPosition::JumpEnd)))
+        );
+        assert_eq!(
+            Timing::parse("steps(2, jump-none)"),
+            Some(Timing::Steps(Steps::new(2, StepPosition::JumpNone)))
+        );
+        assert_eq!(
+            Timing::parse("spring(170, 26)"),
+            Some(Timing::Spring(Spring::new(170., 26.)))
+        );
+        assert_eq!(Timing::parse("cubic-bezier(1.5, 0, 0, 1)"), None);
+        assert_eq!(Timing::parse("steps(1, jump-none)"), None);
+        assert_eq!(Timing::parse("steps(0)"), None);
+        assert_eq!(Timing::parse("bounce"), None);
+
+        for timing in [EASE, STEP_START, Timing::Spring(Spring::new(170., 26.))] {
+            assert_eq!(Timing::parse(&timing.to_string()), Some(timing));
+        }
+    }
+}
@@ -1,92 +1,8 @@
-use bevy::{prelude::*, ui, utils::HashMap};
-use std::fmt::Debug;
-
-/// Represents an animation timing function such as 'ease-in'.
-pub trait TimingFunction
-where
-    Self: Send + Sync + Debug,
-{
-    fn eval(&self, t: f32) -> f32;
-}
-
-/// Module containing various useful timing functions.
-pub mod timing {
-    use std::{f32::consts::PI, fmt::Debug};
-
-    use super::TimingFunction;
-
-    /// Linear easing function
-    pub struct Linear {}
-
-    impl TimingFunction for Linear {
-        fn eval(&self, t: f32) -> f32 {
-            t
-        }
-    }
-
-    impl Debug for Linear {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.write_str("linear")
-        }
-    }
-
-    /// Cubic ease-in function
-    pub struct EaseIn {}
-
-    impl TimingFunction for EaseIn {
-        fn eval(&self, t: f32) -> f32 {
-            t * t * t
-        }
-    }
-
-    impl Debug for EaseIn {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.write_str("ease-in")
-        }
-    }
-
-    /// Cubic ease-out function
-    pub struct EaseOut {}
-
-    impl TimingFunction for EaseOut {
-        fn eval(&self, t: f32) -> f32 {
-            1. - (1. - t).powf(3.)
-        }
-    }
-
-    impl Debug for EaseOut {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.write_str("ease-out")
-        }
-    }
-
-    /// Sinusoidal ease-in-out function
-    pub struct EaseInOut {}
-
-    impl TimingFunction for EaseInOut {
-        fn eval(&self, t: f32) -> f32 {
-            -((PI * t).cos() - 1.) / 2.
-        }
-    }
-
-    impl Debug for EaseInOut {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            f.write_str("ease-in-out")
-        }
-    }
-
-    /// Linear easing function
-    pub const LINEAR: &Linear = &Linear {};
+use bevy::{prelude::*, ui, utils::HashMap, window::PrimaryWindow};
+use bevy_color::{AnyColorRange, ColorRange, Hsla, LinearRgba, Oklaba};
 
-    /// "ease-in" animation function
-    pub const EASE_IN: &EaseIn = &EaseIn {};
-
-    /// "ease-out" animation function
-    pub const EASE_OUT: &EaseOut = &EaseOut {};
-
-    /// "ease-in-out" animation function
-    pub const EASE_IN_OUT: &EaseInOut = &EaseInOut {};
-}
+use super::timing::{self, Timing, TimingFunction};
+use crate::material::{linear_color, StyleMaterial};
 
 /// Specifies which property is being animated.
 #[derive(Clone, Debug, PartialEq, Eq, Copy, Hash)]
@@ -100,6 +16,15 @@ pub enum TransitionProperty {
     /// Animate the element's border color
     BorderColor,
 
+    /// Animate the element's text color
+    Color,
+
+    /// Animate the element's outline color
+    OutlineColor,
+
+    /// Animate the opacity of the element and its descendants
+    Opacity,
+
     /// Animate left
     Left,
 
@@ -129,6 +54,258 @@ pub enum TransitionProperty {
 
     /// Animate border bottom
     BorderBottom,
+
+    /// Animate margin left
+    MarginLeft,
+
+    /// Animate margin top
+    MarginTop,
+
+    /// Animate margin right
+    MarginRight,
+
+    /// Animate margin bottom
+    MarginBottom,
+
+    /// Animate padding left
+    PaddingLeft,
+
+    /// Animate padding top
+    PaddingTop,
+
+    /// Animate padding right
+    PaddingRight,
+
+    /// Animate padding bottom
+    PaddingBottom,
+
+    /// Animate the gap between rows
+    RowGap,
+
+    /// Animate the gap between columns
+    ColumnGap,
+
+    /// Animate flex grow
+    FlexGrow,
+
+    /// Animate flex shrink
+    FlexShrink,
+
+    /// Animate flex basis
+    FlexBasis,
+
+    /// Animate min width
+    MinWidth,
+
+    /// Animate min height
+    MinHeight,
+
+    /// Animate max width
+    MaxWidth,
+
+    /// Animate max height
+    MaxHeight,
+}
+
+impl TransitionProperty {
+    /// Returns whether this property is a color.
+    pub fn is_color(&self) -> bool {
+        matches!(
+            self,
+            TransitionProperty::BackgroundColor
+                | TransitionProperty::BorderColor
+                | TransitionProperty::Color
+                | TransitionProperty::OutlineColor
+        )
+    }
+
+    /// Returns whether this property is a field of the element's [`Style`].
+    pub fn is_layout(&self) -> bool {
+        !self.is_color()
+            && !matches!(
+                self,
+                TransitionProperty::Transform | TransitionProperty::Opacity
+            )
+    }
+
+    /// Read the value of a layout property. Flex factors, which are not lengths, are
+    /// represented as pixels.
+    pub(crate) fn layout_value(&self, style: &Style) -> ui::Val {
+        match self {
+            TransitionProperty::Left => style.left,
+            TransitionProperty::Top => style.top,
+            TransitionProperty::Bottom => style.bottom,
+            TransitionProperty::Right => style.right,
+            TransitionProperty::Height => style.height,
+            TransitionProperty::Width => style.width,
+            TransitionProperty::BorderLeft => style.border.left,
+            TransitionProperty::BorderTop => style.border.top,
+            TransitionProperty::BorderRight => style.border.right,
+            TransitionProperty::BorderBottom => style.border.bottom,
+            TransitionProperty::MarginLeft => style.margin.left,
+            TransitionProperty::MarginTop => style.margin.top,
+            TransitionProperty::MarginRight => style.margin.right,
+            TransitionProperty::MarginBottom => style.margin.bottom,
+            TransitionProperty::PaddingLeft => style.padding.left,
+            TransitionProperty::PaddingTop => style.padding.top,
+            TransitionProperty::PaddingRight => style.padding.right,
+            TransitionProperty::PaddingBottom => style.padding.bottom,
+            TransitionProperty::RowGap => style.row_gap,
+            TransitionProperty::ColumnGap => style.column_gap,
+            TransitionProperty::FlexGrow => ui::Val::Px(style.flex_grow),
+            TransitionProperty::FlexShrink => ui::Val::Px(style.flex_shrink),
+            TransitionProperty::FlexBasis => style.flex_basis,
+            TransitionProperty::MinWidth => style.min_width,
+            TransitionProperty::MinHeight => style.min_height,
+            TransitionProperty::MaxWidth => style.max_width,
+            TransitionProperty::MaxHeight => style.max_height,
+            TransitionProperty::Transform
+            | TransitionProperty::BackgroundColor
+            | TransitionProperty::BorderColor
+            | TransitionProperty::Color
+            | TransitionProperty::OutlineColor
+            | TransitionProperty::Opacity => panic!("Invalid style transition prop"),
+        }
+    }
+
+    /// Write the value of a layout property.
+    fn set_layout_value(&self, style: &mut Style, value: ui::Val) {
+        let factor = || match value {
+            ui::Val::Px(f) => f,
+            _ => 0.,
+        };
+        match self {
+            TransitionProperty::Left => style.left = value,
+            TransitionProperty::Top => style.top = value,
+            TransitionProperty::Bottom => style.bottom = value,
+            TransitionProperty::Right => style.right = value,
+            TransitionProperty::Height => style.height = value,
+            TransitionProperty::Width => style.width = value,
+            TransitionProperty::BorderLeft => style.border.left = value,
+            TransitionProperty::BorderTop => style.border.top = value,
+            TransitionProperty::BorderRight => style.border.right = value,
+            TransitionProperty::BorderBottom => style.border.bottom = value,
+            TransitionProperty::MarginLeft => style.margin.left = value,
+            TransitionProperty::MarginTop => style.margin.top = value,
+            TransitionProperty::MarginRight => style.margin.right = value,
+            TransitionProperty::MarginBottom => style.margin.bottom = value,
+            TransitionProperty::PaddingLeft => style.padding.left = value,
+            TransitionProperty::PaddingTop => style.padding.top = value,
+            TransitionProperty::PaddingRight => style.padding.right = value,
+            TransitionProperty::PaddingBottom => style.padding.bottom = value,
+            TransitionProperty::RowGap => style.row_gap = value,
+            TransitionProperty::ColumnGap => style.column_gap = value,
+            TransitionProperty::FlexGrow => style.flex_grow = factor(),
+            TransitionProperty::FlexShrink => style.flex_shrink = factor(),
+            TransitionProperty::FlexBasis => style.flex_basis = value,
+            TransitionProperty::MinWidth => style.min_width = value,
+            TransitionProperty::MinHeight => style.min_height = value,
+            TransitionProperty::MaxWidth => style.max_width = value,
+            TransitionProperty::MaxHeight => style.max_height = value,
+            TransitionProperty::Transform
+            | TransitionProperty::BackgroundColor
+            | TransitionProperty::BorderColor
+            | TransitionProperty::Color
+            | TransitionProperty::OutlineColor
+            | TransitionProperty::Opacity => panic!("Invalid style transition prop"),
+        }
+    }
+
+    /// Convert the value of a layout property to pixels, or return `None` if it has no fixed
+    /// size. `Val::Auto` widths and heights are resolved to the current size of the element.
+    fn resolve(&self, value: ui::Val, layout: &LayoutContext) -> Option<f32> {
+        let viewport = layout.viewport_size;
+        match value {
+            ui::Val::Px(px) => Some(px),
+            ui::Val::Vw(vw) => Some(viewport.x * vw / 100.),
+            ui::Val::Vh(vh) => Some(viewport.y * vh / 100.),
+            ui::Val::VMin(vmin) => Some(viewport.min_element() * vmin / 100.),
+            ui::Val::VMax(vmax) => Some(viewport.max_element() * vmax / 100.),
+            ui::Val::Percent(percent) => {
+                // Vertical positions and sizes are relative to the height of the parent, all
+                // other percentages are relative to its width.
+                let basis = match self {
+                    TransitionProperty::Top
+                    | TransitionProperty::Bottom
+                    | TransitionProperty::Height
+                    | TransitionProperty::MinHeight
+                    | TransitionProperty::MaxHeight
+                    | TransitionProperty::RowGap => layout.parent_size.y,
+                    _ => layout.parent_size.x,
+                };
+                Some(basis * percent / 100.)
+            }
+            ui::Val::Auto => match self {
+                TransitionProperty::Width => layout.node_size.map(|size| size.x),
+                TransitionProperty::Height => layout.node_size.map(|size| size.y),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Sizes from the most recent layout, used to convert lengths in different units to pixels
+/// so that they can be interpolated.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct LayoutContext {
+    /// Size of the element, if it has been laid out.
+    pub(crate) node_size: Option<Vec2>,
+    /// Size of the element's parent, or of the viewport for a root element.
+    pub(crate) parent_size: Vec2,
+    /// Size of the primary window.
+    pub(crate) viewport_size: Vec2,
+}
+
+impl LayoutContext {
+    pub(crate) fn new(world: &mut World, entity: Entity) -> Self {
+        let viewport_size = world
+            .query_filtered::<&Window, With<PrimaryWindow>>()
+            .get_single(world)
+            .map(|window| Vec2::new(window.width(), window.height()))
+            .unwrap_or_default();
+        let node_size = world.get::<Node>(entity).map(Node::size);
+        let parent_size = world
+            .get::<Parent>(entity)
+            .and_then(|parent| world.get::<Node>(parent.get()))
+            .map_or(viewport_size, Node::size);
+        Self {
+            node_size,
+            parent_size,
+            viewport_size,
+        }
+    }
+}
+
+/// The color space in which color transitions are interpolated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ColorSpace {
+    /// The perceptually uniform Oklab space, which avoids the muddy midpoints of RGB
+    /// interpolation.
+    #[default]
+    Oklab,
+
+    /// Linear RGB.
+    LinearRgb,
+
+    /// Hue, saturation and lightness, with the hue taking the shortest way around the color
+    /// wheel.
+    Hsl,
+}
+
+impl ColorSpace {
+    /// Interpolate between two colors in this color space.
+    pub fn mix(&self, origin: Color, target: Color, t: f32) -> Color {
+        let origin = LinearRgba::from(origin);
+        let target = LinearRgba::from(target);
+        let mixed = match self {
+            ColorSpace::Oklab => {
+                ColorRange::new(Oklaba::from(origin), Oklaba::from(target)).at_linear(t)
+            }
+            ColorSpace::LinearRgb => ColorRange::new(origin, target).at_linear(t),
+            ColorSpace::Hsl => ColorRange::new(Hsla::from(origin), Hsla::from(target)).at_linear(t),
+        };
+        mixed.into()
+    }
 }
 
 /// Defines a CSS-like animated transition
@@ -144,7 +321,10 @@ pub struct Transition {
     pub duration: f32,
 
     /// Easing function
-    pub timing: &'static dyn TimingFunction,
+    pub timing: Timing,
+
+    /// Color space used to interpolate color properties
+    pub color_space: ColorSpace,
 }
 
 impl Default for Transition {
@@ -154,6 +334,7 @@ impl Default for Transition {
             delay: 0.,
             duration: 0.,
             timing: timing::LINEAR,
+            color_space: ColorSpace::default(),
         }
     }
 }
@@ -187,34 +368,71 @@ pub struct AnimatedTransform {
     pub(crate) target: Transform,
 }
 
-#[derive(Component)]
-#[doc(hidden)]
-pub struct AnimatedBackgroundColor {
+pub struct AnimatedColor {
     pub(crate) state: TransitionState,
     pub(crate) origin: Color,
     pub(crate) target: Color,
 }
 
-#[derive(Component)]
-#[doc(hidden)]
-pub struct AnimatedBorderColor {
-    pub(crate) state: TransitionState,
-    pub(crate) origin: Color,
-    pub(crate) target: Color,
+impl AnimatedColor {
+    /// Construct a new color animation which is already at its target color.
+    pub fn new(transition: Transition, color: Color) -> Self {
+        Self {
+            state: TransitionState {
+                transition,
+                clock: 1.,
+            },
+            origin: color,
+            target: color,
+        }
+    }
+
+    /// Restart the animation from the current color if the target changed.
+    pub fn restart_if_changed(&mut self, current: Color, target: Color) {
+        if self.target != target {
+            self.origin = current;
+            self.target = target;
+            self.state.clock = 0.;
+        }
+    }
+
+    /// Advance the animation clock, and return the new color if it has changed.
+    pub fn update(&mut self, delta: f32) -> Option<Color> {
+        let clock_old = self.state.clock;
+        self.state.advance(delta);
+        if self.state.clock == clock_old {
+            return None;
+        }
+        let color_space = self.state.transition.color_space;
+        Some(color_space.mix(self.origin, self.target, self.state.t()))
+    }
 }
 
+#[derive(Component, Default)]
+#[doc(hidden)]
+pub struct AnimatedColors(pub HashMap<TransitionProperty, AnimatedColor>);
+
 pub struct AnimatedLayoutProp {
     pub(crate) state: TransitionState,
-    pub(crate) origin: f32,
-    pub(crate) target: f32,
+    /// Value at the start of the animation.
+    pub(crate) origin: ui::Val,
+    /// Value at the end of the animation, in the same units as `origin`.
+    pub(crate) target: ui::Val,
+    /// The value from the computed style, which is applied once the animation is done.
+    pub(crate) end: ui::Val,
 }
 
 impl AnimatedLayoutProp {
-    pub fn new(state: TransitionState) -> Self {
+    /// Construct a new animation which is already at its final value.
+    pub fn new(transition: Transition, value: ui::Val) -> Self {
         Self {
-            state,
-            origin: 0.,
-            target: 0.,
+            state: TransitionState {
+                transition,
+                clock: 1.,
+            },
+            origin: value,
+            target: value,
+            end: value,
         }
     }
 
@@ -222,61 +440,122 @@ impl AnimatedLayoutProp {
     pub fn update(&mut self, prop: TransitionProperty, style: &mut Style, delta: f32, force: bool) {
         let t_old = self.state.clock;
         self.state.advance(delta);
-        let t = self.state.transition.timing.eval(self.state.clock);
-        if t != t_old || force {
-            let value = self.target * t + self.origin * (1. - t);
-            match prop {
-                TransitionProperty::Width => style.width = ui::Val::Px(value),
-                TransitionProperty::Height => style.height = ui::Val::Px(value),
-                TransitionProperty::Left => style.left = ui::Val::Px(value),
-                TransitionProperty::Top => style.top = ui::Val::Px(value),
-                TransitionProperty::Bottom => style.bottom = ui::Val::Px(value),
-                TransitionProperty::Right => style.right = ui::Val::Px(value),
-                TransitionProperty::BorderLeft => style.border.left = ui::Val::Px(value),
-                TransitionProperty::BorderTop => style.border.top = ui::Val::Px(value),
-                TransitionProperty::BorderRight => style.border.right = ui::Val::Px(value),
-                TransitionProperty::BorderBottom => style.border.bottom = ui::Val::Px(value),
-                TransitionProperty::Transform
-                | TransitionProperty::BackgroundColor
-                | TransitionProperty::BorderColor => panic!("Invalid style transition prop"),
-            }
+        if self.state.clock != t_old || force {
+            let value = if self.state.clock >= 1. {
+                self.end
+            } else {
+                lerp_val(self.origin, self.target, self.state.t())
+            };
+            prop.set_layout_value(style, value);
         }
     }
 
-    /// Restart the animation with a new target if the target changed.
-    pub fn restart_if_changed(
+    /// Restart the animation with a new target if the target changed. If the current and
+    /// target values are in different units, both are converted to pixels using the most
+    /// recent layout. Values which can't be converted, such as a target of `Val::Auto`, are
+    /// applied immediately.
+    pub(crate) fn restart_if_changed(
         &mut self,
         prop: TransitionProperty,
         prev_style: &Style, // The current style values
         next_style: &Style, // The targets we are going for
+        layout: &LayoutContext,
     ) {
-        let (next, prev) = match prop {
-            TransitionProperty::Width => (next_style.width, prev_style.width),
-            TransitionProperty::Height => (next_style.height, prev_style.height),
-            TransitionProperty::Left => (next_style.left, prev_style.left),
-            TransitionProperty::Top => (next_style.top, prev_style.top),
-            TransitionProperty::Bottom => (next_style.bottom, prev_style.bottom),
-            TransitionProperty::Right => (next_style.right, prev_style.right),
-            TransitionProperty::BorderLeft => (next_style.border.left, prev_style.border.left),
-            TransitionProperty::BorderTop => (next_style.border.top, prev_style.border.top),
-            TransitionProperty::BorderRight => (next_style.border.right, prev_style.border.right),
-            TransitionProperty::BorderBottom => {
-                (next_style.border.bottom, prev_style.border.bottom)
-            }
-            TransitionProperty::Transform
-            | TransitionProperty::BackgroundColor
-            | TransitionProperty::BorderColor => panic!("Invalid style transition prop"),
-        };
+        let next = prop.layout_value(next_style);
+        if self.end == next {
+            return;
+        }
+        let prev = prop.layout_value(prev_style);
+        self.end = next;
+        self.state.clock = 0.;
+        if prev != ui::Val::Auto && std::mem::discriminant(&prev) == std::mem::discriminant(&next) {
+            self.origin = prev;
+            self.target = next;
+        } else if let (Some(origin), Some(target)) = (
+            prop.resolve(prev, layout),
+            // The size of an `auto` element is only known for the current layout.
+            Some(next)
+                .filter(|next| *next != ui::Val::Auto)
+                .and_then(|next| prop.resolve(next, layout)),
+        ) {
+            self.origin = ui::Val::Px(origin);
+            self.target = ui::Val::Px(target);
+        } else {
+            self.origin = next;
+            self.target = next;
+            self.state.clock = 1.;
+        }
+    }
+}
 
-        // Assume that all values are in pixels, we don't try and animate in other units.
-        if let (ui::Val::Px(next_value), ui::Val::Px(prev_value)) = (next, prev) {
-            if self.target != next_value {
-                self.origin = prev_value;
-                self.target = next_value;
-                self.state.clock = 0.;
-            }
+/// Interpolate between two lengths. Lengths in different units can't be interpolated without
+/// knowing the layout, so they switch halfway through.
+pub(crate) fn lerp_val(a: ui::Val, b: ui::Val, t: f32) -> ui::Val {
+    let lerp = |a: f32, b: f32| a + (b - a) * t;
+    match (a, b) {
+        (ui::Val::Px(a), ui::Val::Px(b)) => ui::Val::Px(lerp(a, b)),
+        (ui::Val::Percent(a), ui::Val::Percent(b)) => ui::Val::Percent(lerp(a, b)),
+        (ui::Val::Vw(a), ui::Val::Vw(b)) => ui::Val::Vw(lerp(a, b)),
+        (ui::Val::Vh(a), ui::Val::Vh(b)) => ui::Val::Vh(lerp(a, b)),
+        (ui::Val::VMin(a), ui::Val::VMin(b)) => ui::Val::VMin(lerp(a, b)),
+        (ui::Val::VMax(a), ui::Val::VMax(b)) => ui::Val::VMax(lerp(a, b)),
+        _ if t < 0.5 => a,
+        _ => b,
+    }
+}
+
+/// Animates the opacity of an element. Unlike other transitions, which update the element's
+/// components directly, the animated value is read when computing styles, so that it can be
+/// inherited by the element's descendants.
+#[derive(Component)]
+#[doc(hidden)]
+pub struct AnimatedOpacity {
+    pub(crate) state: TransitionState,
+    pub(crate) origin: f32,
+    pub(crate) target: f32,
+    pub(crate) value: f32,
+}
+
+impl AnimatedOpacity {
+    /// Construct a new opacity animation which is already at its target value.
+    pub fn new(transition: Transition, opacity: f32) -> Self {
+        Self {
+            state: TransitionState {
+                transition,
+                clock: 1.,
+            },
+            origin: opacity,
+            target: opacity,
+            value: opacity,
         }
     }
+
+    /// Construct an animation from the current value of this animation to a new target.
+    pub fn restart(&self, transition: Transition, target: f32) -> Self {
+        Self {
+            state: TransitionState {
+                transition,
+                clock: 0.,
+            },
+            origin: self.value,
+            target,
+            value: self.value,
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn animate_opacity(mut query: Query<&mut AnimatedOpacity>, time: Res<Time>) {
+    for mut anim in query.iter_mut() {
+        // Avoid triggering change detection, which causes styles to be re-computed, once the
+        // animation is done.
+        if anim.state.clock >= 1. {
+            continue;
+        }
+        anim.state.advance(time.delta_seconds());
+        let t = anim.state.t();
+        anim.value = anim.origin + (anim.target - anim.origin) * t;
+    }
 }
 
 #[derive(Component)]
@@ -301,38 +580,60 @@ pub fn animate_transforms(
 }
 
 #[doc(hidden)]
-pub fn animate_bg_colors(
+#[allow(clippy::type_complexity)]
+pub fn animate_colors(
     mut query: Query<(
-        Entity,
+        &mut AnimatedColors,
         Option<&mut BackgroundColor>,
-        &mut AnimatedBackgroundColor,
+        Option<&mut BorderColor>,
+        Option<&mut Text>,
+        Option<&mut Outline>,
+        Option<&Handle<StyleMaterial>>,
     )>,
+    mut materials: Option<ResMut<Assets<StyleMaterial>>>,
     time: Res<Time>,
 ) {
-    #![allow(unused)]
-    for (e, mut bg, mut at) in query.iter_mut() {
-        let t_old = at.state.clock;
-        at.state.advance(time.delta_seconds());
-        let t = at.state.transition.timing.eval(at.state.clock);
-        let origin = at.origin.as_rgba_linear();
-        let target = at.target.as_rgba_linear();
-        todo!("Finish color space interpolation!");
-    }
-}
-
-#[doc(hidden)]
-pub fn animate_border_colors(
-    mut query: Query<(Entity, Option<&mut BorderColor>, &mut AnimatedBorderColor)>,
-    time: Res<Time>,
-) {
-    #![allow(unused)]
-    for (e, mut bg, mut at) in query.iter_mut() {
-        let t_old = at.state.clock;
-        at.state.advance(time.delta_seconds());
-        let t = at.state.transition.timing.eval(at.state.clock);
-        let origin = at.origin.as_rgba_linear();
-        let target = at.target.as_rgba_linear();
-        todo!("Finish color space interpolation!");
+    let delta = time.delta_seconds();
+    for (mut anim, mut bg, mut border, mut text, mut outline, material) in query.iter_mut() {
+        for (prop, color_anim) in anim.0.iter_mut() {
+            let Some(color) = color_anim.update(delta) else {
+                continue;
+            };
+            match prop {
+                TransitionProperty::BackgroundColor => {
+                    if let Some(bg) = bg.as_mut() {
+                        bg.0 = color;
+                    } else if let Some(material) =
+                        material.and_then(|handle| materials.as_mut()?.get_mut(handle))
+                    {
+                        // Decorated elements draw their background with a material instead.
+                        material.params.background = linear_color(color);
+                    }
+                }
+                TransitionProperty::BorderColor => {
+                    if let Some(border) = border.as_mut() {
+                        border.0 = color;
+                    } else if let Some(material) =
+                        material.and_then(|handle| materials.as_mut()?.get_mut(handle))
+                    {
+                        material.params.border_color = linear_color(color);
+                    }
+                }
+                TransitionProperty::Color => {
+                    if let Some(text) = text.as_mut() {
+                        for section in text.sections.iter_mut() {
+                            section.style.color = color;
+                        }
+                    }
+                }
+                TransitionProperty::OutlineColor => {
+                    if let Some(outline) = outline.as_mut() {
+                        outline.color = color;
+                    }
+                }
+                _ => {}
+            }
+        }
     }
 }
 
@@ -345,3 +646,113 @@ pub fn animate_layout(mut query: Query<(&mut Style, &mut AnimatedLayout)>, time:
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mix_colors() {
+        let red = Color::rgb(1., 0., 0.);
+        let blue = Color::rgb(0., 0., 1.);
+        for space in [ColorSpace::Oklab, ColorSpace::LinearRgb, ColorSpace::Hsl] {
+            let start = space.mix(red, blue, 0.).as_rgba_f32();
+            let end = space.mix(red, blue, 1.).as_rgba_f32();
+            for (a, b) in start.iter().zip(red.as_rgba_f32()) {
+                assert!((a - b).abs() < 0.001, "{:?}", space);
+            }
+            for (a, b) in end.iter().zip(blue.as_rgba_f32()) {
+                assert!((a - b).abs() < 0.001, "{:?}", space);
+            }
+        }
+
+        // Hue takes the shortest path, from red through magenta to blue.
+        let mid = ColorSpace::Hsl.mix(red, blue, 0.5).as_hsla_f32();
+        assert!((mid[0] - 300.).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_animated_layout() {
+        let transition = Transition {
+            property: TransitionProperty::Height,
+            duration: 1.,
+            ..default()
+        };
+        let layout = LayoutContext {
+            node_size: Some(Vec2::new(100., 50.)),
+            parent_size: Vec2::new(200., 400.),
+            viewport_size: Vec2::new(800., 600.),
+        };
+        let mut style = Style::default();
+        let mut anim = AnimatedLayoutProp::new(transition, ui::Val::Auto);
+
+        // Collapse from `auto`, using the height from the last layout.
+        let next = Style {
+            height: ui::Val::Px(0.),
+            ..default()
+        };
+        anim.restart_if_changed(TransitionProperty::Height, &style, &next, &layout);
+        anim.update(TransitionProperty::Height, &mut style, 0.5, false);
+        assert_eq!(style.height, ui::Val::Px(25.));
+        anim.update(TransitionProperty::Height, &mut style, 0.5, false);
+        assert_eq!(style.height, ui::Val::Px(0.));
+
+        // Mixed units are converted to pixels, but the final value keeps its units.
+        let next = Style {
+            height: ui::Val::Percent(50.),
+            ..default()
+        };
+        anim.restart_if_changed(TransitionProperty::Height, &style, &next, &layout);
+        anim.update(TransitionProperty::Height, &mut style, 0.5, false);
+        assert_eq!(style.height, ui::Val::Px(100.));
+        anim.update(TransitionProperty::Height, &mut style, 0.5, false);
+        assert_eq!(style.height, ui::Val::Percent(50.));
+
+        // Expanding to `auto` can't be animated.
+        anim.restart_if_changed(
+            TransitionProperty::Height,
+            &style,
+            &Style::default(),
+            &layout,
+        );
+        anim.update(TransitionProperty::Height, &mut style, 0., true);
+        assert_eq!(style.height, ui::Val::Auto);
+    }
+
+    #[test]
+    fn test_animated_opacity() {
+        let transition = Transition {
+            property: TransitionProperty::Opacity,
+            duration: 1.,
+            ..default()
+        };
+        let anim = AnimatedOpacity::new(transition.clone(), 0.);
+        let mut anim = anim.restart(transition.clone(), 1.);
+        anim.state.advance(0.25);
+        anim.value = 0.25;
+
+        // Retargeting mid-way starts from the current value.
+        let anim = anim.restart(transition, 0.);
+        assert_eq!(anim.origin, 0.25);
+        assert_eq!(anim.target, 0.);
+        assert_eq!(anim.state.clock, 0.);
+    }
+
+    #[test]
+    fn test_animated_color() {
+        let transition = Transition {
+            property: TransitionProperty::BackgroundColor,
+            duration: 1.,
+            color_space: ColorSpace::LinearRgb,
+            ..default()
+        };
+        let mut anim = AnimatedColor::new(transition, Color::BLACK);
+        assert_eq!(anim.update(0.5), None);
+
+        anim.restart_if_changed(Color::BLACK, Color::WHITE);
+        let half = anim.update(0.5).unwrap().as_linear_rgba_f32();
+        assert!((half[0] - 0.5).abs() < 0.001);
+        assert!(anim.update(0.5).is_some());
+        assert_eq!(anim.update(0.5), None);
+    }
+}
@@ -1,20 +1,211 @@
 use bevy::{
     a11y::Focus,
+    asset::AssetPath,
+    ecs::system::SystemParam,
     prelude::*,
-    render::texture::{ImageLoaderSettings, ImageSampler},
+    render::{
+        camera::NormalizedRenderTarget,
+        texture::{ImageLoaderSettings, ImageSampler},
+    },
+    ui::TargetCamera,
+    utils::{HashMap, HashSet},
+    window::PrimaryWindow,
+};
+use bevy_mod_picking::{
+    backend::HitData,
+    focus::{HoverMap, PickingInteraction, PreviousHoverMap},
+    pointer::PointerId,
 };
-use bevy_mod_picking::focus::{HoverMap, PreviousHoverMap};
 
 use crate::{
-    style::{ComputedStyle, UpdateComputedStyle},
-    ElementClasses, ElementStyles, SelectorMatcher,
+    rich_text::{text_style_eq, UpdateSpanStyle},
+    style::{
+        selector_matcher::{ContainerQuery, ElementStateQuery},
+        ComputedStyle, UpdateComputedStyle,
+    },
+    Checked, DescendantStyles, Disabled, ElementClasses, ElementStyles, ImageTint, RichTextSpans,
+    SelectorMatcher, TextSpan,
+};
+
+use super::{
+    cascade::Cascade,
+    media::{ColorScheme, MediaContext},
+    style_handle::{ScopedStyles, TextStyles},
+    transition::{AnimatedOpacity, TransitionProperty},
+    vars::StyleVars,
 };
 
-use super::style_handle::TextStyles;
+/// Load an image asset with the settings used for UI images.
+pub(crate) fn load_image(assets: &AssetServer, path: &AssetPath<'static>) -> Handle<Image> {
+    assets.load_with_settings(path, |s: &mut ImageLoaderSettings| {
+        s.sampler = ImageSampler::linear()
+    })
+}
 
 #[derive(Resource, Default)]
 pub(crate) struct PreviousFocus(Option<Entity>);
 
+/// Elements which have changed since styles were last updated, in ways which can affect the
+/// result of matching selectors.
+struct ElementChanges {
+    /// Elements whose `:active`, `:disabled` or `:checked` state may have changed.
+    state: HashSet<Entity>,
+    /// Elements whose name has changed.
+    names: HashSet<Entity>,
+    /// Elements which have lost all of their children, and so no longer have [`Children`].
+    emptied: HashSet<Entity>,
+    /// Elements which any selector term might now match differently, including because of
+    /// changes to their hover or focus state, or to their siblings. Selectors with descendant
+    /// combinators are re-evaluated for all of the descendants of these elements.
+    any: HashSet<Entity>,
+}
+
+/// Tracks entities whose state, name, classes or position in the hierarchy may have changed.
+#[derive(SystemParam)]
+#[allow(clippy::type_complexity)]
+pub(crate) struct ElementStateChanges<'w, 's> {
+    changed:
+        Query<'w, 's, Entity, Or<(Added<Disabled>, Added<Checked>, Changed<PickingInteraction>)>>,
+    removed_disabled: RemovedComponents<'w, 's, Disabled>,
+    removed_checked: RemovedComponents<'w, 's, Checked>,
+    removed_interaction: RemovedComponents<'w, 's, PickingInteraction>,
+    changed_names: Query<'w, 's, Entity, Changed<Name>>,
+    changed_classes: Query<'w, 's, Entity, Changed<ElementClasses>>,
+    changed_children: Query<'w, 's, Entity, Or<(Changed<Children>, Changed<Parent>)>>,
+    removed_children: RemovedComponents<'w, 's, Children>,
+}
+
+impl ElementStateChanges<'_, '_> {
+    fn collect(
+        &mut self,
+        hover_map: &HoverMap,
+        hover_map_prev: &PreviousHoverMap,
+        focus: Option<Entity>,
+        focus_prev: Option<Entity>,
+        parent_query: &Query<&Parent, (With<Node>, With<Visibility>)>,
+        children_query: &Query<Ref<Children>, (With<Node>, With<Visibility>)>,
+    ) -> ElementChanges {
+        let mut state: HashSet<Entity> = self.changed.iter().collect();
+        state.extend(self.removed_disabled.read());
+        state.extend(self.removed_checked.read());
+        state.extend(self.removed_interaction.read());
+        let names: HashSet<Entity> = self.changed_names.iter().collect();
+        let emptied: HashSet<Entity> = self.removed_children.read().collect();
+
+        // An element's siblings can be matched by sibling combinators and structural
+        // pseudo-classes, so they are affected by its changes as well.
+        let mut any: HashSet<Entity> = HashSet::new();
+        for entity in state
+            .iter()
+            .chain(names.iter())
+            .copied()
+            .chain(self.changed_classes.iter())
+        {
+            any.insert(entity);
+            if let Ok(siblings) = parent_query
+                .get(entity)
+                .and_then(|parent| children_query.get(parent.get()))
+            {
+                any.extend(siblings.iter());
+            }
+        }
+        any.extend(emptied.iter());
+        for entity in self.changed_children.iter() {
+            any.insert(entity);
+            if let Ok(children) = children_query.get(entity) {
+                any.extend(children.iter());
+            }
+        }
+
+        // Elements are hovered if they, or one of their descendants, are under the pointer.
+        let hovered = |map: &HashMap<PointerId, HashMap<Entity, HitData>>| {
+            let mut hovered = HashSet::new();
+            for entity in map
+                .get(&PointerId::Mouse)
+                .into_iter()
+                .flat_map(|m| m.keys())
+            {
+                with_ancestors(*entity, parent_query, |e| hovered.insert(e));
+            }
+            hovered
+        };
+        let hovered_now = hovered(&hover_map.0);
+        let hovered_prev = hovered(&hover_map_prev.0);
+        any.extend(hovered_now.symmetric_difference(&hovered_prev));
+
+        if focus != focus_prev {
+            for entity in focus.iter().chain(focus_prev.iter()) {
+                with_ancestors(*entity, parent_query, |e| {
+                    any.insert(e);
+                    true
+                });
+            }
+        }
+
+        ElementChanges {
+            state,
+            names,
+            emptied,
+            any,
+        }
+    }
+}
+
+/// Call `f` for an entity and each of its ancestors, stopping early if `f` returns false.
+fn with_ancestors(
+    entity: Entity,
+    parent_query: &Query<&Parent, (With<Node>, With<Visibility>)>,
+    mut f: impl FnMut(Entity) -> bool,
+) {
+    let mut e = entity;
+    while f(e) {
+        match parent_query.get(e) {
+            Ok(parent) => e = parent.get(),
+            Err(_) => break,
+        }
+    }
+}
+
+/// Determines the properties of the windows targeted by UI root nodes, and the sizes of
+/// container elements, which are used to evaluate media and container queries.
+#[derive(SystemParam)]
+pub(crate) struct StyleQueries<'w, 's> {
+    windows: Query<'w, 's, &'static Window>,
+    primary_window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, &'static Camera>,
+    target_cameras: Query<'w, 's, &'static TargetCamera>,
+    color_scheme: Res<'w, ColorScheme>,
+    previous: Local<'s, HashMap<Entity, MediaContext>>,
+    containers: ContainerQuery<'w, 's>,
+}
+
+impl StyleQueries<'_, '_> {
+    /// Returns the media context for the window targeted by a UI root node. Nodes without a
+    /// [`TargetCamera`] are assumed to be displayed in the primary window.
+    fn context(&self, root: Entity) -> MediaContext {
+        let primary = self.primary_window.get_single().ok();
+        let window = match self.target_cameras.get(root) {
+            Ok(target) => self.cameras.get(target.entity()).ok().and_then(|camera| {
+                match camera.target.normalize(primary)? {
+                    NormalizedRenderTarget::Window(window) => Some(window.entity()),
+                    _ => None,
+                }
+            }),
+            Err(_) => primary,
+        };
+        let mut context = MediaContext {
+            color_scheme: *self.color_scheme,
+            ..default()
+        };
+        if let Some(window) = window.and_then(|window| self.windows.get(window).ok()) {
+            context.width = window.width();
+            context.height = window.height();
+            context.scale_factor = window.scale_factor();
+        }
+        context
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::type_complexity)]
 pub(crate) fn update_styles(
@@ -26,45 +217,86 @@ pub(crate) fn update_styles(
             Option<Ref<ElementStyles>>,
             Option<&TextStyles>,
             Option<Ref<Text>>,
+            Option<&RichTextSpans>,
+            Option<Ref<DescendantStyles>>,
+            Option<&StyleVars>,
+            Option<Ref<AnimatedOpacity>>,
+            Option<Ref<ImageTint>>,
+            Option<&ScopedStyles>,
         ),
         With<Node>,
     >,
+    query_spans: Query<(&TextSpan, Option<Ref<ElementStyles>>)>,
     query_element_classes: Query<Ref<'static, ElementClasses>>,
+    query_names: Query<&'static Name>,
+    query_state: ElementStateQuery,
+    mut state_changes: ElementStateChanges,
     query_parents: Query<&'static Parent, (With<Node>, With<Visibility>)>,
-    query_children: Query<&'static Children, (With<Node>, With<Visibility>)>,
+    query_children: Query<Ref<'static, Children>, (With<Node>, With<Visibility>)>,
     hover_map: Res<HoverMap>,
     hover_map_prev: Res<PreviousHoverMap>,
     assets: Res<AssetServer>,
     focus: Res<Focus>,
     mut focus_prev: ResMut<PreviousFocus>,
+    mut queries: StyleQueries,
 ) {
-    let matcher = SelectorMatcher::new(
-        &query_element_classes,
-        &query_parents,
-        &query_children,
-        &hover_map.0,
+    let changes = state_changes.collect(
+        &hover_map,
+        &hover_map_prev,
         focus.0,
-    );
-    let matcher_prev = SelectorMatcher::new(
-        &query_element_classes,
+        focus_prev.0,
         &query_parents,
         &query_children,
-        &hover_map_prev.0,
-        focus_prev.0,
     );
+    let mut media_prev = std::mem::take(&mut *queries.previous);
 
     for root_node in &query_root {
+        let context = queries.context(root_node);
+        let context_prev = media_prev.remove(&root_node).unwrap_or(context);
+        queries.previous.insert(root_node, context);
+
+        let matcher = SelectorMatcher::new(
+            &query_element_classes,
+            &query_names,
+            &query_state,
+            &query_parents,
+            &query_children,
+            &hover_map.0,
+            focus.0,
+            context,
+            &queries.containers,
+        );
+        let matcher_prev = SelectorMatcher::new(
+            &query_element_classes,
+            &query_names,
+            &query_state,
+            &query_parents,
+            &query_children,
+            &hover_map_prev.0,
+            focus_prev.0,
+            context_prev,
+            &queries.containers,
+        );
+
         update_element_styles(
             &mut commands,
             &query_styles,
+            &query_spans,
             &query_element_classes,
             &query_parents,
             &query_children,
             &matcher,
             &matcher_prev,
+            &changes,
             &assets,
             root_node,
+            false,
             &TextStyles::default(),
+            1.,
+            false,
+            &ElementStyles::default(),
+            false,
+            &StyleVars::default(),
             false,
         )
     }
@@ -82,41 +314,128 @@ fn update_element_styles(
             Option<Ref<ElementStyles>>,
             Option<&TextStyles>,
             Option<Ref<Text>>,
+            Option<&RichTextSpans>,
+            Option<Ref<DescendantStyles>>,
+            Option<&StyleVars>,
+            Option<Ref<AnimatedOpacity>>,
+            Option<Ref<ImageTint>>,
+            Option<&ScopedStyles>,
         ),
         With<Node>,
     >,
+    query_spans: &Query<(&TextSpan, Option<Ref<ElementStyles>>)>,
     classes_query: &Query<Ref<'static, ElementClasses>>,
     parent_query: &Query<'_, '_, &Parent, (With<Node>, With<Visibility>)>,
-    children_query: &Query<'_, '_, &Children, (With<Node>, With<Visibility>)>,
+    children_query: &Query<'_, '_, Ref<'static, Children>, (With<Node>, With<Visibility>)>,
     matcher: &SelectorMatcher<'_, '_, '_>,
     matcher_prev: &SelectorMatcher<'_, '_, '_>,
+    changes: &ElementChanges,
     assets: &Res<AssetServer>,
     entity: Entity,
+    ancestor_changed: bool,
     inherited_styles: &TextStyles,
+    inherited_opacity_prev: f32,
     mut inherited_styles_changed: bool,
+    inherited_rules: &ElementStyles,
+    mut inherited_rules_changed: bool,
+    inherited_vars: &StyleVars,
+    mut inherited_vars_changed: bool,
 ) {
     let mut text_styles = inherited_styles.clone();
+    // The opacity that this element's children were last styled with.
+    let mut opacity_prev = inherited_opacity_prev;
+    let mut scoped_rules = None;
+    let mut built_rules = None;
+    let mut vars = inherited_vars.clone();
+
+    if let Ok((
+        style,
+        elt_styles,
+        prev_text_styles,
+        txt,
+        spans,
+        descendant_styles,
+        prev_vars,
+        animated_opacity,
+        image_tint,
+        scoped_prev,
+    )) = query_styles.get(entity)
+    {
+        // Image views draw their own tint, which also needs to be faded.
+        if let Some(tint) = image_tint {
+            if tint.is_changed() || inherited_styles.opacity != inherited_opacity_prev {
+                let color = tint.0.with_a(tint.0.a() * inherited_styles.opacity);
+                commands.entity(entity).insert(BackgroundColor(color));
+            }
+        }
 
-    if let Ok((style, elt_styles, prev_text_styles, txt)) = query_styles.get(entity) {
         // Check if the element styles or ancestor classes have changed.
         let mut changed = match elt_styles {
-            Some(ref element_style) => is_changed(
-                element_style,
-                entity,
-                classes_query,
-                matcher,
-                matcher_prev,
-                parent_query,
-            ),
+            Some(ref element_style) => {
+                element_style.is_changed()
+                    || selectors_changed(
+                        element_style,
+                        entity,
+                        ancestor_changed,
+                        classes_query,
+                        matcher,
+                        matcher_prev,
+                        parent_query,
+                        children_query,
+                        changes,
+                    )
+            }
             None => false,
         };
 
+        // Check whether the descendant styles inherited from ancestors need to be re-applied.
+        if !inherited_rules.styles.is_empty() {
+            changed = changed
+                || inherited_rules_changed
+                || style.is_added()
+                || selectors_changed(
+                    inherited_rules,
+                    entity,
+                    ancestor_changed,
+                    classes_query,
+                    matcher,
+                    matcher_prev,
+                    parent_query,
+                    children_query,
+                    changes,
+                );
+        }
+
         if let Some(ref text_node) = txt {
             if text_node.is_changed() {
                 changed = true;
             }
         }
 
+        // Opacity fades the element's own colors, so a styled element needs to be re-styled
+        // whenever the opacity inherited from its ancestors changes, even if nothing else did.
+        let is_styled = elt_styles.is_some() || !inherited_rules.styles.is_empty();
+        if (is_styled && inherited_styles.opacity != inherited_opacity_prev)
+            || animated_opacity
+                .as_ref()
+                .is_some_and(|anim| anim.is_changed())
+        {
+            changed = true;
+        }
+
+        // Elements without cached text styles had the same opacity as their parent.
+        opacity_prev = prev_text_styles.map_or(inherited_opacity_prev, |prev| prev.opacity);
+
+        // Variables defined by an ancestor have changed; re-compute the element if it
+        // references them, or if it defines variables of its own that need to be merged.
+        if inherited_vars_changed
+            && (inherited_rules.uses_vars
+                || elt_styles.as_ref().is_some_and(|s| s.uses_vars)
+                || prev_vars.is_some())
+        {
+            changed = true;
+        }
+
         if changed || inherited_styles_changed {
             // Compute computed style. Initialize to the current state.
             let mut computed = ComputedStyle::new();
@@ -126,92 +445,288 @@ fn update_element_styles(
             computed.font_handle = inherited_styles.font.clone();
             computed.font_size = inherited_styles.font_size;
             computed.color = inherited_styles.color;
+            computed.inherited_opacity = Some(inherited_styles.opacity);
 
-            // Apply element styles to computed
+            // Apply descendant styles from ancestors, then element styles, in cascade order.
+            computed.vars.clone_from(&inherited_vars.0);
+            let mut cascade = Cascade::default();
+            for ss in inherited_rules.styles.iter() {
+                cascade.add(&ss.0, matcher, &entity);
+            }
+            cascade.next_origin();
             if let Some(ref element_styles) = elt_styles {
                 for ss in element_styles.styles.iter() {
-                    ss.apply_to(&mut computed, matcher, &entity);
+                    cascade.add(&ss.0, matcher, &entity);
+                }
+            }
+            cascade.apply_to(&mut computed);
+
+            // Load font asset if non-null.
+            if let Some(ref font_path) = computed.font {
+                computed.font_handle = Some(assets.load(font_path));
+            }
+
+            // An opacity transition replaces the opacity with its current value.
+            let opacity = computed.opacity.unwrap_or(1.);
+            let transition = computed
+                .transitions
+                .iter()
+                .find(|tr| tr.property == TransitionProperty::Opacity);
+            match (transition, animated_opacity) {
+                (Some(transition), Some(anim)) => {
+                    if anim.target != opacity {
+                        commands
+                            .entity(entity)
+                            .insert(anim.restart(transition.clone(), opacity));
+                    }
+                    computed.opacity = Some(anim.value);
+                }
+                (Some(transition), None) => {
+                    commands
+                        .entity(entity)
+                        .insert(AnimatedOpacity::new(transition.clone(), opacity));
                 }
-                // Load font asset if non-null.
-                if let Some(ref font_path) = computed.font {
-                    computed.font_handle = Some(assets.load(font_path));
+                (None, Some(_)) => {
+                    commands.entity(entity).remove::<AnimatedOpacity>();
                 }
+                (None, None) => {}
             }
 
             // Update inherited text styles
             text_styles.font = computed.font_handle.clone();
             text_styles.font_size = computed.font_size;
             text_styles.color = computed.color;
+            text_styles.opacity = computed.effective_opacity();
 
             if text_styles == *inherited_styles && txt.is_none() {
                 // No change from parent, so we can remove the cached styles and rely on inherited
                 // styles only. Note that for text nodes, we always want to store the inherited
                 // styles, even if they are the same as the parent.
-                inherited_styles_changed = prev_text_styles.is_some();
-                if inherited_styles_changed {
+                if prev_text_styles.is_some() {
+                    inherited_styles_changed = true;
                     changed = true;
                     commands.entity(entity).remove::<TextStyles>();
                 }
-            } else {
+            } else if prev_text_styles != Some(&text_styles) {
                 // Text styles are different from parent, so we need to store a cached copy.
-                inherited_styles_changed = prev_text_styles != Some(&text_styles);
-                if inherited_styles_changed {
-                    changed = true;
-                    commands.entity(entity).insert(text_styles.clone());
+                inherited_styles_changed = true;
+                changed = true;
+                commands.entity(entity).insert(text_styles.clone());
+            }
+
+            // Update variables visible to children, using the same caching strategy as
+            // for text styles.
+            vars.0.clone_from(&computed.vars);
+            if vars == *inherited_vars {
+                if prev_vars.is_some() {
+                    inherited_vars_changed = true;
+                    commands.entity(entity).remove::<StyleVars>();
                 }
+            } else if prev_vars != Some(&vars) {
+                inherited_vars_changed = true;
+                commands.entity(entity).insert(vars.clone());
             }
 
             if changed {
-                computed.image_handle = computed.image.as_ref().map(|path| {
-                    assets.load_with_settings(path, |s: &mut ImageLoaderSettings| {
-                        s.sampler = ImageSampler::linear()
-                    })
-                });
+                computed.image_handle =
+                    computed.image.as_ref().map(|path| load_image(assets, path));
 
                 commands.add(UpdateComputedStyle { entity, computed });
             }
-        } else if let Some(prev) = prev_text_styles {
-            // Styles didn't change, but we need to pass inherited text styles to children.
-            text_styles = prev.clone();
+        } else {
+            // Styles didn't change, but we need to pass inherited text styles and variables
+            // to children.
+            if let Some(prev) = prev_text_styles {
+                text_styles = prev.clone();
+            }
+            if let Some(prev) = prev_vars {
+                vars = prev.clone();
+            }
+        }
+
+        if let Some(spans) = spans {
+            update_span_styles(
+                commands,
+                query_spans,
+                classes_query,
+                parent_query,
+                children_query,
+                matcher,
+                matcher_prev,
+                changes,
+                assets,
+                spans,
+                ancestor_changed || changes.any.contains(&entity),
+                &text_styles,
+                &vars,
+                changed || inherited_styles_changed || inherited_vars_changed,
+            );
+        }
+
+        // Add this element's descendant styles to the ones inherited by its children. The
+        // combined list is cached, and only rebuilt when either part of it changes.
+        if let Some(descendant_styles) = descendant_styles {
+            inherited_rules_changed |= descendant_styles.is_changed();
+            let scoped = inherited_rules
+                .styles
+                .iter()
+                .chain(descendant_styles.styles.iter());
+            match scoped_prev {
+                Some(prev) if prev.0.styles.iter().eq(scoped.clone()) => {
+                    scoped_rules = Some(&prev.0);
+                }
+                _ => {
+                    let rules = ElementStyles::new(&scoped.cloned().collect::<Vec<_>>());
+                    commands.entity(entity).insert(ScopedStyles(rules.clone()));
+                    built_rules = Some(rules);
+                }
+            }
+        } else if scoped_prev.is_some() {
+            commands.entity(entity).remove::<ScopedStyles>();
         }
     }
 
+    let child_rules = built_rules
+        .as_ref()
+        .or(scoped_rules)
+        .unwrap_or(inherited_rules);
+    let ancestor_changed = ancestor_changed || changes.any.contains(&entity);
     if let Ok(children) = children_query.get(entity) {
         for child in children.iter() {
             update_element_styles(
                 commands,
                 query_styles,
+                query_spans,
                 classes_query,
                 parent_query,
                 children_query,
                 matcher,
                 matcher_prev,
+                changes,
                 assets,
                 *child,
+                ancestor_changed,
                 &text_styles,
+                opacity_prev,
                 inherited_styles_changed,
+                child_rules,
+                inherited_rules_changed,
+                &vars,
+                inherited_vars_changed,
             );
         }
     }
 }
 
-/// Detects whether the given entity's styles have changed, or whether any of its ancestors
-/// have changed in a way that would affect the computation of styles (either because
-/// of class list changes or hovering).
-fn is_changed(
-    element_styles: &Ref<'_, ElementStyles>,
+/// Compute the text styles for each span of a rich text node. Spans inherit from the text
+/// node, so all spans are re-computed if the text node's styles changed; otherwise only spans
+/// whose own styles or classes changed are updated.
+#[allow(clippy::too_many_arguments)]
+fn update_span_styles(
+    commands: &mut Commands,
+    query_spans: &Query<(&TextSpan, Option<Ref<ElementStyles>>)>,
+    classes_query: &Query<Ref<'static, ElementClasses>>,
+    parent_query: &Query<'_, '_, &Parent, (With<Node>, With<Visibility>)>,
+    children_query: &Query<'_, '_, Ref<'static, Children>, (With<Node>, With<Visibility>)>,
+    matcher: &SelectorMatcher<'_, '_, '_>,
+    matcher_prev: &SelectorMatcher<'_, '_, '_>,
+    changes: &ElementChanges,
+    assets: &Res<AssetServer>,
+    spans: &RichTextSpans,
+    ancestor_changed: bool,
+    inherited_styles: &TextStyles,
+    inherited_vars: &StyleVars,
+    force: bool,
+) {
+    for span in spans.0.iter() {
+        let Ok((text_span, elt_styles)) = query_spans.get(*span) else {
+            continue;
+        };
+        let changed = force
+            || elt_styles.as_ref().is_some_and(|element_style| {
+                element_style.is_changed()
+                    || selectors_changed(
+                        element_style,
+                        *span,
+                        ancestor_changed,
+                        classes_query,
+                        matcher,
+                        matcher_prev,
+                        parent_query,
+                        children_query,
+                        changes,
+                    )
+            });
+        if !changed {
+            continue;
+        }
+
+        let mut computed = ComputedStyle::new();
+        computed.font_handle = inherited_styles.font.clone();
+        computed.font_size = inherited_styles.font_size;
+        computed.color = inherited_styles.color;
+        computed.vars.clone_from(&inherited_vars.0);
+        if let Some(ref element_styles) = elt_styles {
+            let mut cascade = Cascade::default();
+            for ss in element_styles.styles.iter() {
+                cascade.add(&ss.0, matcher, span);
+            }
+            cascade.apply_to(&mut computed);
+            if let Some(ref font_path) = computed.font {
+                computed.font_handle = Some(assets.load(font_path));
+            }
+        }
+
+        let default_style = TextStyle::default();
+        // White is the default.
+        let color = computed.color.unwrap_or(Color::WHITE);
+        let opacity = inherited_styles.opacity * computed.opacity.unwrap_or(1.);
+        let style = TextStyle {
+            font: computed.font_handle.unwrap_or(default_style.font),
+            font_size: computed.font_size.unwrap_or(default_style.font_size),
+            color: color.with_a(color.a() * opacity),
+        };
+        if !text_style_eq(&style, &text_span.style) {
+            commands.add(UpdateSpanStyle { span: *span, style });
+        }
+    }
+}
+
+/// Detects whether the given entity, or any of its ancestors, have changed in a way that would
+/// affect the result of matching the selectors of the given styles (either because of class
+/// list changes, hovering, element state, changes to the hierarchy, or changes to the window or
+/// container size).
+#[allow(clippy::too_many_arguments)]
+fn selectors_changed(
+    element_styles: &ElementStyles,
     entity: Entity,
+    ancestor_changed: bool,
     classes_query: &Query<Ref<'static, ElementClasses>>,
     matcher: &SelectorMatcher<'_, '_, '_>,
     matcher_prev: &SelectorMatcher<'_, '_, '_>,
     parent_query: &Query<'_, '_, &Parent, (With<Node>, With<Visibility>)>,
+    children_query: &Query<'_, '_, Ref<'static, Children>, (With<Node>, With<Visibility>)>,
+    changes: &ElementChanges,
 ) -> bool {
-    // Style changes only affect current element, not children.
-    let mut changed = element_styles.is_changed();
+    // Descendant combinators can match any ancestor, so rather than searching all of them,
+    // use the flag which was passed down from the ancestors while traversing the hierarchy.
+    if element_styles.uses_ancestor && ancestor_changed {
+        return true;
+    }
+
+    if element_styles.uses_media && matcher.media() != matcher_prev.media() {
+        return true;
+    }
+
+    if element_styles.uses_container && matcher.is_container_changed(&entity) {
+        return true;
+    }
+
+    let mut changed = false;
 
     // Search ancestors to see if any have changed.
     // We want to know if either the class list or the hover state has changed.
-    if !changed && element_styles.selector_depth > 0 {
+    if element_styles.selector_depth > 0 {
         let mut e = entity;
         for _ in 0..element_styles.selector_depth {
             if let Ok(a_classes) = classes_query.get(e) {
@@ -245,6 +760,23 @@ fn is_changed(
                 }
             }
 
+            if element_styles.uses_state && changes.state.contains(&e) {
+                changed = true;
+                break;
+            }
+
+            if changes.names.contains(&e) {
+                changed = true;
+                break;
+            }
+
+            if element_styles.uses_structure
+                && structure_changed(e, classes_query, parent_query, children_query, changes)
+            {
+                changed = true;
+                break;
+            }
+
             match parent_query.get(e) {
                 Ok(parent) => e = **parent,
                 _ => break,
@@ -253,3 +785,255 @@ fn is_changed(
     }
     changed
 }
+
+/// Detects whether the children or siblings of an entity have changed, or whether any of its
+/// siblings have changed classes or state. This affects selectors such as `:nth-child` and `+`.
+fn structure_changed(
+    entity: Entity,
+    classes_query: &Query<Ref<'static, ElementClasses>>,
+    parent_query: &Query<'_, '_, &Parent, (With<Node>, With<Visibility>)>,
+    children_query: &Query<'_, '_, Ref<'static, Children>, (With<Node>, With<Visibility>)>,
+    changes: &ElementChanges,
+) -> bool {
+    if changes.emptied.contains(&entity)
+        || children_query
+            .get(entity)
+            .is_ok_and(|children| children.is_changed())
+    {
+        return true;
+    }
+    let Ok(parent) = parent_query.get(entity) else {
+        return false;
+    };
+    let Ok(siblings) = children_query.get(parent.get()) else {
+        return false;
+    };
+    siblings.is_changed()
+        || siblings.iter().any(|sibling| {
+            changes.state.contains(sibling)
+                || classes_query
+                    .get(*sibling)
+                    .is_ok_and(|classes| classes.is_changed())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        animate_colors,
+        style::transition::{AnimatedColors, Transition},
+        StyleHandle, StyleMaterial,
+    };
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_resource::<HoverMap>()
+            .init_resource::<PreviousHoverMap>()
+            .init_resource::<Focus>()
+            .init_resource::<PreviousFocus>()
+            .init_resource::<ColorScheme>()
+            .add_systems(Update, update_styles);
+        app
+    }
+
+    #[test]
+    fn test_opacity_fades_subtree() {
+        let mut app = test_app();
+        let world = &mut app.world;
+        let parent = world
+            .spawn((
+                NodeBundle::default(),
+                ElementStyles::new(&[StyleHandle::build(|ss| ss.opacity(1.))]),
+            ))
+            .id();
+        let child = world
+            .spawn((
+                NodeBundle::default(),
+                ElementStyles::new(&[StyleHandle::build(|ss| ss.background_color(Color::RED))]),
+            ))
+            .set_parent(parent)
+            .id();
+        let grandchild = world
+            .spawn(TextBundle::from_section("Hello", TextStyle::default()))
+            .set_parent(child)
+            .id();
+        let image = world
+            .spawn((NodeBundle::default(), ImageTint(Color::WHITE)))
+            .set_parent(child)
+            .id();
+        app.update();
+
+        let bg_alpha = |app: &App| app.world.get::<BackgroundColor>(child).unwrap().0.a();
+        let text_alpha = |app: &App| {
+            app.world.get::<Text>(grandchild).unwrap().sections[0]
+                .style
+                .color
+                .a()
+        };
+        let tint_alpha = |app: &App| app.world.get::<BackgroundColor>(image).unwrap().0.a();
+        assert_eq!(bg_alpha(&app), 1.);
+        assert_eq!(text_alpha(&app), 1.);
+        assert_eq!(tint_alpha(&app), 1.);
+
+        // Changing the parent's opacity re-styles descendants whose own styles didn't change.
+        app.world
+            .entity_mut(parent)
+            .insert(ElementStyles::new(&[StyleHandle::build(|ss| {
+                ss.opacity(0.5)
+            })]));
+        app.update();
+        assert_eq!(bg_alpha(&app), 0.5);
+        assert_eq!(text_alpha(&app), 0.5);
+        assert_eq!(tint_alpha(&app), 0.5);
+
+        // And restoring it brings them back.
+        app.world
+            .entity_mut(parent)
+            .insert(ElementStyles::new(&[StyleHandle::build(|ss| {
+                ss.opacity(1.)
+            })]));
+        app.update();
+        assert_eq!(bg_alpha(&app), 1.);
+        assert_eq!(text_alpha(&app), 1.);
+        assert_eq!(tint_alpha(&app), 1.);
+    }
+
+    #[test]
+    fn test_descendant_selector_changes() {
+        let mut app = test_app();
+        let world = &mut app.world;
+        let root = world
+            .spawn((NodeBundle::default(), ElementClasses::default()))
+            .id();
+        let panel = world
+            .spawn((
+                NodeBundle::default(),
+                DescendantStyles::new(&[StyleHandle::build(|ss| ss.border_color(Color::GREEN))]),
+            ))
+            .set_parent(root)
+            .id();
+        let leaf = world
+            .spawn((
+                NodeBundle::default(),
+                ElementStyles::new(&[StyleHandle::build(|ss| {
+                    ss.selector(".open &", |ss| ss.background_color(Color::RED))
+                        .selector("menu &", |ss| ss.background_color(Color::BLUE))
+                })]),
+            ))
+            .set_parent(panel)
+            .id();
+        app.update();
+
+        let bg = |app: &App| app.world.get::<BackgroundColor>(leaf).map(|bg| bg.0);
+        assert_ne!(bg(&app), Some(Color::RED));
+        assert_eq!(
+            app.world.get::<BorderColor>(leaf).map(|b| b.0),
+            Some(Color::GREEN)
+        );
+        let scoped_tick = |app: &App| {
+            app.world
+                .entity(panel)
+                .get_ref::<ScopedStyles>()
+                .unwrap()
+                .last_changed()
+        };
+        let tick = scoped_tick(&app);
+
+        // A class added to an ancestor more than one level up is detected.
+        app.world
+            .get_mut::<ElementClasses>(root)
+            .unwrap()
+            .add_class("open");
+        app.update();
+        assert_eq!(bg(&app), Some(Color::RED));
+
+        app.world
+            .get_mut::<ElementClasses>(root)
+            .unwrap()
+            .remove_class("open");
+        app.update();
+        assert_ne!(bg(&app), Some(Color::RED));
+
+        // So is a change of name.
+        app.world.entity_mut(root).insert(Name::new("menu"));
+        app.update();
+        assert_eq!(bg(&app), Some(Color::BLUE));
+
+        // The combined descendant styles are only rebuilt when they change.
+        assert_eq!(scoped_tick(&app), tick);
+    }
+
+    #[test]
+    fn test_empty_after_last_child_removed() {
+        let mut app = test_app();
+        let world = &mut app.world;
+        let parent = world
+            .spawn((
+                NodeBundle::default(),
+                ElementStyles::new(&[StyleHandle::build(|ss| {
+                    ss.selector(":empty", |ss| ss.background_color(Color::RED))
+                })]),
+            ))
+            .id();
+        let child = world.spawn(NodeBundle::default()).set_parent(parent).id();
+        app.update();
+
+        let bg = |app: &App| app.world.get::<BackgroundColor>(parent).map(|bg| bg.0);
+        assert_ne!(bg(&app), Some(Color::RED));
+
+        // Removing the last child removes the `Children` component altogether.
+        app.world.entity_mut(parent).remove_children(&[child]);
+        app.world.despawn(child);
+        assert!(app.world.get::<Children>(parent).is_none());
+        app.update();
+        assert_eq!(bg(&app), Some(Color::RED));
+    }
+
+    #[test]
+    fn test_decorated_color_transition() {
+        let mut app = test_app();
+        app.init_asset::<StyleMaterial>()
+            .add_systems(Update, animate_colors.after(update_styles));
+        let transition = Transition {
+            property: TransitionProperty::BackgroundColor,
+            duration: 10.,
+            ..default()
+        };
+        let style = |color: Color, transitions: &[Transition]| {
+            StyleHandle::build(|ss| {
+                ss.border_radius(4.)
+                    .background_color(color)
+                    .transition(transitions)
+            })
+        };
+        let element = app
+            .world
+            .spawn((
+                NodeBundle::default(),
+                ElementStyles::new(&[style(Color::BLACK, &[])]),
+            ))
+            .id();
+        app.update();
+
+        let background = |app: &App| {
+            let handle = app.world.get::<Handle<StyleMaterial>>(element).unwrap();
+            let materials = app.world.resource::<Assets<StyleMaterial>>();
+            materials.get(handle).unwrap().params.background
+        };
+        assert!(app.world.get::<BackgroundColor>(element).is_none());
+        assert_eq!(background(&app), Vec4::new(0., 0., 0., 1.));
+
+        // The material's background starts from black rather than jumping to white.
+        app.world
+            .entity_mut(element)
+            .insert(ElementStyles::new(&[style(Color::WHITE, &[transition])]));
+        app.update();
+        let anim = app.world.get::<AnimatedColors>(element).unwrap();
+        let color_anim = &anim.0[&TransitionProperty::BackgroundColor];
+        assert_eq!(color_anim.origin, Color::rgba_linear(0., 0., 0., 1.));
+        assert_eq!(color_anim.target, Color::WHITE);
+        assert!(background(&app).x < 0.5);
+    }
+}
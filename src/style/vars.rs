@@ -0,0 +1,128 @@
+use bevy::{prelude::*, ui, utils::HashMap};
+
+use crate::StyleProp;
+
+/// The value of a style variable, defined with [`StyleBuilder::var`](super::builder::StyleBuilder::var).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VarValue {
+    /// A color, which can be used by color properties.
+    Color(Option<Color>),
+    /// A length, which can be used by length properties, or by edge properties such as margin.
+    Length(ui::Val),
+    /// A set of edge widths, which can be used by edge properties such as margin.
+    Rect(ui::UiRect),
+    /// A number, which can be used as a length in pixels.
+    Number(f32),
+}
+
+impl From<Color> for VarValue {
+    fn from(value: Color) -> Self {
+        Self::Color(Some(value))
+    }
+}
+
+impl From<Option<Color>> for VarValue {
+    fn from(value: Option<Color>) -> Self {
+        Self::Color(value)
+    }
+}
+
+impl From<ui::Val> for VarValue {
+    fn from(value: ui::Val) -> Self {
+        Self::Length(value)
+    }
+}
+
+impl From<ui::UiRect> for VarValue {
+    fn from(value: ui::UiRect) -> Self {
+        Self::Rect(value)
+    }
+}
+
+impl From<f32> for VarValue {
+    fn from(value: f32) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<i32> for VarValue {
+    fn from(value: i32) -> Self {
+        Self::Number(value as f32)
+    }
+}
+
+/// A reference to a style variable, which can be passed to style builder methods in place of
+/// a color or a length. Created with [`var`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleVar(pub(crate) String);
+
+/// Returns a reference to a style variable, which will be resolved when styles are computed,
+/// using the nearest definition on the element itself or its ancestors:
+///
+/// ```rust,ignore
+/// StyleHandle::build(|ss| ss.background_color(var("accent")))
+/// ```
+pub fn var(name: &str) -> StyleVar {
+    StyleVar(name.to_string())
+}
+
+/// How to construct a style property from the value of a variable.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy)]
+pub enum VarProperty {
+    Color(fn(Option<Color>) -> StyleProp),
+    Length(fn(ui::Val) -> StyleProp),
+    Rect(fn(ui::UiRect) -> StyleProp),
+}
+
+impl VarProperty {
+    /// Construct the style property, or return `None` if the value has the wrong type.
+    pub(crate) fn resolve(&self, value: &VarValue) -> Option<StyleProp> {
+        match (self, *value) {
+            (VarProperty::Color(prop), VarValue::Color(color)) => Some(prop(color)),
+            (VarProperty::Length(prop), VarValue::Length(length)) => Some(prop(length)),
+            (VarProperty::Length(prop), VarValue::Number(n)) => Some(prop(ui::Val::Px(n))),
+            (VarProperty::Rect(prop), VarValue::Rect(rect)) => Some(prop(rect)),
+            (VarProperty::Rect(prop), VarValue::Length(length)) => {
+                Some(prop(ui::UiRect::all(length)))
+            }
+            (VarProperty::Rect(prop), VarValue::Number(n)) => {
+                Some(prop(ui::UiRect::all(ui::Val::Px(n))))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Component used to store the style variables which are visible to an element. Like
+/// `TextStyles`, this is only stored on elements which define variables of their own, and is
+/// used to calculate the variables inherited by child nodes.
+#[derive(Component, Default, PartialEq, Clone, Debug)]
+pub struct StyleVars(pub(crate) HashMap<String, VarValue>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let color = VarProperty::Color(StyleProp::BackgroundColor);
+        assert!(matches!(
+            color.resolve(&Color::RED.into()),
+            Some(StyleProp::BackgroundColor(Some(c))) if c == Color::RED
+        ));
+        assert!(color.resolve(&10.into()).is_none());
+
+        let margin = VarProperty::Rect(StyleProp::Margin);
+        assert!(matches!(
+            margin.resolve(&10.into()),
+            Some(StyleProp::Margin(rect)) if rect == ui::UiRect::all(ui::Val::Px(10.))
+        ));
+
+        let width = VarProperty::Length(StyleProp::Width);
+        assert!(matches!(
+            width.resolve(&ui::Val::Percent(50.).into()),
+            Some(StyleProp::Width(ui::Val::Percent(p))) if p == 50.
+        ));
+    }
+}
@@ -82,12 +82,7 @@ impl View for Bind {
     type State = Entity;
 
     fn nodes(&self, bc: &BuildContext, state: &Self::State) -> NodeSpan {
-        // get the handle from the PresenterState for this invocation.
-        let entt = bc.entity(*state);
-        let Some(handle) = entt.get::<ViewHandle>() else {
-            return NodeSpan::Empty;
-        };
-        handle.nodes()
+        presenter_nodes(bc, *state)
     }
 
     // Spawn a new presenter entity.
@@ -117,17 +112,7 @@ impl View for Bind {
     }
 
     fn raze(&self, world: &mut World, state: &mut Self::State) {
-        let mut entt = world.entity_mut(*state);
-        let Some(handle) = entt.get_mut::<ViewHandle>() else {
-            panic!("Bind::raze called without ViewHandle");
-        };
-        let inner = handle.inner.clone();
-        // Raze the contents of the child ViewState.
-        inner.lock().unwrap().raze(world, *state);
-        // Despawn the ViewHandle.
-        let mut entt = world.entity_mut(*state);
-        entt.remove_parent();
-        entt.despawn();
+        raze_presenter(world, *state);
     }
 }
 
@@ -147,3 +132,28 @@ impl PartialEq for Bind {
         self.binding.eq(&*other.binding)
     }
 }
+
+/// Returns the output nodes of a presenter invocation.
+pub(crate) fn presenter_nodes(bc: &BuildContext, state: Entity) -> NodeSpan {
+    // get the handle from the PresenterState for this invocation.
+    let entt = bc.entity(state);
+    let Some(handle) = entt.get::<ViewHandle>() else {
+        return NodeSpan::Empty;
+    };
+    handle.nodes()
+}
+
+/// Razes the view produced by a presenter invocation, and despawns the presenter entity.
+pub(crate) fn raze_presenter(world: &mut World, state: Entity) {
+    let mut entt = world.entity_mut(state);
+    let Some(handle) = entt.get_mut::<ViewHandle>() else {
+        panic!("Bind::raze called without ViewHandle");
+    };
+    let inner = handle.inner.clone();
+    // Raze the contents of the child ViewState.
+    inner.lock().unwrap().raze(world, state);
+    // Despawn the ViewHandle.
+    let mut entt = world.entity_mut(state);
+    entt.remove_parent();
+    entt.despawn();
+}
@@ -0,0 +1,314 @@
+use std::{
+    any::{Any, TypeId},
+    sync::{Arc, Mutex},
+};
+
+use bevy::ecs::world::World;
+
+use crate::node_span::NodeSpan;
+use crate::{BuildContext, View};
+
+/// Object-safe version of [`View`], which erases both the view type and its state type.
+trait AnyView: Send {
+    /// The [`TypeId`] of the concrete view type.
+    fn view_type(&self) -> TypeId;
+
+    fn any_nodes(&self, bc: &BuildContext, state: &(dyn Any + Send)) -> NodeSpan;
+
+    fn any_build(&self, bc: &mut BuildContext) -> Box<dyn Any + Send>;
+
+    fn any_update(&self, bc: &mut BuildContext, state: &mut (dyn Any + Send));
+
+    fn any_assemble(&self, bc: &mut BuildContext, state: &mut (dyn Any + Send)) -> NodeSpan;
+
+    fn any_raze(&self, world: &mut World, state: &mut (dyn Any + Send));
+}
+
+impl<V: View + 'static> AnyView for V
+where
+    V::State: 'static,
+{
+    fn view_type(&self) -> TypeId {
+        TypeId::of::<V>()
+    }
+
+    fn any_nodes(&self, bc: &BuildContext, state: &(dyn Any + Send)) -> NodeSpan {
+        View::nodes(
+            self,
+            bc,
+            state
+                .downcast_ref::<V::State>()
+                .expect("Mismatched view state"),
+        )
+    }
+
+    fn any_build(&self, bc: &mut BuildContext) -> Box<dyn Any + Send> {
+        Box::new(View::build(self, bc))
+    }
+
+    fn any_update(&self, bc: &mut BuildContext, state: &mut (dyn Any + Send)) {
+        View::update(
+            self,
+            bc,
+            state
+                .downcast_mut::<V::State>()
+                .expect("Mismatched view state"),
+        )
+    }
+
+    fn any_assemble(&self, bc: &mut BuildContext, state: &mut (dyn Any + Send)) -> NodeSpan {
+        View::assemble(
+            self,
+            bc,
+            state
+                .downcast_mut::<V::State>()
+                .expect("Mismatched view state"),
+        )
+    }
+
+    fn any_raze(&self, world: &mut World, state: &mut (dyn Any + Send)) {
+        View::raze(
+            self,
+            world,
+            state
+                .downcast_mut::<V::State>()
+                .expect("Mismatched view state"),
+        )
+    }
+}
+
+/// A type-erased [`View`]. This allows views of different types to be stored in the same
+/// collection, for example a toolbar whose items are supplied by plugins.
+///
+/// When a `BoxedView` is updated with a view of the same concrete type as the previous render,
+/// the view state is updated in place. If the concrete type is different, the old output is
+/// razed and the new view is built from scratch.
+pub struct BoxedView {
+    inner: Arc<Mutex<dyn AnyView>>,
+}
+
+impl BoxedView {
+    /// Construct a new `BoxedView` from a view. Usually it's more convenient to call
+    /// [`View::boxed()`].
+    pub fn new<V: View + 'static>(view: V) -> Self
+    where
+        V::State: 'static,
+    {
+        Self {
+            inner: Arc::new(Mutex::new(view)),
+        }
+    }
+
+    fn view_type(&self) -> TypeId {
+        self.inner.lock().unwrap().view_type()
+    }
+}
+
+/// The state for a [`BoxedView`]. Because the view type is erased, the state also retains
+/// a reference to the view that produced it, so that it can be razed later.
+pub struct BoxedViewState {
+    view: BoxedView,
+    state: Box<dyn Any + Send>,
+}
+
+impl BoxedViewState {
+    /// Return the span of UiNodes produced by the view which owns this state.
+    pub(crate) fn nodes(&self, bc: &BuildContext) -> NodeSpan {
+        self.view
+            .inner
+            .lock()
+            .unwrap()
+            .any_nodes(bc, self.state.as_ref())
+    }
+
+    /// Attach child nodes to parents, using the view which owns this state.
+    pub(crate) fn assemble(&mut self, bc: &mut BuildContext) -> NodeSpan {
+        self.view
+            .inner
+            .lock()
+            .unwrap()
+            .any_assemble(bc, self.state.as_mut())
+    }
+
+    /// Despawn the output of the view which owns this state.
+    pub(crate) fn raze(&mut self, world: &mut World) {
+        self.view
+            .inner
+            .lock()
+            .unwrap()
+            .any_raze(world, self.state.as_mut());
+    }
+}
+
+impl View for BoxedView {
+    type State = BoxedViewState;
+
+    fn nodes(&self, bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        state
+            .view
+            .inner
+            .lock()
+            .unwrap()
+            .any_nodes(bc, state.state.as_ref())
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        BoxedViewState {
+            view: self.clone(),
+            state: self.inner.lock().unwrap().any_build(bc),
+        }
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        if self.view_type() == state.view.view_type() {
+            // Same type of view, mutate state in place
+            self.inner
+                .lock()
+                .unwrap()
+                .any_update(bc, state.state.as_mut());
+            state.view = self.clone();
+        } else {
+            // Different type of view: despawn old state and construct new state
+            self.raze(bc.world, state);
+            bc.mark_changed_shape();
+            *state = self.build(bc);
+        }
+    }
+
+    fn assemble(&self, bc: &mut BuildContext, state: &mut Self::State) -> NodeSpan {
+        state.assemble(bc)
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        state.raze(world);
+    }
+}
+
+impl Clone for BoxedView {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl PartialEq for BoxedView {
+    fn eq(&self, other: &Self) -> bool {
+        // Views in general aren't comparable, so we compare pointers.
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+/// View which renders a list of boxed views. Children are matched up with the previous
+/// render by index.
+impl View for Vec<BoxedView> {
+    type State = Vec<BoxedViewState>;
+
+    fn nodes(&self, bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        let child_spans: Vec<NodeSpan> = state.iter().map(|child| child.nodes(bc)).collect();
+        NodeSpan::Fragment(child_spans.into_boxed_slice())
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        self.iter().map(|child| child.build(bc)).collect()
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        let next_len = self.len();
+        let prev_len = state.len();
+
+        // Overwrite existing items.
+        for (child, child_state) in self.iter().zip(state.iter_mut()) {
+            child.update(bc, child_state);
+        }
+
+        // Append new items
+        for child in self.iter().skip(prev_len) {
+            state.push(child.build(bc));
+        }
+
+        // Raze surplus items.
+        if prev_len > next_len {
+            for mut child_state in state.drain(next_len..) {
+                child_state.raze(bc.world);
+            }
+        }
+    }
+
+    fn assemble(&self, bc: &mut BuildContext, state: &mut Self::State) -> NodeSpan {
+        let child_spans: Vec<NodeSpan> = state.iter_mut().map(|child| child.assemble(bc)).collect();
+        NodeSpan::Fragment(child_spans.into_boxed_slice())
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        for child_state in state.iter_mut() {
+            child_state.raze(world);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+    use crate::Element;
+
+    #[test]
+    fn test_update() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        // Initial render
+        let view = "Hello".to_string().boxed();
+        let mut state = view.build(&mut bc);
+        let NodeSpan::Node(e1) = view.nodes(&bc, &state) else {
+            panic!("Expected a single node");
+        };
+
+        // Same view type, should update in place.
+        let view = "World".to_string().boxed();
+        view.update(&mut bc, &mut state);
+        assert_eq!(view.nodes(&bc, &state), NodeSpan::Node(e1));
+        assert_eq!(bc.world.get::<Text>(e1).unwrap().sections[0].value, "World");
+
+        // Different view type, should raze and rebuild.
+        let view = Element::new().boxed();
+        view.update(&mut bc, &mut state);
+        assert_ne!(view.nodes(&bc, &state), NodeSpan::Node(e1));
+        assert!(bc.world.get_entity(e1).is_none(), "Should be despawned");
+    }
+
+    #[test]
+    fn test_vec() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = vec!["One".to_string().boxed(), Element::new().boxed()];
+        let mut state = view.build(&mut bc);
+        assert_eq!(state.len(), 2);
+        assert_eq!(view.nodes(&bc, &state).count(), 2);
+
+        let view = vec![
+            "Two".to_string().boxed(),
+            Element::new().boxed(),
+            "Three".boxed(),
+        ];
+        view.update(&mut bc, &mut state);
+        assert_eq!(state.len(), 3);
+        assert_eq!(view.nodes(&bc, &state).count(), 3);
+
+        let view = vec![Element::new().boxed()];
+        view.update(&mut bc, &mut state);
+        assert_eq!(state.len(), 1);
+        assert_eq!(view.nodes(&bc, &state).count(), 1);
+    }
+}
@@ -1,9 +1,15 @@
-use super::{for_index::ForIndex, for_keyed::ForKeyed, View};
+use std::hash::Hash;
+
+use super::{for_hashed::ForHashed, for_index::ForIndex, for_keyed::ForKeyed, View};
 
 /// A namespace that contains constructor functions for various kinds of for-loops:
 /// * `For::each()`
 /// * `For::keyed()`
+/// * `For::hashed()`
 /// * `For::index()`
+///
+/// Items which are removed are razed immediately. For items which animate out, use a
+/// [`TransitionGroup`](crate::TransitionGroup).
 pub struct For;
 
 impl For {
@@ -35,13 +41,33 @@ impl For {
         items: &[Item],
         keyof: K,
         each: F,
-    ) -> impl View
+    ) -> ForKeyed<Item, Key, V, K, F>
     where
         V::State: Clone,
     {
         ForKeyed::new(items, keyof, each)
     }
 
+    /// Construct a keyed for loop whose keys are hashable. This works like `For::keyed()`, but
+    /// items are matched up using a hash map, so the cost of an update is linear in the number
+    /// of items. Prefer this for large lists which may be re-ordered or filtered.
+    pub fn hashed<
+        Item: Send + Clone,
+        Key: Send + Hash + Eq,
+        V: View,
+        K: Fn(&Item) -> Key + Send + Clone,
+        F: Fn(&Item) -> V + Send + Clone,
+    >(
+        items: &[Item],
+        keyof: K,
+        each: F,
+    ) -> ForHashed<Item, Key, V, K, F>
+    where
+        V::State: Clone,
+    {
+        ForHashed::new(items, keyof, each)
+    }
+
     /// Construct an unkeyed for loop for an array of items. The callback is called once for each
     /// array element; its argument is the item, which must be equals-comparable, and it's result
     /// is a View. During rebuild, the list of child views may be re-ordered based on a comparison
@@ -0,0 +1,219 @@
+use std::{collections::HashMap, hash::Hash, marker::PhantomData};
+
+use bevy::ecs::world::World;
+
+use crate::{
+    view::{for_keyed::KeyedListItem, move_animation::NodePositions},
+    BuildContext, Timing, Transition, TransitionProperty, View,
+};
+
+use crate::node_span::NodeSpan;
+
+/// A keyed for-loop which matches up previous and current items using a hash map, rather than
+/// by searching for common subsequences. This makes updates linear in the number of items,
+/// which is much faster than [`ForKeyed`](super::for_keyed::ForKeyed) when large lists are
+/// shuffled or filtered. Items whose key is retained keep their state and output nodes, and
+/// are simply moved to their new position.
+#[doc(hidden)]
+pub struct ForHashed<
+    Item: Send + Clone,
+    Key: Send + Hash + Eq,
+    V: View,
+    K: Fn(&Item) -> Key + Send,
+    F: Fn(&Item) -> V + Send,
+> where
+    V::State: Clone,
+{
+    items: Vec<Item>,
+    keyof: K,
+    each: F,
+    key: PhantomData<Key>,
+    moves: Option<Transition>,
+}
+
+impl<
+        Item: Send + Clone,
+        Key: Send + Hash + Eq,
+        V: View,
+        K: Fn(&Item) -> Key + Send + Clone,
+        F: Fn(&Item) -> V + Send + Clone,
+    > ForHashed<Item, Key, V, K, F>
+where
+    V::State: Clone,
+{
+    pub fn new(items: &[Item], keyof: K, each: F) -> Self {
+        Self {
+            items: Vec::from(items),
+            each,
+            keyof,
+            key: PhantomData::<Key> {},
+            moves: None,
+        }
+    }
+
+    /// Animate items which move to a new position when the list is re-ordered, or when items
+    /// are inserted or removed before them. Each moved item slides from its old position to
+    /// its new one over the given duration, in seconds.
+    pub fn animate_moves(mut self, duration: f32, timing: Timing) -> Self {
+        self.moves = Some(Transition {
+            property: TransitionProperty::Transform,
+            duration,
+            timing,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Patch the list of item states to match the current list of items.
+    fn update_items(&self, bc: &mut BuildContext, state: &mut Vec<KeyedListItem<Key, V>>) {
+        // Index the previous items by key.
+        let mut prev_items: HashMap<Key, (Option<V>, Option<V::State>)> =
+            HashMap::with_capacity(state.len());
+        for KeyedListItem { view, state, key } in state.drain(..) {
+            if let Some((Some(dup_view), Some(mut dup_state))) =
+                prev_items.insert(key, (view, state))
+            {
+                // Duplicate key, only one of them can be matched.
+                dup_view.raze(bc.world, &mut dup_state);
+            }
+        }
+
+        // Take the previous state for each retained key, or build new state.
+        for item in self.items.iter() {
+            let key = (self.keyof)(item);
+            let view = (self.each)(item);
+            let item_state = match prev_items.remove(&key) {
+                Some((_, Some(mut prev_state))) => {
+                    view.update(bc, &mut prev_state);
+                    prev_state
+                }
+                _ => view.build(bc),
+            };
+            state.push(KeyedListItem {
+                view: Some(view),
+                state: Some(item_state),
+                key,
+            });
+        }
+
+        // Raze items whose keys are gone.
+        for (_, (view, item_state)) in prev_items.drain() {
+            if let (Some(view), Some(mut item_state)) = (view, item_state) {
+                view.raze(bc.world, &mut item_state);
+            }
+        }
+    }
+}
+
+impl<
+        Item: Send + Clone,
+        Key: Send + Hash + Eq,
+        V: View,
+        K: Fn(&Item) -> Key + Send + Clone,
+        F: Fn(&Item) -> V + Send + Clone,
+    > View for ForHashed<Item, Key, V, K, F>
+where
+    V::State: Clone,
+{
+    type State = Vec<KeyedListItem<Key, V>>;
+
+    fn nodes(&self, bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        let child_spans: Vec<NodeSpan> = state.iter().map(|item| item.nodes(bc)).collect();
+        NodeSpan::Fragment(child_spans.into_boxed_slice())
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        self.items
+            .iter()
+            .map(|item| {
+                let view = (self.each)(item);
+                let state = view.build(bc);
+                KeyedListItem {
+                    view: Some(view),
+                    state: Some(state),
+                    key: (self.keyof)(item),
+                }
+            })
+            .collect()
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        let positions = self
+            .moves
+            .as_ref()
+            .map(|_| NodePositions::record(bc.world, &self.nodes(bc, state)));
+        self.update_items(bc, state);
+        if let (Some(positions), Some(transition)) = (positions, self.moves.as_ref()) {
+            let nodes = self.nodes(bc, state);
+            positions.animate(bc.world, &nodes, transition);
+        }
+    }
+
+    fn assemble(&self, bc: &mut BuildContext, state: &mut Self::State) -> NodeSpan {
+        let child_spans: Vec<NodeSpan> = state.iter_mut().map(|item| item.assemble(bc)).collect();
+        NodeSpan::Fragment(child_spans.into_boxed_slice())
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        for child_state in state {
+            if let Some(ref view) = child_state.view {
+                view.raze(world, child_state.state.as_mut().unwrap());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::world::World;
+
+    use super::*;
+
+    #[test]
+    fn test_update() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        // Initial render
+        let view = ForHashed::new(&[1, 2, 3], |item| *item, |item| format!("{}", item));
+        let mut state = view.build(&mut bc);
+        assert_eq!(state.len(), 3);
+        let e1 = state[0].state;
+        let e3 = state[2].state;
+
+        // Reverse
+        let view = ForHashed::new(&[3, 2, 1], |item| *item, |item| format!("{}", item));
+        view.update(&mut bc, &mut state);
+        assert_eq!(state.len(), 3);
+        assert_eq!(state[0].key, 3);
+        assert_eq!(state[2].key, 1);
+        assert_eq!(state[0].state, e3, "Should be same entity");
+        assert_eq!(state[2].state, e1, "Should be same entity");
+
+        // Insert, delete and move
+        let view = ForHashed::new(&[4, 1, 3], |item| *item, |item| format!("{}", item));
+        view.update(&mut bc, &mut state);
+        assert_eq!(state.len(), 3);
+        assert_eq!(state[0].key, 4);
+        assert_eq!(state[1].state, e1, "Should be same entity");
+        assert_eq!(state[2].state, e3, "Should be same entity");
+        assert_eq!(bc.world.entities().len(), 4);
+
+        // Duplicate keys
+        let view = ForHashed::new(&[1, 1], |item| *item, |item| format!("{}", item));
+        view.update(&mut bc, &mut state);
+        assert_eq!(state.len(), 2);
+        assert_eq!(state[0].state, e1, "Should be same entity");
+        assert_ne!(state[1].state, e1);
+        assert_eq!(bc.world.entities().len(), 3);
+
+        let view = ForHashed::new(&[], |item: &i32| *item, |item| format!("{}", item));
+        view.update(&mut bc, &mut state);
+        assert!(state.is_empty());
+        assert_eq!(bc.world.entities().len(), 1);
+    }
+}
@@ -2,25 +2,28 @@ use std::{marker::PhantomData, ops::Range};
 
 use bevy::ecs::world::World;
 
-use crate::{view::lcs::lcs, BuildContext, View};
+use crate::{
+    view::{lcs::lcs, move_animation::NodePositions},
+    BuildContext, Timing, Transition, TransitionProperty, View,
+};
 
 use crate::node_span::NodeSpan;
 
 pub struct KeyedListItem<Key: Send + PartialEq, V: View> {
-    view: Option<V>,
-    state: Option<V::State>,
-    key: Key,
+    pub(crate) view: Option<V>,
+    pub(crate) state: Option<V::State>,
+    pub(crate) key: Key,
 }
 
 impl<Key: Send + PartialEq, V: View> KeyedListItem<Key, V> {
-    fn nodes(&self, bc: &BuildContext) -> NodeSpan {
+    pub(crate) fn nodes(&self, bc: &BuildContext) -> NodeSpan {
         self.view
             .as_ref()
             .unwrap()
             .nodes(bc, self.state.as_ref().unwrap())
     }
 
-    fn assemble(&mut self, bc: &mut BuildContext) -> NodeSpan {
+    pub(crate) fn assemble(&mut self, bc: &mut BuildContext) -> NodeSpan {
         self.view
             .as_ref()
             .unwrap()
@@ -43,6 +46,7 @@ pub struct ForKeyed<
     keyof: K,
     each: F,
     key: PhantomData<Key>,
+    moves: Option<Transition>,
 }
 
 #[allow(clippy::needless_range_loop)]
@@ -62,9 +66,47 @@ where
             each,
             keyof,
             key: PhantomData::<Key> {},
+            moves: None,
         }
     }
 
+    /// Animate items which move to a new position when the list is re-ordered, or when items
+    /// are inserted or removed before them. Each moved item slides from its old position to
+    /// its new one over the given duration, in seconds.
+    pub fn animate_moves(mut self, duration: f32, timing: Timing) -> Self {
+        self.moves = Some(Transition {
+            property: TransitionProperty::Transform,
+            duration,
+            timing,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Patch the list of item states to match the current list of items.
+    fn update_items(&self, bc: &mut BuildContext, state: &mut Vec<KeyedListItem<Key, V>>) {
+        let next_len = self.items.len();
+        let mut next_state: Vec<KeyedListItem<Key, V>> = Vec::with_capacity(next_len);
+        let prev_len = state.len();
+
+        // Initialize output state array; fill in keys.
+        for j in 0..next_len {
+            next_state.push({
+                KeyedListItem {
+                    view: None,
+                    state: None,
+                    key: (self.keyof)(&self.items[j]),
+                }
+            });
+        }
+
+        self.build_recursive(bc, state, 0..prev_len, &mut next_state, 0..next_len);
+        for j in 0..next_len {
+            assert!(next_state[j].state.is_some(), "Empty state: {}", j);
+        }
+        std::mem::swap(state, &mut next_state);
+    }
+
     /// Uses the sequence of key values to match the previous array items with the updated
     /// array items. Matching items are patched, other items are inserted or deleted.
     ///
@@ -228,26 +270,15 @@ where
     }
 
     fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
-        let next_len = self.items.len();
-        let mut next_state: Self::State = Vec::with_capacity(next_len);
-        let prev_len = state.len();
-
-        // Initialize output state array; fill in keys.
-        for j in 0..next_len {
-            next_state.push({
-                KeyedListItem {
-                    view: None,
-                    state: None,
-                    key: (self.keyof)(&self.items[j]),
-                }
-            });
-        }
-
-        self.build_recursive(bc, state, 0..prev_len, &mut next_state, 0..next_len);
-        for j in 0..next_len {
-            assert!(next_state[j].state.is_some(), "Empty state: {}", j);
+        let positions = self
+            .moves
+            .as_ref()
+            .map(|_| NodePositions::record(bc.world, &self.nodes(bc, state)));
+        self.update_items(bc, state);
+        if let (Some(positions), Some(transition)) = (positions, self.moves.as_ref()) {
+            let nodes = self.nodes(bc, state);
+            positions.animate(bc.world, &nodes, transition);
         }
-        std::mem::swap(state, &mut next_state);
     }
 
     fn assemble(&self, bc: &mut BuildContext, state: &mut Self::State) -> NodeSpan {
@@ -280,6 +311,7 @@ where
             keyof: self.keyof.clone(),
             each: self.each.clone(),
             key: self.key,
+            moves: self.moves.clone(),
         }
     }
 }
@@ -13,6 +13,8 @@ pub enum IfState<Pos, Neg> {
 }
 
 /// A conditional view which renders one of two children depending on the condition expression.
+/// The branch which is no longer rendered is razed immediately; to animate a child out, use a
+/// [`TransitionGroup`](crate::TransitionGroup) of zero or one items instead.
 pub struct If<Pos: View, Neg: View> {
     test: bool,
     pos: Pos,
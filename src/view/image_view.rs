@@ -0,0 +1,413 @@
+use bevy::{
+    asset::AssetPath,
+    prelude::*,
+    sprite::{BorderRect, ImageScaleMode, TextureSlicer},
+    ui::widget::UiImageSize,
+};
+
+use crate::{style::load_image, BuildContext, View};
+
+use crate::node_span::NodeSpan;
+
+/// How an [`ImageView`] sizes its image within the bounds of the view, similar to the CSS
+/// `object-fit` property.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ImageFit {
+    /// Stretch the image to fill the view, ignoring the aspect ratio.
+    #[default]
+    Fill,
+    /// Scale the image to fit within the view, preserving the aspect ratio.
+    Contain,
+    /// Scale the image to cover the view, preserving the aspect ratio. The parts of the image
+    /// which overflow the view are clipped.
+    Cover,
+    /// Draw the image at its natural size, centered and clipped to the view.
+    None,
+}
+
+impl ImageFit {
+    /// Calculate the size of the image, given the size of the view and of the image itself.
+    pub fn fit(&self, bounds: Vec2, image: Vec2) -> Vec2 {
+        if image.x <= 0. || image.y <= 0. {
+            return bounds;
+        }
+        let scale = bounds / image;
+        match self {
+            ImageFit::Fill => bounds,
+            ImageFit::Contain => image * scale.x.min(scale.y),
+            ImageFit::Cover => image * scale.x.max(scale.y),
+            ImageFit::None => image,
+        }
+    }
+}
+
+/// The image displayed by an [`ImageView`]: either an asset path, which is loaded the same way
+/// as style background images, or a handle to an image which has already been loaded.
+#[derive(Clone, PartialEq)]
+pub enum ImageSource {
+    /// Asset path of the image.
+    Path(AssetPath<'static>),
+    /// Handle to an image asset.
+    Handle(Handle<Image>),
+}
+
+impl ImageSource {
+    fn handle(&self, world: &World) -> Handle<Image> {
+        match self {
+            ImageSource::Path(path) => load_image(world.resource::<AssetServer>(), path),
+            ImageSource::Handle(handle) => handle.clone(),
+        }
+    }
+}
+
+impl From<&str> for ImageSource {
+    fn from(path: &str) -> Self {
+        ImageSource::Path(AssetPath::parse(path).into_owned())
+    }
+}
+
+impl From<String> for ImageSource {
+    fn from(path: String) -> Self {
+        ImageSource::Path(AssetPath::from(path))
+    }
+}
+
+impl From<AssetPath<'static>> for ImageSource {
+    fn from(path: AssetPath<'static>) -> Self {
+        ImageSource::Path(path)
+    }
+}
+
+impl From<Handle<Image>> for ImageSource {
+    fn from(handle: Handle<Image>) -> Self {
+        ImageSource::Handle(handle)
+    }
+}
+
+/// Component on the inner image node of an [`ImageView`], used by [`update_image_fit`] to
+/// size the image relative to the view's outer node.
+#[derive(Component, Clone, Copy, PartialEq)]
+#[doc(hidden)]
+pub struct FittedImage(pub(crate) ImageFit);
+
+/// Component on the inner image node of an [`ImageView`] which holds the tint color. The node's
+/// [`BackgroundColor`] is the tint faded by the opacity inherited from the view's ancestors.
+#[derive(Component, Clone, Copy, PartialEq)]
+#[doc(hidden)]
+pub struct ImageTint(pub(crate) Color);
+
+/// A View which displays an image. The view consists of an outer node, which can be styled
+/// like any other element, and an inner image node which is sized according to the
+/// [`ImageFit`] mode.
+#[derive(Clone, PartialEq)]
+pub struct ImageView {
+    source: ImageSource,
+    fit: ImageFit,
+    tint: Color,
+    flip_x: bool,
+    flip_y: bool,
+    atlas: Option<(Handle<TextureAtlasLayout>, usize)>,
+    nine_slice: Option<BorderRect>,
+}
+
+impl ImageView {
+    /// Construct a new `ImageView` from an asset path or image handle.
+    pub fn new(source: impl Into<ImageSource>) -> Self {
+        Self {
+            source: source.into(),
+            fit: ImageFit::default(),
+            tint: Color::WHITE,
+            flip_x: false,
+            flip_y: false,
+            atlas: None,
+            nine_slice: None,
+        }
+    }
+
+    /// Set how the image is sized within the view.
+    pub fn fit(mut self, fit: ImageFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    /// Set the color which the image is multiplied by. Defaults to white.
+    pub fn tint(mut self, tint: Color) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    /// Flip the image horizontally.
+    pub fn flip_x(mut self, flip: bool) -> Self {
+        self.flip_x = flip;
+        self
+    }
+
+    /// Flip the image vertically.
+    pub fn flip_y(mut self, flip: bool) -> Self {
+        self.flip_y = flip;
+        self
+    }
+
+    /// Display a single region of a texture atlas, rather than the whole image.
+    pub fn atlas(mut self, layout: Handle<TextureAtlasLayout>, index: usize) -> Self {
+        self.atlas = Some((layout, index));
+        self
+    }
+
+    /// Draw the image as a nine-slice: the corners are drawn at their natural size, while the
+    /// edges and center stretch to fill the view. The insets are in image pixels. This always
+    /// fills the view, and can't be combined with a texture atlas.
+    pub fn nine_slice(mut self, top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        self.nine_slice = Some(BorderRect {
+            left,
+            right,
+            top,
+            bottom,
+        });
+        self
+    }
+
+    fn image_style(&self) -> Style {
+        Style {
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_shrink: 0.,
+            ..default()
+        }
+    }
+
+    fn fitted(&self) -> Option<FittedImage> {
+        match (self.fit, self.nine_slice) {
+            (ImageFit::Fill, _) | (_, Some(_)) => None,
+            (fit, None) => Some(FittedImage(fit)),
+        }
+    }
+
+    fn scale_mode(&self) -> Option<ImageScaleMode> {
+        self.nine_slice.map(|border| {
+            ImageScaleMode::Sliced(TextureSlicer {
+                border,
+                center_scale_mode: default(),
+                sides_scale_mode: default(),
+                max_corner_scale: 1.,
+            })
+        })
+    }
+
+    fn texture_atlas(&self) -> Option<TextureAtlas> {
+        match (&self.atlas, self.nine_slice) {
+            (Some((layout, index)), None) => Some(TextureAtlas {
+                layout: layout.clone(),
+                index: *index,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl View for ImageView {
+    /// The outer and inner nodes, and the source of the image which is displayed.
+    type State = (Entity, Entity, ImageSource);
+
+    fn nodes(&self, _bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        NodeSpan::Node(state.0)
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        let texture = self.source.handle(bc.world);
+        let mut image = bc.world.spawn(ImageBundle {
+            style: self.image_style(),
+            image: UiImage {
+                texture,
+                flip_x: self.flip_x,
+                flip_y: self.flip_y,
+            },
+            background_color: self.tint.into(),
+            ..default()
+        });
+        image.insert(ImageTint(self.tint));
+        if let Some(fitted) = self.fitted() {
+            image.insert(fitted);
+        }
+        if let Some(atlas) = self.texture_atlas() {
+            image.insert(atlas);
+        }
+        if let Some(scale_mode) = self.scale_mode() {
+            image.insert(scale_mode);
+        }
+        let image = image.id();
+
+        let outer = bc
+            .world
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    visibility: Visibility::Visible,
+                    ..default()
+                },
+                Name::new("image"),
+            ))
+            .id();
+        bc.world.entity_mut(outer).add_child(image);
+        (outer, image, self.source.clone())
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        // Only resolve the source when it changes, rather than going through the asset server
+        // on every update.
+        let texture = (state.2 != self.source).then(|| self.source.handle(bc.world));
+        let mut image = bc.entity_mut(state.1);
+
+        let mut ui_image = image.get_mut::<UiImage>().unwrap();
+        if let Some(texture) = texture {
+            ui_image.texture = texture;
+            state.2.clone_from(&self.source);
+        }
+        if ui_image.flip_x != self.flip_x {
+            ui_image.flip_x = self.flip_x;
+        }
+        if ui_image.flip_y != self.flip_y {
+            ui_image.flip_y = self.flip_y;
+        }
+
+        // The background color is faded when styles are next updated.
+        let mut tint = image.get_mut::<ImageTint>().unwrap();
+        if tint.0 != self.tint {
+            tint.0 = self.tint;
+            image.insert(BackgroundColor(self.tint));
+        }
+
+        let fitted = self.fitted();
+        if image.get::<FittedImage>() != fitted.as_ref() {
+            match fitted {
+                Some(fitted) => {
+                    image.insert(fitted);
+                }
+                None => {
+                    image.remove::<FittedImage>();
+                    image.insert(self.image_style());
+                }
+            }
+        }
+
+        match (self.texture_atlas(), image.get_mut::<TextureAtlas>()) {
+            (Some(atlas), Some(mut prev)) => {
+                if prev.layout != atlas.layout || prev.index != atlas.index {
+                    *prev = atlas;
+                }
+            }
+            (Some(atlas), None) => {
+                image.insert(atlas);
+            }
+            (None, Some(_)) => {
+                image.remove::<TextureAtlas>();
+            }
+            (None, None) => {}
+        }
+
+        let border = match image.get::<ImageScaleMode>() {
+            Some(ImageScaleMode::Sliced(slicer)) => Some(slicer.border),
+            _ => None,
+        };
+        if border != self.nine_slice {
+            match self.scale_mode() {
+                Some(scale_mode) => {
+                    image.insert(scale_mode);
+                }
+                None => {
+                    image.remove::<ImageScaleMode>();
+                }
+            }
+        }
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        let mut entt = world.entity_mut(state.0);
+        entt.remove_parent();
+        entt.despawn_recursive();
+    }
+}
+
+/// System which sizes the inner node of each [`ImageView`] according to its fit mode, based on
+/// the size of the view's outer node from the previous layout.
+pub(crate) fn update_image_fit(
+    mut query: Query<(&FittedImage, &UiImageSize, &Parent, &mut Style)>,
+    query_nodes: Query<&Node>,
+) {
+    for (fitted, image_size, parent, mut style) in query.iter_mut() {
+        let Ok(bounds) = query_nodes.get(parent.get()) else {
+            continue;
+        };
+        let size = fitted.0.fit(bounds.size(), image_size.size());
+        if style.width != Val::Px(size.x) {
+            style.width = Val::Px(size.x);
+        }
+        if style.height != Val::Px(size.y) {
+            style.height = Val::Px(size.y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::world::World;
+
+    use super::*;
+
+    #[test]
+    fn test_fit() {
+        let bounds = Vec2::new(100., 50.);
+        let image = Vec2::new(20., 20.);
+        assert_eq!(ImageFit::Fill.fit(bounds, image), bounds);
+        assert_eq!(ImageFit::Contain.fit(bounds, image), Vec2::new(50., 50.));
+        assert_eq!(ImageFit::Cover.fit(bounds, image), Vec2::new(100., 100.));
+        assert_eq!(ImageFit::None.fit(bounds, image), image);
+        // Image size isn't known until it has loaded.
+        assert_eq!(ImageFit::Contain.fit(bounds, Vec2::ZERO), bounds);
+    }
+
+    #[test]
+    fn test_update() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = ImageView::new(Handle::<Image>::default()).fit(ImageFit::Contain);
+        let mut state = view.build(&mut bc);
+        let image = bc.world.entity(state.1);
+        assert!(image.contains::<FittedImage>());
+        assert!(!image.contains::<ImageScaleMode>());
+
+        // Nine-slice images always fill the view.
+        let view = ImageView::new(Handle::<Image>::default())
+            .fit(ImageFit::Contain)
+            .tint(Color::RED)
+            .flip_x(true)
+            .nine_slice(4., 4., 4., 4.);
+        view.update(&mut bc, &mut state);
+        let image = bc.world.entity(state.1);
+        assert!(!image.contains::<FittedImage>());
+        assert!(image.contains::<ImageScaleMode>());
+        assert!(image.get::<UiImage>().unwrap().flip_x);
+        assert_eq!(image.get::<BackgroundColor>().unwrap().0, Color::RED);
+
+        // Changing the source replaces the image.
+        let handle = Handle::<Image>::weak_from_u128(1);
+        let view = ImageView::new(handle.clone());
+        view.update(&mut bc, &mut state);
+        let image = bc.world.entity(state.1);
+        assert_eq!(image.get::<UiImage>().unwrap().texture, handle);
+        assert!(state.2 == ImageSource::Handle(handle));
+
+        view.raze(bc.world, &mut state);
+        assert_eq!(bc.world.entities().len(), 1);
+    }
+}
@@ -1,23 +1,34 @@
 mod atom;
 mod bind;
+mod boxed_view;
 mod cx;
 mod element;
 mod r#for;
+mod for_hashed;
 mod for_index;
 mod for_keyed;
 mod fragment;
 mod r#if;
+mod image_view;
 mod lcs;
+mod move_animation;
 mod portal;
+mod presented;
 pub(crate) mod presenter_state;
 mod ref_element;
+pub(crate) mod rich_text;
 mod scoped_values;
+pub(crate) mod show;
+mod switch;
+mod text_binding;
 pub(crate) mod tracked_resources;
 pub(crate) mod tracking;
+mod transition_group;
 #[allow(clippy::module_inception)]
 pub(crate) mod view;
 mod view_children;
 mod view_classes;
+pub(crate) mod view_hidden;
 mod view_insert_bundle;
 mod view_named;
 mod view_param;
@@ -25,23 +36,41 @@ mod view_styled;
 mod view_tuple;
 mod view_with;
 mod view_with_memo;
+mod virtual_list;
 
 pub use atom::*;
 pub use bind::Bind;
+pub use boxed_view::{BoxedView, BoxedViewState};
 pub use cx::Cx;
 pub use element::Element;
+pub use for_hashed::ForHashed;
 pub use for_index::ForIndex;
 pub use for_keyed::ForKeyed;
 pub use fragment::Fragment;
+pub(crate) use image_view::update_image_fit;
+pub use image_view::{FittedImage, ImageFit, ImageSource, ImageTint, ImageView};
+pub use move_animation::{animate_moves, AnimatedMove};
 pub use portal::Portal;
+pub use presented::{Presented, PresenterFor, Props};
 pub use presenter_state::ViewHandle;
 pub use r#for::For;
 pub use r#if::If;
 pub use ref_element::RefElement;
+pub use rich_text::{RichText, RichTextSpans, Span, TextSpan};
 pub use scoped_values::ScopedValueKey;
+pub use show::{Show, ViewScope};
+pub use switch::Switch;
+pub(crate) use text_binding::update_text_bindings;
+pub use text_binding::{BindText, BoundText, TextBinding};
 pub(crate) use tracking::TrackingContext;
+pub(crate) use transition_group::update_transition_groups;
+pub use transition_group::{
+    TransitionGroup, TransitionGroupItem, TransitionGroupItemState, TransitionPhase,
+};
 pub use view::PresenterFn;
 pub use view::View;
 pub use view::*;
 pub use view_param::ViewParam;
 pub use view_tuple::ViewTuple;
+pub(crate) use virtual_list::update_virtual_lists;
+pub use virtual_list::{RowHeight, VirtualList, VirtualListLayout, VirtualRow};
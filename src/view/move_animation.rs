@@ -0,0 +1,152 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    node_span::NodeSpan,
+    style::{Transition, TransitionState},
+};
+
+/// Component which animates a display node from its previous position to its new layout
+/// position, after it has been moved by a keyed list. The offset is applied to the node's
+/// transform after layout, so it does not affect the position of other nodes.
+#[derive(Component)]
+#[doc(hidden)]
+pub struct AnimatedMove {
+    state: TransitionState,
+    /// Position of the node, relative to its parent, before it was moved.
+    origin: Vec2,
+    /// Initial offset from the new layout position. This isn't known until layout has run.
+    offset: Option<Vec2>,
+}
+
+/// The positions of a list's display nodes before an update.
+pub(crate) struct NodePositions {
+    order: Vec<Entity>,
+    positions: HashMap<Entity, Vec2>,
+}
+
+impl NodePositions {
+    /// Record the current positions of the top-level nodes in a span.
+    pub(crate) fn record(world: &World, nodes: &NodeSpan) -> Self {
+        let mut order: Vec<Entity> = Vec::new();
+        nodes.flatten(&mut order);
+        let positions = order
+            .iter()
+            .filter_map(|entity| {
+                world
+                    .get::<Transform>(*entity)
+                    .map(|transform| (*entity, transform.translation.truncate()))
+            })
+            .collect();
+        Self { order, positions }
+    }
+
+    /// Start move animations for all of the previously recorded nodes which are still
+    /// present in `nodes`. Nothing is animated if the order of nodes did not change.
+    pub(crate) fn animate(self, world: &mut World, nodes: &NodeSpan, transition: &Transition) {
+        let mut order: Vec<Entity> = Vec::new();
+        nodes.flatten(&mut order);
+        if order == self.order {
+            return;
+        }
+        for entity in order {
+            if let Some(origin) = self.positions.get(&entity) {
+                world.entity_mut(entity).insert(AnimatedMove {
+                    state: TransitionState {
+                        transition: transition.clone(),
+                        clock: 0.,
+                    },
+                    origin: *origin,
+                    offset: None,
+                });
+            }
+        }
+    }
+}
+
+/// System which animates moved nodes. This must run after UI layout, but before transforms
+/// are propagated.
+#[doc(hidden)]
+pub fn animate_moves(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &mut AnimatedMove)>,
+    time: Res<Time>,
+) {
+    for (e, mut transform, mut anim) in query.iter_mut() {
+        let offset = match anim.offset {
+            Some(offset) => {
+                anim.state.advance(time.delta_seconds());
+                offset
+            }
+            None => {
+                // First frame after the move: layout has calculated the new position.
+                let offset = anim.origin - transform.translation.truncate();
+                anim.offset = Some(offset);
+                offset
+            }
+        };
+        if offset == Vec2::ZERO || anim.state.clock >= 1. {
+            commands.entity(e).remove::<AnimatedMove>();
+            continue;
+        }
+        // Layout resets the translation each frame, so the offset isn't cumulative.
+        transform.translation += (offset * (1. - anim.state.t())).extend(0.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::{timing, BuildContext, For, View};
+
+    fn set_y(world: &mut World, entity: Entity, y: f32) {
+        world.get_mut::<Transform>(entity).unwrap().translation.y = y;
+    }
+
+    #[test]
+    fn test_animate_moves() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let list = |items: &[i32]| {
+            For::hashed(items, |item| *item, |item| format!("{}", item))
+                .animate_moves(0.5, timing::LINEAR)
+        };
+
+        let view = list(&[1, 2, 3]);
+        let mut state = view.build(&mut bc);
+        let mut nodes: Vec<Entity> = Vec::new();
+        view.nodes(&bc, &state).flatten(&mut nodes);
+        for (index, node) in nodes.iter().enumerate() {
+            set_y(bc.world, *node, index as f32 * 10.);
+        }
+
+        // Same order, nothing to animate.
+        let view = list(&[1, 2, 3]);
+        view.update(&mut bc, &mut state);
+        assert!(nodes
+            .iter()
+            .all(|node| !bc.world.entity(*node).contains::<AnimatedMove>()));
+
+        // Reverse the list, then simulate layout.
+        let view = list(&[3, 2, 1]);
+        view.update(&mut bc, &mut state);
+        set_y(bc.world, nodes[0], 20.);
+        set_y(bc.world, nodes[2], 0.);
+        bc.world.run_system_once(animate_moves);
+
+        // Moved nodes are drawn at their old position; the node which didn't move is done.
+        let y =
+            |world: &World, entity: Entity| world.get::<Transform>(entity).unwrap().translation.y;
+        assert_eq!(y(bc.world, nodes[0]), 0.);
+        assert_eq!(y(bc.world, nodes[2]), 20.);
+        assert!(bc.world.entity(nodes[0]).contains::<AnimatedMove>());
+        assert!(!bc.world.entity(nodes[1]).contains::<AnimatedMove>());
+    }
+}
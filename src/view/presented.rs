@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use crate::{Bind, BuildContext, View};
+
+use crate::node_span::NodeSpan;
+
+use super::bind::{presenter_nodes, raze_presenter};
+
+/// A presenter which can be invoked with props of type `P`. This is implemented by the
+/// `#[presenter]` attribute for a marker type, rather than for the presenter function itself,
+/// so that the props can change type (for example when setting `children`) while they are
+/// being built.
+pub trait PresenterFor<P>: Copy + Send + 'static {
+    /// Bind the presenter to a set of props.
+    fn bind(self, props: P) -> Bind;
+}
+
+/// A set of presenter properties which can be built incrementally. This is implemented by
+/// `#[derive(Props)]`.
+pub trait Props: Sized {
+    /// The builder type, which holds both the props and the presenter they will be passed to.
+    type Builder<M>;
+
+    /// Create a builder with default props, which will invoke the given presenter.
+    fn builder<M>(presenter: M) -> Self::Builder<M>;
+}
+
+/// A View which invokes a presenter with a set of props. This is used by the builders which
+/// are generated by `#[derive(Props)]`.
+#[doc(hidden)]
+#[derive(Clone, PartialEq)]
+pub struct Presented<M, P> {
+    pub presenter: M,
+    pub props: P,
+}
+
+impl<M: PresenterFor<P>, P: Clone + Send> View for Presented<M, P> {
+    // State holds the PresenterState entity.
+    type State = Entity;
+
+    fn nodes(&self, bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        presenter_nodes(bc, *state)
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        self.presenter.bind(self.props.clone()).build(bc)
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        self.presenter.bind(self.props.clone()).update(bc, state)
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        raze_presenter(world, *state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use bevy::ecs::world::World;
+
+    use super::*;
+    use crate::{presenter, Cx, ViewHandle};
+
+    #[derive(crate::Props)]
+    struct LabelProps<V: View + Clone + PartialEq = ()> {
+        id: &'static str,
+        children: V,
+        #[props(default = 1.)]
+        scale: f32,
+        #[props(ptr_eq)]
+        on_click: Option<Arc<dyn Fn() + Send + Sync>>,
+    }
+
+    #[presenter]
+    fn label<V: View + Clone + PartialEq + 'static>(cx: Cx<LabelProps<V>>) -> impl View {
+        cx.props.children.clone()
+    }
+
+    #[test]
+    fn test_props() {
+        let props = LabelProps::new();
+        assert_eq!(props.scale, 1.);
+
+        // Setting children changes the props type.
+        let props: LabelProps<&str> = props.id("ok").children("OK");
+        assert_eq!(props.id, "ok");
+        assert_eq!(props.children, "OK");
+        assert!(props == props.clone());
+
+        // Closures are compared by address.
+        let callback: Arc<dyn Fn() + Send + Sync> = Arc::new(|| {});
+        let a = LabelProps::new().on_click(Some(callback.clone()));
+        let b = LabelProps::new().on_click(Some(callback));
+        let c = LabelProps::new().on_click(Some(Arc::new(|| {})));
+        assert!(a == b);
+        assert!(a != c);
+    }
+
+    #[test]
+    fn test_presenter() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = label().id("ok").scale(2.).children("OK");
+        assert!(view == label().id("ok").scale(2.).children("OK"));
+        let mut state = view.build(&mut bc);
+        assert!(bc.world.entity(state).contains::<ViewHandle>());
+        view.update(&mut bc, &mut state);
+        view.raze(bc.world, &mut state);
+        assert_eq!(bc.world.entities().len(), 1);
+    }
+}
@@ -228,3 +228,9 @@ pub struct PresenterStateChanged;
 /// rebuilt.
 #[derive(Component)]
 pub struct PresenterGraphChanged;
+
+/// Marker component for presenters which needed to be rebuilt, but were skipped because they are
+/// inside a suspended [`Show`](crate::Show). They will be rebuilt when the `Show` becomes
+/// visible again.
+#[derive(Component)]
+pub struct PresenterSuspended;
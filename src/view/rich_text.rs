@@ -0,0 +1,278 @@
+use bevy::{ecs::system::Command, prelude::*};
+
+use crate::{BuildContext, View, ViewTuple};
+
+use crate::node_span::NodeSpan;
+
+/// Component which holds the content and computed style of a single [`Span`]. Span entities
+/// are not UI nodes; their content is copied into a section of the owning [`RichText`] node.
+#[derive(Component, Default)]
+#[doc(hidden)]
+pub struct TextSpan {
+    /// The text of this span.
+    pub(crate) value: String,
+
+    /// The computed text style for this span, including inherited properties.
+    pub(crate) style: TextStyle,
+
+    /// The text node which this span is rendered into.
+    pub(crate) owner: Option<Entity>,
+}
+
+/// Component on a [`RichText`] node which records the span entities for each text section,
+/// in order.
+#[derive(Component, Default, PartialEq)]
+#[doc(hidden)]
+pub struct RichTextSpans(pub(crate) Vec<Entity>);
+
+/// A View which renders a run of text within a [`RichText`] view. Spans can be styled and
+/// given class names like any other view, but only the text properties (font, font size and
+/// color) are used. Style selectors for a span can only match the span's own class names.
+#[derive(Clone, PartialEq)]
+pub struct Span {
+    text: String,
+}
+
+impl Span {
+    /// Construct a new text span.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into() }
+    }
+}
+
+impl View for Span {
+    type State = Entity;
+
+    fn nodes(&self, _bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        NodeSpan::Node(*state)
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        bc.world
+            .spawn(TextSpan {
+                value: self.text.clone(),
+                ..default()
+            })
+            .id()
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        let mut entt = bc.entity_mut(*state);
+        let changed = entt
+            .get::<TextSpan>()
+            .is_some_and(|span| span.value != self.text);
+        if changed {
+            entt.get_mut::<TextSpan>()
+                .unwrap()
+                .value
+                .clone_from(&self.text);
+        }
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        world.entity_mut(*state).despawn();
+    }
+}
+
+/// A View which renders a single text node whose sections are defined by a list of [`Span`]
+/// child views. Unlike placing several text views in a row, the spans are laid out as a
+/// single paragraph, so mixed formatting wraps correctly. Child views which don't produce
+/// spans are ignored.
+///
+/// Only the sections whose span has changed are updated. Text properties which are not set
+/// on a span are inherited from the `RichText` node.
+pub struct RichText<A: ViewTuple> {
+    spans: A,
+}
+
+impl<A: ViewTuple> RichText<A> {
+    /// Construct a new `RichText` from a tuple of spans.
+    pub fn new(spans: A) -> Self {
+        Self { spans }
+    }
+}
+
+impl<A: ViewTuple> View for RichText<A> {
+    type State = (Entity, A::State);
+
+    fn nodes(&self, _bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        NodeSpan::Node(state.0)
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        let entity = bc
+            .world
+            .spawn((
+                TextBundle {
+                    text: Text::from_sections([]),
+                    ..default()
+                },
+                RichTextSpans::default(),
+            ))
+            .id();
+        (entity, self.spans.build_spans(bc))
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        self.spans.update_spans(bc, &mut state.1);
+    }
+
+    fn assemble(&self, bc: &mut BuildContext, state: &mut Self::State) -> NodeSpan {
+        let entity = state.0;
+        let mut flat: Vec<Entity> = Vec::new();
+        self.spans
+            .assemble_spans(bc, &mut state.1)
+            .flatten(&mut flat);
+        flat.retain(|span| bc.world.get::<TextSpan>(*span).is_some());
+
+        for span in flat.iter() {
+            let mut text_span = bc.world.get_mut::<TextSpan>(*span).unwrap();
+            if text_span.owner != Some(entity) {
+                text_span.owner = Some(entity);
+            }
+        }
+
+        let mut entt = bc.entity_mut(entity);
+        if entt.get::<RichTextSpans>().map(|spans| &spans.0) != Some(&flat) {
+            entt.insert(RichTextSpans(flat.clone()));
+        }
+
+        // Find the sections which differ from their span.
+        let text = bc.world.get::<Text>(entity).unwrap();
+        let mut changed: Vec<usize> = Vec::new();
+        for (index, span) in flat.iter().enumerate() {
+            let text_span = bc.world.get::<TextSpan>(*span).unwrap();
+            match text.sections.get(index) {
+                Some(section)
+                    if section.value == text_span.value
+                        && text_style_eq(&section.style, &text_span.style) => {}
+                _ => changed.push(index),
+            }
+        }
+        let truncate = text.sections.len() > flat.len();
+
+        if !changed.is_empty() || truncate {
+            let sections: Vec<TextSection> = changed
+                .iter()
+                .map(|index| {
+                    let text_span = bc.world.get::<TextSpan>(flat[*index]).unwrap();
+                    TextSection {
+                        value: text_span.value.clone(),
+                        style: text_span.style.clone(),
+                    }
+                })
+                .collect();
+            let mut text = bc.world.get_mut::<Text>(entity).unwrap();
+            text.sections.truncate(flat.len());
+            for (index, section) in changed.into_iter().zip(sections) {
+                if index < text.sections.len() {
+                    text.sections[index] = section;
+                } else {
+                    text.sections.push(section);
+                }
+            }
+        }
+
+        NodeSpan::Node(entity)
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        self.spans.raze_spans(world, &mut state.1);
+        let mut entt = world.entity_mut(state.0);
+        entt.remove_parent();
+        entt.despawn();
+    }
+}
+
+/// Compare two text styles. `TextStyle` does not implement `PartialEq`.
+pub(crate) fn text_style_eq(a: &TextStyle, b: &TextStyle) -> bool {
+    a.font == b.font && a.font_size == b.font_size && a.color == b.color
+}
+
+/// Custom command that updates the computed style of a span, and the text section it is
+/// rendered into.
+pub(crate) struct UpdateSpanStyle {
+    pub(crate) span: Entity,
+    pub(crate) style: TextStyle,
+}
+
+impl Command for UpdateSpanStyle {
+    fn apply(self, world: &mut World) {
+        let Some(mut text_span) = world.get_mut::<TextSpan>(self.span) else {
+            return;
+        };
+        text_span.style = self.style.clone();
+        let Some(owner) = text_span.owner else {
+            return;
+        };
+        let Some(index) = world
+            .get::<RichTextSpans>(owner)
+            .and_then(|spans| spans.0.iter().position(|span| *span == self.span))
+        else {
+            return;
+        };
+        if let Some(mut text) = world.get_mut::<Text>(owner) {
+            if let Some(section) = text.sections.get_mut(index) {
+                section.style = self.style;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::world::World;
+
+    use super::*;
+
+    fn section_values(world: &World, entity: Entity) -> Vec<String> {
+        world
+            .get::<Text>(entity)
+            .unwrap()
+            .sections
+            .iter()
+            .map(|section| section.value.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_rich_text() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = RichText::new((Span::new("Damage: "), Span::new("42")));
+        let mut state = view.build(&mut bc);
+        view.assemble(&mut bc, &mut state);
+        let text = state.0;
+        assert_eq!(section_values(bc.world, text), vec!["Damage: ", "42"]);
+
+        // Only the changed section is replaced.
+        let view = RichText::new((Span::new("Damage: "), Span::new("47")));
+        view.update(&mut bc, &mut state);
+        view.assemble(&mut bc, &mut state);
+        assert_eq!(section_values(bc.world, text), vec!["Damage: ", "47"]);
+
+        // Styles are written to the span's section.
+        let span = state.1 .1;
+        let style = TextStyle {
+            font_size: 10.,
+            color: Color::RED,
+            ..default()
+        };
+        UpdateSpanStyle {
+            span,
+            style: style.clone(),
+        }
+        .apply(bc.world);
+        let sections = &bc.world.get::<Text>(text).unwrap().sections;
+        assert!(text_style_eq(&sections[1].style, &style));
+        assert!(text_style_eq(&sections[0].style, &TextStyle::default()));
+
+        view.raze(bc.world, &mut state);
+        assert_eq!(bc.world.entities().len(), 1);
+    }
+}
@@ -0,0 +1,228 @@
+use bevy::prelude::*;
+
+use crate::{
+    presenter_state::{PresenterStateChanged, PresenterSuspended},
+    view::view_hidden::set_view_hidden,
+    BuildContext, View,
+};
+
+use crate::node_span::NodeSpan;
+
+/// Component for the entity which parents the presenters created inside a [`Show`]. This
+/// lets us find the presenters that belong to a hidden subtree.
+#[derive(Component)]
+#[doc(hidden)]
+pub struct ViewScope {
+    /// Whether presenters within this scope should skip re-rendering.
+    pub(crate) suspended: bool,
+}
+
+/// State for a [`Show`] view.
+pub struct ShowState<S> {
+    /// Entity which parents the presenters created by the child view.
+    scope: Entity,
+
+    /// Whether the child is currently hidden.
+    hidden: bool,
+
+    /// State of the child view.
+    state: S,
+}
+
+/// A conditional view which, unlike [`If`](crate::If), keeps its child alive when the condition
+/// is false. The child is built once, and is hidden using `Display::None` rather than being
+/// razed, so that its state (atoms, scroll positions, child presenters) is preserved.
+///
+/// If `suspend_when_hidden()` is set, then presenters inside the child will not re-render
+/// while hidden. Any changes they missed are applied when the child becomes visible again.
+///
+/// Example:
+///
+/// ```ignore
+/// Show::new(panel_open, panel_contents.bind(()))
+/// ```
+pub struct Show<V: View> {
+    test: bool,
+    child: V,
+    suspend: bool,
+}
+
+impl<V: View> Show<V> {
+    /// Construct a new Show View.
+    pub fn new(test: bool, child: V) -> Self {
+        Self {
+            test,
+            child,
+            suspend: false,
+        }
+    }
+
+    /// Don't re-render presenters inside the child while it is hidden.
+    pub fn suspend_when_hidden(mut self) -> Self {
+        self.suspend = true;
+        self
+    }
+}
+
+impl<V: View> View for Show<V> {
+    type State = ShowState<V::State>;
+
+    fn nodes(&self, bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        self.child.nodes(bc, &state.state)
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        let hidden = !self.test;
+        let scope = bc
+            .world
+            .spawn((
+                ViewScope {
+                    suspended: hidden && self.suspend,
+                },
+                Name::new("show"),
+            ))
+            .set_parent(bc.entity)
+            .id();
+        let state = self.child.build(&mut bc.for_entity(scope));
+        ShowState {
+            scope,
+            hidden,
+            state,
+        }
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        self.child
+            .update(&mut bc.for_entity(state.scope), &mut state.state);
+        state.hidden = !self.test;
+        let suspended = state.hidden && self.suspend;
+        let mut view_scope = bc.world.get_mut::<ViewScope>(state.scope).unwrap();
+        if view_scope.suspended != suspended {
+            view_scope.suspended = suspended;
+            if !suspended {
+                resume_presenters(bc.world, state.scope);
+            }
+        }
+    }
+
+    fn assemble(&self, bc: &mut BuildContext, state: &mut Self::State) -> NodeSpan {
+        let nodes = self
+            .child
+            .assemble(&mut bc.for_entity(state.scope), &mut state.state);
+        set_view_hidden(&nodes, bc.world, state.hidden);
+        nodes
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        self.child.raze(world, &mut state.state);
+        let mut entt = world.entity_mut(state.scope);
+        entt.remove_parent();
+        entt.despawn();
+    }
+}
+
+/// Returns true if the presenter entity is inside a suspended [`Show`].
+pub(crate) fn is_presenter_suspended(world: &World, presenter: Entity) -> bool {
+    let mut entity = presenter;
+    while let Some(parent) = world.get::<Parent>(entity) {
+        entity = parent.get();
+        if let Some(scope) = world.get::<ViewScope>(entity) {
+            if scope.suspended {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Schedule a rebuild of any presenters within the scope that were skipped while suspended.
+fn resume_presenters(world: &mut World, entity: Entity) {
+    let Some(children) = world.get::<Children>(entity) else {
+        return;
+    };
+    let children: Vec<Entity> = children.iter().copied().collect();
+    for child in children {
+        let mut entt = world.entity_mut(child);
+        if entt.contains::<PresenterSuspended>() {
+            entt.remove::<PresenterSuspended>();
+            entt.insert(PresenterStateChanged);
+        }
+        // Recurse
+        resume_presenters(world, child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::*, ui};
+
+    use super::*;
+    use crate::Element;
+
+    #[test]
+    fn test_show() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = Show::new(true, Element::new());
+        let mut state = view.build(&mut bc);
+        let nodes = view.assemble(&mut bc, &mut state);
+        let NodeSpan::Node(e1) = nodes else {
+            panic!("Expected a single node");
+        };
+        bc.world.get_mut::<Style>(e1).unwrap().display = ui::Display::Grid;
+
+        // Hidden, but not despawned.
+        let view = Show::new(false, Element::new());
+        view.update(&mut bc, &mut state);
+        assert_eq!(view.assemble(&mut bc, &mut state), NodeSpan::Node(e1));
+        assert_eq!(
+            bc.world.get::<Style>(e1).unwrap().display,
+            ui::Display::None
+        );
+
+        // Shown again with the original display mode.
+        let view = Show::new(true, Element::new());
+        view.update(&mut bc, &mut state);
+        assert_eq!(view.assemble(&mut bc, &mut state), NodeSpan::Node(e1));
+        assert_eq!(
+            bc.world.get::<Style>(e1).unwrap().display,
+            ui::Display::Grid
+        );
+
+        view.raze(bc.world, &mut state);
+        assert!(bc.world.get_entity(e1).is_none());
+        assert!(bc.world.get_entity(state.scope).is_none());
+    }
+
+    #[test]
+    fn test_show_suspend() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = Show::new(false, Element::new()).suspend_when_hidden();
+        let mut state = view.build(&mut bc);
+        let presenter = bc.world.spawn_empty().set_parent(state.scope).id();
+        assert!(is_presenter_suspended(bc.world, presenter));
+        assert!(!is_presenter_suspended(bc.world, entity));
+
+        // A presenter which was skipped while suspended is rebuilt when shown.
+        bc.world.entity_mut(presenter).insert(PresenterSuspended);
+        let view = Show::new(true, Element::new()).suspend_when_hidden();
+        view.update(&mut bc, &mut state);
+        assert!(!is_presenter_suspended(bc.world, presenter));
+        assert!(!bc.world.entity(presenter).contains::<PresenterSuspended>());
+        assert!(bc
+            .world
+            .entity(presenter)
+            .contains::<PresenterStateChanged>());
+    }
+}
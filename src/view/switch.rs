@@ -0,0 +1,274 @@
+use bevy::ecs::world::World;
+
+use crate::{view::view_hidden::set_view_hidden, BoxedView, BoxedViewState, BuildContext, View};
+
+use crate::node_span::NodeSpan;
+
+/// State for a [`Switch`] view.
+pub struct SwitchState {
+    /// Index of the currently active branch. The fallback branch is at the end of the list.
+    active: Option<usize>,
+
+    /// View state for each branch. Branches that have not been built (or which were razed)
+    /// are `None`.
+    branches: Vec<Option<BoxedViewState>>,
+}
+
+/// A conditional view which renders one of several children, depending on which case matches
+/// the switch value. Cases are tested in order; if none match, then the fallback view (if any)
+/// is rendered.
+///
+/// By default, switching to a different case razes the output of the previous case. If
+/// `keep_alive()` is set, then inactive cases are instead kept alive and hidden using
+/// `Display::None`, which preserves their state (atoms, scroll positions, etc.).
+///
+/// Example:
+///
+/// ```ignore
+/// Switch::new(mode)
+///     .case(Mode::Select, select_tools.bind(()))
+///     .case(Mode::Paint, paint_tools.bind(()))
+///     .fallback("No tools")
+/// ```
+pub struct Switch<Value: PartialEq + Send> {
+    value: Value,
+    cases: Vec<(Value, BoxedView)>,
+    fallback: Option<BoxedView>,
+    keep_alive: bool,
+}
+
+impl<Value: PartialEq + Send> Switch<Value> {
+    /// Construct a new Switch View.
+    pub fn new(value: Value) -> Self {
+        Self {
+            value,
+            cases: Vec::new(),
+            fallback: None,
+            keep_alive: false,
+        }
+    }
+
+    /// Add a case, which renders `view` when the switch value equals `value`.
+    pub fn case<V: View + 'static>(mut self, value: Value, view: V) -> Self
+    where
+        V::State: 'static,
+    {
+        self.cases.push((value, view.boxed()));
+        self
+    }
+
+    /// Set the view which is rendered when none of the cases match.
+    pub fn fallback<V: View + 'static>(mut self, view: V) -> Self
+    where
+        V::State: 'static,
+    {
+        self.fallback = Some(view.boxed());
+        self
+    }
+
+    /// Keep inactive branches alive, hiding them rather than razing them.
+    pub fn keep_alive(mut self) -> Self {
+        self.keep_alive = true;
+        self
+    }
+
+    /// Return the index of the branch which should be rendered.
+    fn select(&self) -> Option<usize> {
+        self.cases
+            .iter()
+            .position(|(value, _)| *value == self.value)
+            .or_else(|| self.fallback.as_ref().map(|_| self.cases.len()))
+    }
+
+    /// Return the view for the branch at the given index.
+    fn branch(&self, index: usize) -> &BoxedView {
+        if index < self.cases.len() {
+            &self.cases[index].1
+        } else {
+            self.fallback.as_ref().unwrap()
+        }
+    }
+}
+
+impl<Value: PartialEq + Send> View for Switch<Value> {
+    type State = SwitchState;
+
+    fn nodes(&self, bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        // Inactive branches which are kept alive still need to be in the display graph,
+        // otherwise they would become root nodes.
+        let child_spans: Vec<NodeSpan> = state
+            .branches
+            .iter()
+            .filter_map(|branch| branch.as_ref().map(|branch| branch.nodes(bc)))
+            .collect();
+        NodeSpan::Fragment(child_spans.into_boxed_slice())
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        let mut branches: Vec<Option<BoxedViewState>> =
+            (0..=self.cases.len()).map(|_| None).collect();
+        let active = self.select();
+        if let Some(index) = active {
+            branches[index] = Some(self.branch(index).build(bc));
+        }
+        SwitchState { active, branches }
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        if state.branches.len() != self.cases.len() + 1 {
+            // The set of cases changed, so per-case state is meaningless; start over.
+            self.raze(bc.world, state);
+            bc.mark_changed_shape();
+            *state = self.build(bc);
+            return;
+        }
+
+        let next = self.select();
+        if next != state.active {
+            if let Some(prev) = state.active {
+                if !self.keep_alive {
+                    // Despawn old state
+                    if let Some(mut prev_state) = state.branches[prev].take() {
+                        prev_state.raze(bc.world);
+                    }
+                }
+            }
+            bc.mark_changed_shape();
+        }
+
+        if let Some(index) = next {
+            let view = self.branch(index);
+            match state.branches[index] {
+                // Mutate state in place
+                Some(ref mut branch) => view.update(bc, branch),
+                // Construct new state
+                None => state.branches[index] = Some(view.build(bc)),
+            }
+        }
+        state.active = next;
+    }
+
+    fn assemble(&self, bc: &mut BuildContext, state: &mut Self::State) -> NodeSpan {
+        let active = state.active;
+        let child_spans: Vec<NodeSpan> = state
+            .branches
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, branch)| {
+                branch.as_mut().map(|branch| {
+                    let nodes = branch.assemble(bc);
+                    set_view_hidden(&nodes, bc.world, Some(index) != active);
+                    nodes
+                })
+            })
+            .collect();
+        NodeSpan::Fragment(child_spans.into_boxed_slice())
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        for branch in state.branches.iter_mut() {
+            if let Some(mut branch) = branch.take() {
+                branch.raze(world);
+            }
+        }
+        state.active = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::*, ui};
+
+    use super::*;
+    use crate::Element;
+
+    #[derive(PartialEq, Clone, Copy)]
+    enum Mode {
+        A,
+        B,
+        C,
+    }
+
+    fn switch(mode: Mode) -> Switch<Mode> {
+        Switch::new(mode)
+            .case(Mode::A, Element::new())
+            .case(Mode::B, Element::new())
+    }
+
+    #[test]
+    fn test_switch() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = switch(Mode::A);
+        let mut state = view.build(&mut bc);
+        let nodes_a = view.assemble(&mut bc, &mut state);
+        assert_eq!(nodes_a.count(), 1);
+
+        // Same case, same output.
+        let view = switch(Mode::A);
+        view.update(&mut bc, &mut state);
+        assert_eq!(view.assemble(&mut bc, &mut state), nodes_a);
+
+        // Different case, previous output should be razed.
+        let view = switch(Mode::B);
+        view.update(&mut bc, &mut state);
+        let nodes_b = view.assemble(&mut bc, &mut state);
+        assert_eq!(nodes_b.count(), 1);
+        assert_ne!(nodes_b, nodes_a);
+        let mut entities = Vec::new();
+        nodes_a.flatten(&mut entities);
+        assert!(bc.world.get_entity(entities[0]).is_none());
+
+        // No match and no fallback renders nothing.
+        let view = switch(Mode::C);
+        view.update(&mut bc, &mut state);
+        assert_eq!(view.assemble(&mut bc, &mut state).count(), 0);
+
+        // Fallback
+        let view = switch(Mode::C).fallback("Fallback");
+        view.update(&mut bc, &mut state);
+        assert_eq!(view.assemble(&mut bc, &mut state).count(), 1);
+    }
+
+    #[test]
+    fn test_switch_keep_alive() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = switch(Mode::A).keep_alive();
+        let mut state = view.build(&mut bc);
+        let nodes = view.assemble(&mut bc, &mut state);
+        let mut entities = Vec::new();
+        nodes.flatten(&mut entities);
+        let e_a = entities[0];
+        bc.world.get_mut::<Style>(e_a).unwrap().display = ui::Display::Grid;
+
+        // Inactive branch is hidden, not razed.
+        let view = switch(Mode::B).keep_alive();
+        view.update(&mut bc, &mut state);
+        let nodes = view.assemble(&mut bc, &mut state);
+        assert_eq!(nodes.count(), 2);
+        assert_eq!(
+            bc.world.get::<Style>(e_a).unwrap().display,
+            ui::Display::None
+        );
+
+        // Switching back restores the same entity and its display mode.
+        let view = switch(Mode::A).keep_alive();
+        view.update(&mut bc, &mut state);
+        view.assemble(&mut bc, &mut state);
+        assert_eq!(
+            bc.world.get::<Style>(e_a).unwrap().display,
+            ui::Display::Grid
+        );
+    }
+}
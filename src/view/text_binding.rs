@@ -0,0 +1,304 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use bevy::{
+    ecs::component::Tick,
+    prelude::*,
+    text::{Text, TextStyle},
+};
+
+use crate::{AtomCell, AtomHandle, BuildContext, View};
+
+use crate::node_span::NodeSpan;
+
+/// A type-erased source of text for a [`BoundText`] view. The source knows how to detect
+/// changes to the data it depends on, and how to format that data as a string.
+trait AnyTextSource: Send + Sync {
+    /// Returns true if the underlying data has changed since `last_run`.
+    fn is_changed(&self, world: &World, last_run: Tick, this_run: Tick) -> bool;
+
+    /// Format the underlying data, or return `None` if the data no longer exists.
+    fn format(&self, world: &World) -> Option<String>;
+}
+
+struct AtomTextSource<T: Clone + Send + Sync + 'static, F: Fn(&T) -> String + Send + Sync> {
+    handle: AtomHandle<T>,
+    format: F,
+}
+
+impl<T: Clone + Send + Sync + 'static, F: Fn(&T) -> String + Send + Sync> AnyTextSource
+    for AtomTextSource<T, F>
+{
+    fn is_changed(&self, world: &World, last_run: Tick, this_run: Tick) -> bool {
+        world
+            .get_entity(self.handle.id)
+            .and_then(|entt| entt.get_ref::<AtomCell>())
+            .map(|cell| cell.last_changed().is_newer_than(last_run, this_run))
+            .unwrap_or(false)
+    }
+
+    fn format(&self, world: &World) -> Option<String> {
+        let cell = world.get_entity(self.handle.id)?.get::<AtomCell>()?;
+        let value = cell
+            .0
+            .as_ref()
+            .downcast_ref::<T>()
+            .expect("Atom is incorrect type");
+        Some((self.format)(value))
+    }
+}
+
+struct ResourceTextSource<R: Resource, F: Fn(&R) -> String + Send + Sync> {
+    format: F,
+    marker: PhantomData<R>,
+}
+
+impl<R: Resource, F: Fn(&R) -> String + Send + Sync> AnyTextSource for ResourceTextSource<R, F> {
+    fn is_changed(&self, world: &World, last_run: Tick, this_run: Tick) -> bool {
+        world
+            .get_resource_change_ticks::<R>()
+            .map(|ticks| ticks.is_changed(last_run, this_run))
+            .unwrap_or(false)
+    }
+
+    fn format(&self, world: &World) -> Option<String> {
+        world.get_resource::<R>().map(&self.format)
+    }
+}
+
+struct ComponentTextSource<C: Component, F: Fn(&C) -> String + Send + Sync> {
+    entity: Entity,
+    format: F,
+    marker: PhantomData<C>,
+}
+
+impl<C: Component, F: Fn(&C) -> String + Send + Sync> AnyTextSource for ComponentTextSource<C, F> {
+    fn is_changed(&self, world: &World, last_run: Tick, this_run: Tick) -> bool {
+        world
+            .get_entity(self.entity)
+            .and_then(|entt| entt.get_ref::<C>())
+            .map(|cmp| cmp.last_changed().is_newer_than(last_run, this_run))
+            .unwrap_or(false)
+    }
+
+    fn format(&self, world: &World) -> Option<String> {
+        world.get_entity(self.entity)?.get::<C>().map(&self.format)
+    }
+}
+
+/// Component which links a text display node to the data source it is bound to. The
+/// [`update_text_bindings`] system uses this to update the text directly, without re-running
+/// the presenter that created it.
+#[derive(Component)]
+#[doc(hidden)]
+pub struct TextBinding {
+    source: Arc<dyn AnyTextSource>,
+    tick: Tick,
+}
+
+/// A View which renders a text node whose content is bound to an atom, resource or
+/// component. When the source data changes, the text is updated in place by a system;
+/// the presenter which created the view is not re-run, and no diffing takes place.
+///
+/// Construct these using the methods of [`BindText`], e.g. `Text::bind(atom, |v| ...)`.
+#[derive(Clone)]
+pub struct BoundText {
+    source: Arc<dyn AnyTextSource>,
+}
+
+impl BoundText {
+    fn format(&self, world: &World) -> String {
+        self.source.format(world).unwrap_or_default()
+    }
+}
+
+impl View for BoundText {
+    type State = Entity;
+
+    fn nodes(&self, _bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        NodeSpan::Node(*state)
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        let value = self.format(bc.world);
+        let tick = bc.world.change_tick();
+        bc.world
+            .spawn((
+                TextBundle {
+                    text: Text::from_section(value, TextStyle { ..default() }),
+                    ..default()
+                },
+                TextBinding {
+                    source: self.source.clone(),
+                    tick,
+                },
+            ))
+            .id()
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        // The presenter re-ran for some other reason. The formatting closure may have captured
+        // different values this time, so re-bind and refresh the text.
+        let value = self.format(bc.world);
+        let tick = bc.world.change_tick();
+        let mut entt = bc.entity_mut(*state);
+        if let Some(mut binding) = entt.get_mut::<TextBinding>() {
+            binding.source = self.source.clone();
+            binding.tick = tick;
+        }
+        if let Some(mut text) = entt.get_mut::<Text>() {
+            set_text_value(&mut text, value);
+        }
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        let mut entt = world.entity_mut(*state);
+        entt.remove_parent();
+        entt.despawn();
+    }
+}
+
+impl PartialEq for BoundText {
+    fn eq(&self, other: &Self) -> bool {
+        // Closures can't be compared, so reference-equality is the best we can do.
+        Arc::ptr_eq(&self.source, &other.source)
+    }
+}
+
+/// Methods for constructing [`BoundText`] views. This is implemented for Bevy's [`Text`]
+/// component, so bindings can be written as `Text::bind(atom, |v| format!("{}", v))`.
+pub trait BindText {
+    /// Construct a text view whose content is derived from the value of an atom.
+    fn bind<T: Clone + Send + Sync + 'static, F: Fn(&T) -> String + Send + Sync + 'static>(
+        atom: AtomHandle<T>,
+        format: F,
+    ) -> BoundText {
+        BoundText {
+            source: Arc::new(AtomTextSource {
+                handle: atom,
+                format,
+            }),
+        }
+    }
+
+    /// Construct a text view whose content is derived from a resource.
+    fn bind_resource<R: Resource, F: Fn(&R) -> String + Send + Sync + 'static>(
+        format: F,
+    ) -> BoundText {
+        BoundText {
+            source: Arc::new(ResourceTextSource {
+                format,
+                marker: PhantomData,
+            }),
+        }
+    }
+
+    /// Construct a text view whose content is derived from a component on the given entity.
+    fn bind_component<C: Component, F: Fn(&C) -> String + Send + Sync + 'static>(
+        entity: Entity,
+        format: F,
+    ) -> BoundText {
+        BoundText {
+            source: Arc::new(ComponentTextSource {
+                entity,
+                format,
+                marker: PhantomData,
+            }),
+        }
+    }
+}
+
+impl BindText for Text {}
+
+fn set_text_value(text: &mut Mut<Text>, value: String) {
+    if text.sections.len() == 1 {
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    } else {
+        text.sections.clear();
+        text.sections.push(TextSection {
+            value,
+            style: TextStyle { ..default() },
+        });
+    }
+}
+
+/// System which updates the content of bound text nodes whose source data has changed.
+pub(crate) fn update_text_bindings(world: &mut World) {
+    let this_run = world.change_tick();
+    let mut q = world.query_filtered::<Entity, With<TextBinding>>();
+    let bound: Vec<Entity> = q.iter(world).collect();
+    for e in bound {
+        let binding = world.get::<TextBinding>(e).unwrap();
+        if !binding.source.is_changed(world, binding.tick, this_run) {
+            continue;
+        }
+        let value = binding.source.format(world).unwrap_or_default();
+        let mut entt = world.entity_mut(e);
+        entt.get_mut::<TextBinding>().unwrap().tick = this_run;
+        if let Some(mut text) = entt.get_mut::<Text>() {
+            set_text_value(&mut text, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::world::World;
+
+    use super::*;
+    use crate::AtomMethods;
+
+    #[derive(Resource)]
+    struct Score(i32);
+
+    fn text_value(world: &World, entity: Entity) -> String {
+        world.get::<Text>(entity).unwrap().sections[0].value.clone()
+    }
+
+    #[test]
+    fn test_bind_atom() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let atom = world.create_atom::<i32>();
+        world.set_atom(atom, 1);
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = Text::bind(atom, |v| format!("Count: {}", v));
+        let state = view.build(&mut bc);
+        assert_eq!(text_value(&world, state), "Count: 1");
+
+        // Nothing changed, so the text stays the same.
+        world.increment_change_tick();
+        update_text_bindings(&mut world);
+        assert_eq!(text_value(&world, state), "Count: 1");
+
+        world.increment_change_tick();
+        world.set_atom(atom, 2);
+        update_text_bindings(&mut world);
+        assert_eq!(text_value(&world, state), "Count: 2");
+    }
+
+    #[test]
+    fn test_bind_resource() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        world.insert_resource(Score(10));
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = Text::bind_resource(|score: &Score| format!("{}", score.0));
+        let state = view.build(&mut bc);
+        assert_eq!(text_value(&world, state), "10");
+
+        world.increment_change_tick();
+        world.resource_mut::<Score>().0 = 20;
+        update_text_bindings(&mut world);
+        assert_eq!(text_value(&world, state), "20");
+    }
+}
@@ -0,0 +1,493 @@
+use std::{collections::HashMap, hash::Hash, marker::PhantomData};
+
+use bevy::prelude::*;
+
+use crate::{presenter_state::PresenterStateChanged, BuildContext, ElementClasses, View};
+
+use crate::node_span::NodeSpan;
+
+/// The stages of an item's enter and exit transitions within a [`TransitionGroup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionPhase {
+    /// One-frame delay at start of entering.
+    EnterStart,
+
+    /// Entering animation.
+    Entering,
+
+    /// Fully entered.
+    Entered,
+
+    /// One-frame delay at start of exiting.
+    ExitStart,
+
+    /// Exiting animation.
+    Exiting,
+
+    /// Fully exited; the item will be razed.
+    Exited,
+}
+
+impl TransitionPhase {
+    /// The class name which is added to the item's display nodes during this phase, if any.
+    pub fn as_class_name(&self) -> Option<&'static str> {
+        match self {
+            TransitionPhase::EnterStart => Some("enter-start"),
+            TransitionPhase::Entering => Some("entering"),
+            TransitionPhase::ExitStart => Some("exit-start"),
+            TransitionPhase::Exiting => Some("exiting"),
+            TransitionPhase::Entered | TransitionPhase::Exited => None,
+        }
+    }
+
+    fn is_exit(&self) -> bool {
+        matches!(
+            self,
+            TransitionPhase::ExitStart | TransitionPhase::Exiting | TransitionPhase::Exited
+        )
+    }
+}
+
+/// Component attached to the display nodes of each item in a [`TransitionGroup`], which tracks
+/// the progress of its enter or exit transition.
+#[derive(Component)]
+#[doc(hidden)]
+pub struct TransitionGroupItem {
+    phase: TransitionPhase,
+    timer: f32,
+    enter_duration: f32,
+    exit_duration: f32,
+    /// Presenter to re-render when the item has finished exiting.
+    owner: Entity,
+}
+
+/// Set the class name for the current phase, removing the class names of other phases.
+fn set_phase_class(entt: &mut EntityWorldMut, phase: TransitionPhase) {
+    let class_name = phase.as_class_name();
+    match entt.get_mut::<ElementClasses>() {
+        Some(mut classes) => update_classes(&mut classes, class_name),
+        None => {
+            if let Some(class_name) = class_name {
+                let mut classes = ElementClasses::default();
+                classes.add_class(class_name);
+                entt.insert(classes);
+            }
+        }
+    }
+}
+
+fn update_classes(classes: &mut Mut<ElementClasses>, class_name: Option<&str>) {
+    const PHASE_CLASSES: [&str; 4] = ["enter-start", "entering", "exit-start", "exiting"];
+    let stale = PHASE_CLASSES
+        .iter()
+        .any(|cls| Some(*cls) != class_name && classes.0.contains(*cls));
+    let missing = class_name.is_some_and(|cls| !classes.0.contains(cls));
+    if stale || missing {
+        for cls in PHASE_CLASSES {
+            classes.remove_class(cls);
+        }
+        if let Some(cls) = class_name {
+            classes.add_class(cls);
+        }
+    }
+}
+
+/// State for a single item in a [`TransitionGroup`].
+pub struct TransitionGroupItemState<Key, V: View> {
+    key: Key,
+    view: V,
+    state: V::State,
+    /// Phase to use for display nodes which don't have a phase yet.
+    initial_phase: TransitionPhase,
+    /// True if this item has been removed, and is waiting for its exit transition.
+    exiting: bool,
+}
+
+/// A keyed list of items which, rather than being razed immediately when they are removed,
+/// remain mounted with the `exit-start` and then `exiting` class names until their exit
+/// transition has finished. Likewise, items which are added to the group get the
+/// `enter-start` and then `entering` class names. Styles can use these class names to
+/// animate items in and out.
+///
+/// Items which are present when the group is first built don't play an enter transition.
+///
+/// For a single conditional child, use a list of zero or one items.
+///
+/// Example:
+///
+/// ```ignore
+/// TransitionGroup::new(&notifications, |n| n.id, |n| notification.bind(n.clone()))
+///     .enter_duration(0.2)
+///     .exit_duration(0.3)
+/// ```
+pub struct TransitionGroup<
+    Item: Send + Clone,
+    Key: Send + Hash + Eq,
+    V: View,
+    K: Fn(&Item) -> Key + Send,
+    F: Fn(&Item) -> V + Send,
+> {
+    items: Vec<Item>,
+    keyof: K,
+    each: F,
+    enter_duration: f32,
+    exit_duration: f32,
+    key: PhantomData<Key>,
+}
+
+impl<
+        Item: Send + Clone,
+        Key: Send + Hash + Eq,
+        V: View,
+        K: Fn(&Item) -> Key + Send,
+        F: Fn(&Item) -> V + Send,
+    > TransitionGroup<Item, Key, V, K, F>
+{
+    /// Construct a new `TransitionGroup`. Items are matched by key, as with `For::hashed()`.
+    pub fn new(items: &[Item], keyof: K, each: F) -> Self {
+        Self {
+            items: Vec::from(items),
+            keyof,
+            each,
+            enter_duration: 0.3,
+            exit_duration: 0.3,
+            key: PhantomData,
+        }
+    }
+
+    /// Set how long, in seconds, items keep the `entering` class. Default is 0.3.
+    pub fn enter_duration(mut self, duration: f32) -> Self {
+        self.enter_duration = duration;
+        self
+    }
+
+    /// Set how long, in seconds, removed items remain mounted with the `exiting` class.
+    /// Default is 0.3.
+    pub fn exit_duration(mut self, duration: f32) -> Self {
+        self.exit_duration = duration;
+        self
+    }
+
+    /// Apply enter and exit phases to the display nodes of an item.
+    fn apply_phase(
+        &self,
+        world: &mut World,
+        owner: Entity,
+        item: &TransitionGroupItemState<Key, V>,
+        nodes: &NodeSpan,
+    ) {
+        let mut entities: Vec<Entity> = Vec::new();
+        nodes.flatten(&mut entities);
+        for entity in entities {
+            let mut entt = world.entity_mut(entity);
+            let phase = match entt.get_mut::<TransitionGroupItem>() {
+                Some(mut ti) => {
+                    ti.enter_duration = self.enter_duration;
+                    ti.exit_duration = self.exit_duration;
+                    if item.exiting != ti.phase.is_exit() {
+                        // Item was removed, or re-added while exiting.
+                        ti.phase = if item.exiting {
+                            TransitionPhase::ExitStart
+                        } else {
+                            TransitionPhase::EnterStart
+                        };
+                        ti.timer = 0.;
+                    }
+                    ti.phase
+                }
+                None => {
+                    let phase = if item.exiting {
+                        TransitionPhase::ExitStart
+                    } else {
+                        item.initial_phase
+                    };
+                    entt.insert(TransitionGroupItem {
+                        phase,
+                        timer: 0.,
+                        enter_duration: self.enter_duration,
+                        exit_duration: self.exit_duration,
+                        owner,
+                    });
+                    phase
+                }
+            };
+            set_phase_class(&mut entt, phase);
+        }
+    }
+
+    /// Returns true if the item has finished its exit transition.
+    fn has_exited(&self, bc: &BuildContext, item: &TransitionGroupItemState<Key, V>) -> bool {
+        let mut entities: Vec<Entity> = Vec::new();
+        item.view.nodes(bc, &item.state).flatten(&mut entities);
+        !entities.iter().any(|entity| {
+            bc.world
+                .get::<TransitionGroupItem>(*entity)
+                .is_some_and(|ti| ti.phase != TransitionPhase::Exited)
+        })
+    }
+}
+
+impl<
+        Item: Send + Clone,
+        Key: Send + Hash + Eq,
+        V: View,
+        K: Fn(&Item) -> Key + Send,
+        F: Fn(&Item) -> V + Send,
+    > View for TransitionGroup<Item, Key, V, K, F>
+{
+    type State = Vec<TransitionGroupItemState<Key, V>>;
+
+    fn nodes(&self, bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        let child_spans: Vec<NodeSpan> = state
+            .iter()
+            .map(|item| item.view.nodes(bc, &item.state))
+            .collect();
+        NodeSpan::Fragment(child_spans.into_boxed_slice())
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        self.items
+            .iter()
+            .map(|item| {
+                let view = (self.each)(item);
+                let state = view.build(bc);
+                TransitionGroupItemState {
+                    key: (self.keyof)(item),
+                    view,
+                    state,
+                    initial_phase: TransitionPhase::Entered,
+                    exiting: false,
+                }
+            })
+            .collect()
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        let next_len = self.items.len();
+        let index_of: HashMap<Key, usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| ((self.keyof)(item), index))
+            .collect();
+
+        // Match previous items to current items. Items which are no longer present are kept
+        // after the item which preceded them, so that they exit in place.
+        let mut retained: Vec<Option<TransitionGroupItemState<Key, V>>> =
+            (0..next_len).map(|_| None).collect();
+        let mut exiting: Vec<Vec<TransitionGroupItemState<Key, V>>> =
+            (0..=next_len).map(|_| Vec::new()).collect();
+        let mut anchor = 0;
+        for mut item in state.drain(..) {
+            match index_of.get(&item.key) {
+                Some(&index) if retained[index].is_none() => {
+                    item.exiting = false;
+                    anchor = index + 1;
+                    retained[index] = Some(item);
+                }
+                _ => {
+                    if item.exiting && self.has_exited(bc, &item) {
+                        item.view.raze(bc.world, &mut item.state);
+                    } else {
+                        item.exiting = true;
+                        exiting[anchor].push(item);
+                    }
+                }
+            }
+        }
+
+        state.append(&mut exiting[0]);
+        for (index, item) in self.items.iter().enumerate() {
+            let view = (self.each)(item);
+            match retained[index].take() {
+                Some(mut prev) => {
+                    view.update(bc, &mut prev.state);
+                    prev.view = view;
+                    state.push(prev);
+                }
+                None => {
+                    let item_state = view.build(bc);
+                    state.push(TransitionGroupItemState {
+                        key: (self.keyof)(item),
+                        view,
+                        state: item_state,
+                        initial_phase: TransitionPhase::EnterStart,
+                        exiting: false,
+                    });
+                }
+            }
+            state.append(&mut exiting[index + 1]);
+        }
+    }
+
+    fn assemble(&self, bc: &mut BuildContext, state: &mut Self::State) -> NodeSpan {
+        let owner = bc.presenter();
+        let child_spans: Vec<NodeSpan> = state
+            .iter_mut()
+            .map(|item| {
+                let nodes = item.view.assemble(bc, &mut item.state);
+                self.apply_phase(bc.world, owner, item, &nodes);
+                nodes
+            })
+            .collect();
+        NodeSpan::Fragment(child_spans.into_boxed_slice())
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        for item in state.iter_mut() {
+            item.view.raze(world, &mut item.state);
+        }
+    }
+}
+
+/// System which advances the enter and exit transitions of items in transition groups.
+pub(crate) fn update_transition_groups(
+    mut commands: Commands,
+    mut query: Query<(&mut TransitionGroupItem, Option<&mut ElementClasses>)>,
+    time: Res<Time>,
+) {
+    for (mut ti, classes) in query.iter_mut() {
+        let prev_phase = ti.phase;
+        match ti.phase {
+            TransitionPhase::EnterStart => {
+                ti.phase = TransitionPhase::Entering;
+                ti.timer = 0.;
+            }
+            TransitionPhase::Entering => {
+                ti.timer += time.delta_seconds();
+                if ti.timer >= ti.enter_duration {
+                    ti.phase = TransitionPhase::Entered;
+                }
+            }
+            TransitionPhase::ExitStart => {
+                ti.phase = TransitionPhase::Exiting;
+                ti.timer = 0.;
+            }
+            TransitionPhase::Exiting => {
+                ti.timer += time.delta_seconds();
+                if ti.timer >= ti.exit_duration {
+                    ti.phase = TransitionPhase::Exited;
+                    // Re-render the presenter so that the item gets razed.
+                    commands.entity(ti.owner).insert(PresenterStateChanged);
+                }
+            }
+            TransitionPhase::Entered | TransitionPhase::Exited => {}
+        }
+        if ti.phase != prev_phase {
+            if let Some(mut classes) = classes {
+                update_classes(&mut classes, ti.phase.as_class_name());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::{ClassNames, Element};
+
+    fn phase(world: &World, entity: Entity) -> TransitionPhase {
+        world.get::<TransitionGroupItem>(entity).unwrap().phase
+    }
+
+    fn has_class(world: &World, entity: Entity, cls: &str) -> bool {
+        world
+            .get::<ElementClasses>(entity)
+            .is_some_and(|classes| classes.0.contains(cls))
+    }
+
+    #[test]
+    fn test_transition_group() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let group = |items: &[i32]| {
+            TransitionGroup::new(items, |item| *item, |_| Element::new())
+                .enter_duration(0.)
+                .exit_duration(0.)
+        };
+
+        // Initial items don't transition.
+        let view = group(&[1, 2]);
+        let mut state = view.build(&mut bc);
+        view.assemble(&mut bc, &mut state);
+        let e1 = state[0].state;
+        let e2 = state[1].state;
+        assert_eq!(phase(bc.world, e1), TransitionPhase::Entered);
+        assert!(!has_class(bc.world, e1, "enter-start"));
+
+        // Added item enters.
+        let view = group(&[1, 2, 3]);
+        view.update(&mut bc, &mut state);
+        view.assemble(&mut bc, &mut state);
+        let e3 = state[2].state;
+        assert!(has_class(bc.world, e3, "enter-start"));
+
+        // Removed item stays mounted, in the same position, until it has exited.
+        let view = group(&[1, 3]);
+        view.update(&mut bc, &mut state);
+        view.assemble(&mut bc, &mut state);
+        assert_eq!(state.len(), 3);
+        assert_eq!(state[1].state, e2);
+        assert!(has_class(bc.world, e2, "exit-start"));
+
+        bc.world.run_system_once(update_transition_groups);
+        assert!(has_class(bc.world, e2, "exiting"));
+        assert!(has_class(bc.world, e3, "entering"));
+        bc.world.run_system_once(update_transition_groups);
+        assert_eq!(phase(bc.world, e2), TransitionPhase::Exited);
+        assert_eq!(phase(bc.world, e3), TransitionPhase::Entered);
+        assert!(!has_class(bc.world, e3, "entering"));
+        assert!(bc.world.entity(entity).contains::<PresenterStateChanged>());
+
+        // Presenter re-renders, and the exited item is razed.
+        let view = group(&[1, 3]);
+        view.update(&mut bc, &mut state);
+        view.assemble(&mut bc, &mut state);
+        assert_eq!(state.len(), 2);
+        assert!(bc.world.get_entity(e2).is_none());
+    }
+
+    #[test]
+    fn test_rerender_keeps_phase_class() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let group = |items: &[i32]| {
+            TransitionGroup::new(
+                items,
+                |item| *item,
+                |item| Element::new().class_names(("item", "odd".if_true(item % 2 == 1))),
+            )
+        };
+
+        let view = group(&[1, 2]);
+        let mut state = view.build(&mut bc);
+        view.assemble(&mut bc, &mut state);
+        let e1 = state[0].state.0;
+
+        // Remove the item, then re-render it as its own presenter would, with different classes.
+        let view = group(&[2]);
+        view.update(&mut bc, &mut state);
+        view.assemble(&mut bc, &mut state);
+        assert!(has_class(bc.world, e1, "exit-start"));
+        let item = &mut state[0];
+        let rerender = Element::new().class_names("item");
+        rerender.update(&mut bc, &mut item.state);
+        assert!(has_class(bc.world, e1, "exit-start"));
+        assert!(has_class(bc.world, e1, "item"));
+        assert!(!has_class(bc.world, e1, "odd"));
+    }
+}
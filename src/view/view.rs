@@ -10,7 +10,7 @@ use crate::{presenter_state::*, ClassNames, Cx, StyleTuple, ViewHandle, ViewTupl
 use crate::node_span::NodeSpan;
 
 use super::{
-    bind::Bind, view_children::ViewChildren, view_classes::ViewClasses,
+    bind::Bind, boxed_view::BoxedView, view_children::ViewChildren, view_classes::ViewClasses,
     view_insert_bundle::ViewInsertBundle, view_named::ViewNamed, view_styled::ViewStyled,
     view_with::ViewWith, view_with_memo::ViewWithMemo,
 };
@@ -35,6 +35,19 @@ impl<'w> BuildContext<'w> {
             .insert(PresenterGraphChanged);
     }
 
+    /// Return the entity of the presenter which is being built. This differs from `entity`
+    /// for views which are built within a scope, such as the child of a [`Show`](crate::Show).
+    pub(crate) fn presenter(&self) -> Entity {
+        let mut entity = self.entity;
+        while !self.world.entity(entity).contains::<ViewHandle>() {
+            match self.world.get::<Parent>(entity) {
+                Some(parent) => entity = parent.get(),
+                None => return self.entity,
+            }
+        }
+        entity
+    }
+
     /// Return a modified [`ViewContext`] for a different entity.
     pub(crate) fn for_entity<'k>(&'k mut self, entity: Entity) -> BuildContext<'k>
     where
@@ -144,6 +157,16 @@ where
     fn children<A: ViewTuple>(self, items: A) -> ViewChildren<Self, A> {
         ViewChildren { inner: self, items }
     }
+
+    /// Erase the type of this view, so that it can be stored in a collection with views of
+    /// other types.
+    fn boxed(self) -> BoxedView
+    where
+        Self: 'static,
+        Self::State: 'static,
+    {
+        BoxedView::new(self)
+    }
 }
 
 /// View which renders nothing
@@ -16,15 +16,23 @@ impl<V: View> ViewClasses<V> {
         Self { inner, class_names }
     }
 
-    fn set_class_names(&self, nodes: &NodeSpan, bc: &mut BuildContext) {
+    /// Add this view's class names to the nodes, and remove the class names which were set
+    /// previously but are no longer present. Class names added by other means, such as the
+    /// phase classes of a [`TransitionGroup`](crate::TransitionGroup), are left alone.
+    fn set_class_names(&self, nodes: &NodeSpan, prev: &HashSet<String>, bc: &mut BuildContext) {
         match nodes {
             NodeSpan::Empty => (),
             NodeSpan::Node(entity) => {
                 let em = &mut bc.entity_mut(*entity);
-                match em.get_mut::<ElementClasses>() {
-                    Some(mut ec) => {
-                        if !ec.0.eq(&self.class_names) {
-                            ec.as_mut().0.clone_from(&self.class_names);
+                match em.get::<ElementClasses>() {
+                    Some(ec) => {
+                        let is_stale = |cls: &String| !self.class_names.contains(cls);
+                        if prev.iter().any(|cls| is_stale(cls) && ec.0.contains(cls))
+                            || !ec.0.is_superset(&self.class_names)
+                        {
+                            let mut ec = em.get_mut::<ElementClasses>().unwrap();
+                            ec.0.retain(|cls| !(prev.contains(cls) && is_stale(cls)));
+                            ec.0.extend(self.class_names.iter().cloned());
                         }
                     }
                     None => {
@@ -35,7 +43,7 @@ impl<V: View> ViewClasses<V> {
             NodeSpan::Fragment(ref nodes) => {
                 for node in nodes.iter() {
                     // Recurse
-                    self.set_class_names(node, bc);
+                    self.set_class_names(node, prev, bc);
                 }
             }
         }
@@ -43,29 +51,35 @@ impl<V: View> ViewClasses<V> {
 }
 
 impl<V: View> View for ViewClasses<V> {
-    type State = V::State;
+    /// The inner view's state, and the class names which were last applied.
+    type State = (V::State, HashSet<String>);
 
     fn nodes(&self, bc: &BuildContext, state: &Self::State) -> NodeSpan {
-        self.inner.nodes(bc, state)
+        self.inner.nodes(bc, &state.0)
     }
 
     fn build(&self, bc: &mut BuildContext) -> Self::State {
         let state = self.inner.build(bc);
-        self.set_class_names(&self.nodes(bc, &state), bc);
-        state
+        let nodes = self.inner.nodes(bc, &state);
+        self.set_class_names(&nodes, &HashSet::new(), bc);
+        (state, self.class_names.clone())
     }
 
     fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
-        self.inner.update(bc, state);
-        self.set_class_names(&self.nodes(bc, state), bc);
+        self.inner.update(bc, &mut state.0);
+        let nodes = self.inner.nodes(bc, &state.0);
+        self.set_class_names(&nodes, &state.1, bc);
+        if state.1 != self.class_names {
+            state.1.clone_from(&self.class_names);
+        }
     }
 
     fn assemble(&self, bc: &mut BuildContext, state: &mut Self::State) -> NodeSpan {
-        self.inner.assemble(bc, state)
+        self.inner.assemble(bc, &mut state.0)
     }
 
     fn raze(&self, world: &mut World, state: &mut Self::State) {
-        self.inner.raze(world, state);
+        self.inner.raze(world, &mut state.0);
     }
 }
 
@@ -0,0 +1,51 @@
+use bevy::{prelude::*, ui};
+
+use crate::node_span::NodeSpan;
+
+/// Marker component for display nodes which have been hidden by a view such as [`Show`] or
+/// [`Switch`], rather than by their styles. While present, the node's `Style::display` is forced
+/// to `Display::None`, and the display value that would otherwise apply is stored here so that
+/// it can be restored when the node is shown again.
+///
+/// [`Show`]: crate::Show
+/// [`Switch`]: crate::Switch
+#[derive(Component, Debug, Clone, Copy)]
+#[doc(hidden)]
+pub struct ViewHidden {
+    pub(crate) display: ui::Display,
+}
+
+/// Hide or show all of the top-level nodes in a [`NodeSpan`]. This is idempotent; nodes that
+/// are already in the requested state are not touched.
+pub(crate) fn set_view_hidden(nodes: &NodeSpan, world: &mut World, hidden: bool) {
+    match nodes {
+        NodeSpan::Empty => (),
+        NodeSpan::Node(entity) => {
+            let Some(mut entt) = world.get_entity_mut(*entity) else {
+                return;
+            };
+            match (hidden, entt.get::<ViewHidden>().copied()) {
+                (true, None) => {
+                    if let Some(mut style) = entt.get_mut::<Style>() {
+                        let display = style.display;
+                        style.display = ui::Display::None;
+                        entt.insert(ViewHidden { display });
+                    }
+                }
+                (false, Some(prev)) => {
+                    entt.remove::<ViewHidden>();
+                    if let Some(mut style) = entt.get_mut::<Style>() {
+                        style.display = prev.display;
+                    }
+                }
+                _ => (),
+            }
+        }
+        NodeSpan::Fragment(ref nodes) => {
+            for node in nodes.iter() {
+                // Recurse
+                set_view_hidden(node, world, hidden);
+            }
+        }
+    }
+}
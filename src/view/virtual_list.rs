@@ -0,0 +1,545 @@
+use std::{collections::BTreeMap, marker::PhantomData, ops::Range};
+
+use bevy::{prelude::*, ui};
+
+use crate::{
+    presenter_state::PresenterStateChanged, Bind, BuildContext, PresenterFn, ScrollArea,
+    ScrollContent, View,
+};
+
+use crate::node_span::NodeSpan;
+
+/// How to determine the height of rows in a [`VirtualList`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowHeight {
+    /// All rows are exactly this height.
+    Fixed(f32),
+
+    /// Rows are assumed to be about this height until they have been rendered, after which
+    /// their actual height is used.
+    Estimated(f32),
+}
+
+/// Computes row positions for a virtual list. Rows which have not been measured are assumed
+/// to be the nominal row height.
+#[derive(Debug, Clone)]
+struct RowLayout {
+    count: usize,
+    row_height: RowHeight,
+    /// Actual heights of rows that have been rendered, for estimated row heights.
+    measured: BTreeMap<usize, f32>,
+}
+
+impl RowLayout {
+    fn nominal_height(&self) -> f32 {
+        match self.row_height {
+            RowHeight::Fixed(height) | RowHeight::Estimated(height) => height,
+        }
+    }
+
+    /// Offset of the top of the given row from the top of the list.
+    fn offset(&self, index: usize) -> f32 {
+        let nominal = self.nominal_height();
+        self.measured
+            .range(..index)
+            .fold(index as f32 * nominal, |offset, (_, height)| {
+                offset + height - nominal
+            })
+    }
+
+    /// Total height of all rows.
+    fn total_height(&self) -> f32 {
+        self.offset(self.count)
+    }
+
+    /// Index of the row at the given vertical position.
+    fn row_at(&self, y: f32) -> usize {
+        let nominal = self.nominal_height();
+        let mut top = 0.;
+        let mut next = 0;
+        for (&index, &height) in self.measured.iter() {
+            // Unmeasured rows preceding this one.
+            let span = (index - next) as f32 * nominal;
+            if y < top + span {
+                break;
+            }
+            top += span;
+            if y < top + height {
+                return index.min(self.count);
+            }
+            top += height;
+            next = index + 1;
+        }
+        let skip = if nominal > 0. {
+            ((y - top) / nominal).max(0.) as usize
+        } else {
+            0
+        };
+        (next + skip).min(self.count)
+    }
+
+    /// Range of rows which intersect the viewport, plus `overscan` rows on either side.
+    fn visible_range(&self, scroll_top: f32, visible_height: f32, overscan: usize) -> Range<usize> {
+        let first = self.row_at(scroll_top);
+        let last = (self.row_at(scroll_top + visible_height) + 1).min(self.count);
+        first.saturating_sub(overscan)..(last + overscan).min(self.count)
+    }
+}
+
+/// Component attached to the content node of a [`VirtualList`], which records the layout of
+/// the list, and the position of the viewport as of the last frame.
+#[derive(Component)]
+#[doc(hidden)]
+pub struct VirtualListLayout {
+    /// Presenter which needs to re-render when the range of visible rows changes.
+    owner: Entity,
+    rows: RowLayout,
+    overscan: usize,
+    /// Range of rows that are currently rendered.
+    range: Range<usize>,
+    scroll_top: f32,
+    visible_height: f32,
+}
+
+impl VirtualListLayout {
+    fn visible_range(&self) -> Range<usize> {
+        self.rows
+            .visible_range(self.scroll_top, self.visible_height, self.overscan)
+    }
+}
+
+/// Component attached to the node which wraps each row of a [`VirtualList`].
+#[derive(Component)]
+#[doc(hidden)]
+pub struct VirtualRow {
+    pub(crate) index: usize,
+}
+
+/// State for a single rendered row.
+struct RowState {
+    index: usize,
+    /// Node which positions the row within the list.
+    wrapper: Entity,
+    /// Entity for the row presenter.
+    presenter: Entity,
+}
+
+/// State for a [`VirtualList`] view.
+pub struct VirtualListState {
+    /// The scroll content node.
+    content: Entity,
+    /// Node which gives the content node its full height.
+    spacer: Entity,
+    rows: Vec<RowState>,
+}
+
+/// A view which displays a long list of rows, but only renders the rows which are within the
+/// visible area of the enclosing [`ScrollArea`] (plus some overscan). As the user scrolls,
+/// row presenters which scroll out of view are re-used for the rows which scroll into view.
+///
+/// The view renders a single node, which should be placed as the content of a scrolling
+/// element: that is, as the child of an element which has a [`ScrollArea`] component. The
+/// row presenter is passed the index of the row as its props.
+///
+/// Example:
+///
+/// ```ignore
+/// VirtualList::new(assets.len(), RowHeight::Fixed(24.), asset_row)
+/// ```
+pub struct VirtualList<Marker: 'static, F: PresenterFn<Marker, Props = usize>> {
+    count: usize,
+    row_height: RowHeight,
+    overscan: usize,
+    presenter: F,
+    marker: PhantomData<fn() -> Marker>,
+}
+
+impl<Marker: 'static, F: PresenterFn<Marker, Props = usize>> VirtualList<Marker, F> {
+    /// Construct a new `VirtualList` with the given number of items.
+    pub fn new(count: usize, row_height: RowHeight, presenter: F) -> Self {
+        Self {
+            count,
+            row_height,
+            overscan: 4,
+            presenter,
+            marker: PhantomData,
+        }
+    }
+
+    /// Set the number of extra rows to render above and below the visible area. Default is 4.
+    pub fn overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    /// Create the node which positions a row.
+    fn spawn_wrapper(&self, world: &mut World, index: usize) -> Entity {
+        world
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: ui::PositionType::Absolute,
+                        left: ui::Val::Px(0.),
+                        right: ui::Val::Px(0.),
+                        ..default()
+                    },
+                    visibility: Visibility::Visible,
+                    ..default()
+                },
+                VirtualRow { index },
+                Name::new("virtual_row"),
+            ))
+            .id()
+    }
+
+    /// Update the position of each row, and the height of the content.
+    fn update_positions(&self, world: &mut World, state: &VirtualListState, rows: &RowLayout) {
+        for row in state.rows.iter() {
+            let mut entt = world.entity_mut(row.wrapper);
+            entt.get_mut::<VirtualRow>().unwrap().index = row.index;
+            let mut style = entt.get_mut::<Style>().unwrap();
+            let top = ui::Val::Px(rows.offset(row.index));
+            let height = match rows.row_height {
+                RowHeight::Fixed(height) => ui::Val::Px(height),
+                RowHeight::Estimated(_) => ui::Val::Auto,
+            };
+            if style.top != top || style.height != height {
+                style.top = top;
+                style.height = height;
+            }
+        }
+
+        let height = ui::Val::Px(rows.total_height());
+        let mut style = world.get_mut::<Style>(state.spacer).unwrap();
+        if style.height != height {
+            style.height = height;
+        }
+    }
+}
+
+impl<Marker: 'static, F: PresenterFn<Marker, Props = usize>> View for VirtualList<Marker, F> {
+    type State = VirtualListState;
+
+    fn nodes(&self, _bc: &BuildContext, state: &Self::State) -> NodeSpan {
+        NodeSpan::Node(state.content)
+    }
+
+    fn build(&self, bc: &mut BuildContext) -> Self::State {
+        let owner = bc.presenter();
+        let spacer = bc
+            .world
+            .spawn(NodeBundle {
+                style: Style {
+                    width: ui::Val::Px(0.),
+                    ..default()
+                },
+                ..default()
+            })
+            .id();
+        let content = bc
+            .world
+            .spawn((
+                NodeBundle {
+                    visibility: Visibility::Visible,
+                    ..default()
+                },
+                ScrollContent,
+                VirtualListLayout {
+                    owner,
+                    rows: RowLayout {
+                        count: self.count,
+                        row_height: self.row_height,
+                        measured: BTreeMap::new(),
+                    },
+                    overscan: self.overscan,
+                    range: 0..0,
+                    scroll_top: 0.,
+                    visible_height: 0.,
+                },
+                Name::new("virtual_list"),
+            ))
+            .id();
+        let mut state = VirtualListState {
+            content,
+            spacer,
+            rows: Vec::new(),
+        };
+        self.update(bc, &mut state);
+        state
+    }
+
+    fn update(&self, bc: &mut BuildContext, state: &mut Self::State) {
+        let mut layout = bc
+            .world
+            .get_mut::<VirtualListLayout>(state.content)
+            .unwrap();
+        if layout.rows.count != self.count || layout.rows.row_height != self.row_height {
+            layout.rows.count = self.count;
+            if layout.rows.row_height != self.row_height {
+                layout.rows.row_height = self.row_height;
+                layout.rows.measured.clear();
+            }
+            let count = self.count;
+            layout.rows.measured.retain(|index, _| *index < count);
+        }
+        layout.overscan = self.overscan;
+        let range = layout.visible_range();
+        layout.range = range.clone();
+        let rows = layout.rows.clone();
+
+        // Rows which are still visible keep their index; the rest are available for re-use.
+        let mut recycled: Vec<usize> = Vec::new();
+        let mut present = vec![false; range.len()];
+        for (slot, row) in state.rows.iter().enumerate() {
+            if range.contains(&row.index) {
+                present[row.index - range.start] = true;
+            } else {
+                recycled.push(slot);
+            }
+        }
+
+        for (offset, _) in present.iter().enumerate().filter(|(_, p)| !**p) {
+            let index = range.start + offset;
+            match recycled.pop() {
+                Some(slot) => state.rows[slot].index = index,
+                None => {
+                    let wrapper = self.spawn_wrapper(bc.world, index);
+                    let presenter = Bind::new(self.presenter, index).build(bc);
+                    state.rows.push(RowState {
+                        index,
+                        wrapper,
+                        presenter,
+                    })
+                }
+            }
+        }
+
+        // Raze rows which are no longer needed.
+        recycled.sort_unstable();
+        for slot in recycled.into_iter().rev() {
+            let mut row = state.rows.swap_remove(slot);
+            Bind::new(self.presenter, row.index).raze(bc.world, &mut row.presenter);
+            let mut entt = bc.world.entity_mut(row.wrapper);
+            entt.remove_parent();
+            entt.despawn();
+        }
+
+        // Pass the current row index to each presenter; only recycled rows will re-render.
+        state.rows.sort_by_key(|row| row.index);
+        for row in state.rows.iter_mut() {
+            Bind::new(self.presenter, row.index).update(bc, &mut row.presenter);
+        }
+
+        self.update_positions(bc.world, state, &rows);
+    }
+
+    fn assemble(&self, bc: &mut BuildContext, state: &mut Self::State) -> NodeSpan {
+        let mut children: Vec<Entity> = Vec::with_capacity(state.rows.len() + 1);
+        children.push(state.spacer);
+        for row in state.rows.iter() {
+            children.push(row.wrapper);
+            let mut row_nodes: Vec<Entity> = Vec::new();
+            Bind::new(self.presenter, row.index)
+                .nodes(bc, &row.presenter)
+                .flatten(&mut row_nodes);
+            replace_children(bc.world, row.wrapper, &row_nodes);
+        }
+        replace_children(bc.world, state.content, &children);
+        NodeSpan::Node(state.content)
+    }
+
+    fn raze(&self, world: &mut World, state: &mut Self::State) {
+        for row in state.rows.iter_mut() {
+            Bind::new(self.presenter, row.index).raze(world, &mut row.presenter);
+        }
+        // The spacer and row wrappers are only parented to the content node once assembled,
+        // so despawn them explicitly.
+        for entity in state
+            .rows
+            .drain(..)
+            .map(|row| row.wrapper)
+            .chain([state.spacer])
+        {
+            if let Some(entt) = world.get_entity_mut(entity) {
+                entt.despawn_recursive();
+            }
+        }
+        let mut entt = world.entity_mut(state.content);
+        entt.remove_parent();
+        entt.despawn_recursive();
+    }
+}
+
+fn replace_children(world: &mut World, parent: Entity, children: &[Entity]) {
+    let mut em = world.entity_mut(parent);
+    match em.get::<Children>() {
+        Some(prev) if prev.eq(children) => (),
+        _ => {
+            em.replace_children(children);
+        }
+    }
+}
+
+/// System which tracks the scroll position of each [`VirtualList`], measures rendered rows,
+/// and re-renders the list when the range of visible rows changes.
+pub(crate) fn update_virtual_lists(world: &mut World) {
+    let mut q = world.query_filtered::<Entity, With<VirtualListLayout>>();
+    let lists: Vec<Entity> = q.iter(world).collect();
+    for list in lists {
+        let Some(scroll_area) = world
+            .get::<Parent>(list)
+            .and_then(|parent| world.get::<ScrollArea>(parent.get()))
+        else {
+            continue;
+        };
+        let scroll_top = scroll_area.scroll_top;
+        let visible_height = scroll_area.visible_size.y;
+
+        // Measure the actual height of each row.
+        let mut heights: Vec<(usize, f32)> = Vec::new();
+        if let Some(children) = world.get::<Children>(list) {
+            for child in children.iter() {
+                let entt = world.entity(*child);
+                if let (Some(row), Some(node)) = (entt.get::<VirtualRow>(), entt.get::<Node>()) {
+                    if node.size().y > 0. {
+                        heights.push((row.index, node.size().y));
+                    }
+                }
+            }
+        }
+
+        let mut layout = world.get_mut::<VirtualListLayout>(list).unwrap();
+        let mut changed = false;
+        if let RowHeight::Estimated(_) = layout.rows.row_height {
+            for (index, height) in heights {
+                // Ignore sub-pixel differences so that rounding doesn't cause endless updates.
+                let unchanged = matches!(
+                    layout.rows.measured.get(&index),
+                    Some(prev) if (prev - height).abs() <= 0.5
+                );
+                if index < layout.rows.count && !unchanged {
+                    layout.rows.measured.insert(index, height);
+                    changed = true;
+                }
+            }
+        }
+        if layout.scroll_top != scroll_top || layout.visible_height != visible_height {
+            layout.scroll_top = scroll_top;
+            layout.visible_height = visible_height;
+        }
+        if changed || layout.visible_range() != layout.range {
+            let owner = layout.owner;
+            if let Some(mut entt) = world.get_entity_mut(owner) {
+                entt.insert(PresenterStateChanged);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cx, Element};
+
+    fn row(_cx: Cx<usize>) -> impl View {
+        Element::new()
+    }
+
+    #[test]
+    fn test_row_layout() {
+        let mut rows = RowLayout {
+            count: 100,
+            row_height: RowHeight::Fixed(10.),
+            measured: BTreeMap::new(),
+        };
+        assert_eq!(rows.offset(5), 50.);
+        assert_eq!(rows.total_height(), 1000.);
+        assert_eq!(rows.row_at(55.), 5);
+        assert_eq!(rows.row_at(5000.), 100);
+        assert_eq!(rows.visible_range(55., 30., 0), 5..9);
+        assert_eq!(rows.visible_range(55., 30., 2), 3..11);
+        assert_eq!(rows.visible_range(995., 30., 2), 97..100);
+
+        // Row 2 turns out to be taller than estimated.
+        rows.row_height = RowHeight::Estimated(10.);
+        rows.measured.insert(2, 30.);
+        assert_eq!(rows.offset(2), 20.);
+        assert_eq!(rows.offset(3), 50.);
+        assert_eq!(rows.total_height(), 1020.);
+        assert_eq!(rows.row_at(15.), 1);
+        assert_eq!(rows.row_at(45.), 2);
+        assert_eq!(rows.row_at(55.), 3);
+    }
+
+    #[test]
+    fn test_virtual_list() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = VirtualList::new(10_000, RowHeight::Fixed(20.), row).overscan(1);
+        let mut state = view.build(&mut bc);
+        view.assemble(&mut bc, &mut state);
+        bc.world
+            .get_mut::<VirtualListLayout>(state.content)
+            .unwrap()
+            .visible_height = 100.;
+        view.update(&mut bc, &mut state);
+        view.assemble(&mut bc, &mut state);
+        assert_eq!(state.rows.len(), 7);
+        assert_eq!(
+            bc.world.get::<Style>(state.spacer).unwrap().height,
+            ui::Val::Px(200_000.)
+        );
+        let wrappers: Vec<Entity> = state.rows.iter().map(|row| row.wrapper).collect();
+
+        // Scroll down: the existing row entities are re-used for the new range.
+        bc.world
+            .get_mut::<VirtualListLayout>(state.content)
+            .unwrap()
+            .scroll_top = 1000.;
+        view.update(&mut bc, &mut state);
+        view.assemble(&mut bc, &mut state);
+        let indices: Vec<usize> = state.rows.iter().map(|row| row.index).collect();
+        assert_eq!(indices, (49..57).collect::<Vec<usize>>());
+        let reused = state
+            .rows
+            .iter()
+            .filter(|row| wrappers.contains(&row.wrapper))
+            .count();
+        assert_eq!(reused, 7);
+        for row in state.rows.iter() {
+            assert_eq!(
+                bc.world.get::<Style>(row.wrapper).unwrap().top,
+                ui::Val::Px(row.index as f32 * 20.)
+            );
+        }
+
+        view.raze(bc.world, &mut state);
+        assert!(bc.world.get_entity(wrappers[0]).is_none());
+    }
+
+    #[test]
+    fn test_raze_before_assemble() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let mut bc = BuildContext {
+            world: &mut world,
+            entity,
+        };
+
+        let view = VirtualList::new(100, RowHeight::Fixed(20.), row);
+        let mut state = view.build(&mut bc);
+        bc.world
+            .get_mut::<VirtualListLayout>(state.content)
+            .unwrap()
+            .visible_height = 100.;
+        view.update(&mut bc, &mut state);
+        assert!(!state.rows.is_empty());
+        view.raze(bc.world, &mut state);
+        assert_eq!(bc.world.entities().len(), 1);
+    }
+}